@@ -0,0 +1,1977 @@
+use std::error::Error;
+
+use crate::filters::{is_search_match, sort_file_list, Filters, SortType};
+use crate::keymap::Keymap;
+use crate::preview::{BinaryPreview, TextPreview};
+use crate::sizes::{format_size, SizeWorker};
+use crate::stats::TrashStats;
+use crate::theme::Theme;
+use crate::watcher::TrashWatcher;
+use crate::{get_trash_roots, get_trashed_files};
+use chrono::{DateTime, Local};
+use libtrash::*;
+use ratatui::layout::Rect;
+use std::collections::{HashMap, HashSet};
+use std::env;
+use std::ffi::OsStr;
+use std::fs::read_dir;
+use std::os::unix::ffi::OsStrExt;
+use std::path::{Path, PathBuf};
+use std::process::{Command, Stdio};
+use std::rc::Rc;
+use std::time::{Duration, Instant};
+
+mod input;
+mod view;
+
+// how many items on each side before scrolling starts
+const FILELIST_SCROLL_VIEW_OFFSET: usize = 3;
+
+// used when $PAGER or $EDITOR are not set
+const DEFAULT_PAGER: &str = "less";
+
+const DEFAULT_EDITOR: &str = "vi";
+
+// editors known to have a read-only mode, and the args for it. trashed files
+// shouldn't be changed before they're restored
+const READ_ONLY_EDITOR_ARGS: &[(&str, &[&str])] = &[
+    ("vi", &["-R"]),
+    ("vim", &["-R"]),
+    ("nvim", &["-R"]),
+    ("view", &[]),
+    ("nano", &["-v"]),
+    ("micro", &["-readonly", "true"]),
+    ("kak", &["-ro"]),
+];
+
+// the longest gap between the clicks of a double click
+const DOUBLE_CLICK_INTERVAL: Duration = Duration::from_millis(500);
+
+// how long a notification is shown for
+pub const NOTIFICATION_TIMEOUT: Duration = Duration::from_secs(5);
+
+pub struct Notification {
+    message: String,
+    is_error: bool,
+    pub shown_at: Instant,
+}
+
+#[derive(Clone, Copy, PartialEq)]
+pub enum Opener {
+    Pager,
+    Editor,
+    Default, // xdg-open
+}
+
+#[derive(Clone, Copy, PartialEq)]
+pub enum BatchAction {
+    Restore,
+    Delete,
+}
+
+// a trash root in the sidebar, counted on refresh
+struct RootSummary {
+    trash_dir: TrashDirectory, // with the mount resolved
+    count: usize,
+    size: Option<u64>, // None while any entry is being calculated
+}
+
+impl RootSummary {
+    // the device, or the trash directory if it's not known
+    fn get_name(&self) -> String {
+        match self.trash_dir.root_type {
+            TrashRootType::Home => "Home trash".to_string(),
+            _ => match self.trash_dir.device.get_dev_name() {
+                Some(v) => v.clone(),
+                None => self.trash_dir.home.display().to_string(),
+            },
+        }
+    }
+
+    fn get_location(&self) -> String {
+        let kind = match self.trash_dir.root_type {
+            TrashRootType::Home => "home",
+            TrashRootType::TopDirAdmin => ".Trash",
+            TrashRootType::TopDirUser => ".Trash-uid",
+        };
+        match self.trash_dir.device.get_mount_point() {
+            Some(v) => format!("{} · {kind}", v.display()),
+            None => kind.to_string(),
+        }
+    }
+}
+
+#[derive(PartialEq)]
+pub enum AppState {
+    RefreshFileList,
+    MainScreen,
+    Search,
+    RestoreConfirmation(usize),
+    RestoreToDialog,
+    DeletionConfirmation(usize),
+    BatchReport(BatchAction),
+    EmptyBinConfirmation(usize),
+    SortListDialog(SortType),
+    FilterListDialog(usize), // the row being changed
+    OpenFile(Opener),
+    Browse,
+    RestoreChildConfirmation(usize),
+    Stats,
+    PurgeConfirmation(usize),
+    ErrorLog,
+    HelpScreen,
+    Exiting,
+}
+
+// a restore made in this session, undone by trashing the file back into the
+// same entry
+#[derive(Clone)]
+struct Restored {
+    trash_file: TrashFile,  // as it was in the trash
+    target: PathBuf,        // where it was restored to
+    child: Option<PathBuf>, // the path inside the trashed directory, if from inside it
+}
+
+impl Restored {
+    fn undo(&self) -> Result<(), Box<dyn Error>> {
+        match &self.child {
+            Some(v) => self.trash_file.retrash_child(v).map(|_| ()),
+            None => self.trash_file.retrash(&self.target).map(|_| ()),
+        }
+    }
+
+    fn redo(&self) -> Result<(), Box<dyn Error>> {
+        match &self.child {
+            Some(v) => self.trash_file.restore_child(v).map(|_| ()),
+            // rename replaces an existing file silently
+            None if self.target.symlink_metadata().is_ok() => Err(Box::<dyn Error>::from(format!(
+                "'{}' exists",
+                self.target.display()
+            ))),
+            None if self.target == self.trash_file.original_file => {
+                self.trash_file.restore().map(|_| ())
+            }
+            None => self.trash_file.restore_to(&self.target).map(|_| ()),
+        }
+    }
+
+    fn get_file_name(&self) -> String {
+        self.target
+            .file_name()
+            .unwrap_or_default()
+            .to_string_lossy()
+            .to_string()
+    }
+}
+
+// a trashed directory being browsed
+struct Browser {
+    trash_file: TrashFile,
+    path: PathBuf,         // relative to the files entry, empty at the top
+    entries: Vec<PathBuf>, // relative to the files entry
+    selected: usize,
+    scroll_offset: usize,
+}
+
+impl Browser {
+    // select an entry, scrolling just enough to show it
+    fn select(&mut self, index: usize, max_visible_items: usize) {
+        if self.entries.is_empty() {
+            return;
+        }
+
+        self.selected = index.min(self.entries.len() - 1);
+        if self.selected < self.scroll_offset {
+            self.scroll_offset = self.selected;
+        } else if max_visible_items > 0 && self.selected >= self.scroll_offset + max_visible_items {
+            self.scroll_offset = self.selected + 1 - max_visible_items;
+        }
+    }
+
+    fn get_selected(&self) -> Option<PathBuf> {
+        self.entries.get(self.selected).cloned()
+    }
+
+    // the path of an entry in the trash
+    fn get_files_entry(&self, relative_path: &Path) -> PathBuf {
+        self.trash_file
+            .files_entry
+            .as_ref()
+            .unwrap()
+            .join(relative_path)
+    }
+}
+
+pub struct App {
+    pub state: AppState,
+    all_files: Vec<TrashFile>,
+    trashed_files: Vec<TrashFile>, // files matching the search
+    search: String,
+    pub filters: Filters,
+    filters_draft: Filters, // changed in the filter dialog until applied
+    selected: usize,
+    marked: HashSet<PathBuf>,     // files entries of the marked files
+    visual_anchor: Option<usize>, // where the range being marked starts
+    report: Vec<(String, Result<String, String>)>, // file name, outcome
+    report_scroll: usize,
+    undo_stack: Vec<Vec<Restored>>, // a step for each confirmed restore
+    redo_stack: Vec<Vec<Restored>>, // undone steps, until the next restore
+    pub notification: Option<Notification>,
+    error_log: Vec<(String, String)>, // time, message
+    error_log_scroll: usize,
+    pub sort_type: SortType,
+    pub sort_reversed: bool,
+    scroll_offset: usize,
+    max_visible_items: usize,
+    browser: Option<Browser>,
+    preview_entry: Option<PathBuf>, // the files entry the preview scroll is for
+    preview_scroll: usize,
+    preview_focused: bool,
+    text_preview: Option<TextPreview>,
+    binary_preview: Option<BinaryPreview>,
+    // areas from the last render, for the mouse
+    list_area: Rect,
+    preview_area: Rect,
+    click_targets: Vec<(Rect, usize)>, // button or option of the open dialog
+    last_click: Option<(usize, Instant)>, // file list index
+    help_scroll: usize,
+    roots: Vec<RootSummary>,
+    roots_shown: bool,
+    roots_focused: bool,
+    roots_selected: usize, // 0 is all the roots, then the roots in order
+    roots_area: Rect,
+    roots_scroll: usize,
+    empty_root: Option<PathBuf>, // home of the root being emptied, None for all
+    stats: Option<TrashStats>,
+    stats_scroll: usize,
+    stats_by_week: bool,
+    purge_days: i64,                      // of the purge being confirmed
+    sizes: HashMap<PathBuf, Option<u64>>, // None if it can't be read
+    sizes_pending: HashSet<PathBuf>,
+    size_worker: SizeWorker,
+    pub watcher: Option<TrashWatcher>,
+    pending_changes: Vec<PathBuf>, // picked up while a dialog was open
+    pending_refresh: bool,
+    restore_to_input: String,
+    restore_to_completions: Vec<String>, // of the last tab, if more than one
+    keymap: Rc<Keymap>,
+    theme: Theme,
+    now: fn() -> DateTime<Local>,    // what ages are worked out from
+    var: fn(&str) -> Option<String>, // what the openers are picked from
+}
+
+impl App {
+    pub fn new(theme: Theme, keymap: Keymap) -> Self {
+        Self {
+            state: AppState::RefreshFileList,
+            all_files: vec![],
+            trashed_files: vec![],
+            search: String::new(),
+            filters: Filters::default(),
+            filters_draft: Filters::default(),
+            selected: 0,
+            marked: HashSet::new(),
+            visual_anchor: None,
+            report: vec![],
+            report_scroll: 0,
+            undo_stack: vec![],
+            redo_stack: vec![],
+            notification: None,
+            error_log: vec![],
+            error_log_scroll: 0,
+            sort_type: SortType::DeletionDate,
+            sort_reversed: false,
+            scroll_offset: 0,
+            max_visible_items: 0,
+            browser: None,
+            preview_entry: None,
+            preview_scroll: 0,
+            preview_focused: false,
+            text_preview: None,
+            binary_preview: None,
+            list_area: Rect::default(),
+            preview_area: Rect::default(),
+            click_targets: vec![],
+            last_click: None,
+            help_scroll: 0,
+            roots: vec![],
+            roots_shown: false,
+            roots_focused: false,
+            roots_selected: 0,
+            roots_area: Rect::default(),
+            roots_scroll: 0,
+            empty_root: None,
+            stats: None,
+            stats_scroll: 0,
+            stats_by_week: false,
+            purge_days: 0,
+            sizes: HashMap::new(),
+            sizes_pending: HashSet::new(),
+            size_worker: SizeWorker::new(),
+            watcher: None,
+            pending_changes: vec![],
+            pending_refresh: false,
+            restore_to_input: String::new(),
+            restore_to_completions: vec![],
+            keymap: Rc::new(keymap),
+            theme,
+            now: Local::now,
+            var: |v| env::var(v).ok(),
+        }
+    }
+
+    // list a directory inside a trashed directory, directories first
+    fn browse(&mut self, trash_file: TrashFile, path: PathBuf) -> Result<(), Box<dyn Error>> {
+        let files_entry = match &trash_file.files_entry {
+            Some(v) => v.join(&path),
+            None => return Err(Box::<dyn Error>::from("trash entries are uninitialised")),
+        };
+
+        let mut entries = vec![];
+        for child in read_dir(&files_entry)? {
+            let name = child?.file_name();
+            entries.push(path.join(name));
+        }
+        let is_dir = |v: &PathBuf| {
+            let child = files_entry.join(v.file_name().unwrap_or_default());
+            !child.is_symlink() && child.is_dir()
+        };
+        entries.sort_by(|a, b| {
+            is_dir(b).cmp(&is_dir(a)).then_with(|| {
+                a.to_string_lossy()
+                    .to_lowercase()
+                    .cmp(&b.to_string_lossy().to_lowercase())
+            })
+        });
+
+        self.browser = Some(Browser {
+            trash_file,
+            path,
+            entries,
+            selected: 0,
+            scroll_offset: 0,
+        });
+        Ok(())
+    }
+
+    fn browse_selected(&mut self) {
+        let Some(browser) = &self.browser else {
+            return;
+        };
+        let Some(entry) = browser.get_selected() else {
+            return;
+        };
+        let files_entry = browser.get_files_entry(&entry);
+        if files_entry.is_symlink() || !files_entry.is_dir() {
+            return;
+        }
+
+        let trash_file = browser.trash_file.clone();
+        if let Err(e) = self.browse(trash_file, entry) {
+            self.notify_error(format!("cannot browse: {e}"));
+        }
+    }
+
+    // where the restore to dialog restores the selected file, and whether it
+    // has to be copied there. a directory that exists gets the file in it
+    fn get_restore_target(&self) -> Result<(PathBuf, bool), String> {
+        let Some(trash_file) = self.trashed_files.get(self.selected) else {
+            return Err("no file selected".to_string());
+        };
+        let input = self.restore_to_input.trim();
+        if input.is_empty() {
+            return Err("enter a path".to_string());
+        }
+
+        let mut target = expand_path(input);
+        if !target.is_symlink() && target.is_dir() {
+            target = target.join(trash_file.original_file.file_name().unwrap_or_default());
+        }
+        if target.symlink_metadata().is_ok() {
+            return Err(format!("'{}' exists", target.display()));
+        }
+        let Some(parent) = target.parent() else {
+            return Err("cannot restore to the root directory".to_string());
+        };
+        if parent.symlink_metadata().is_err() {
+            return Err(format!("'{}' doesn't exist", parent.display()));
+        }
+        if !parent.is_dir() {
+            return Err(format!("'{}' is not a directory", parent.display()));
+        }
+
+        let files_entry = trash_file.files_entry.clone().unwrap_or_default();
+        let copy = match (
+            DeviceNumber::for_path(parent),
+            DeviceNumber::for_path(&files_entry),
+        ) {
+            (Ok(a), Ok(b)) => a.dev_id != b.dev_id,
+            _ => false,
+        };
+        Ok((target, copy))
+    }
+
+    // restore the selected file to the path of the restore to dialog, false if
+    // the path isn't valid
+    fn restore_selected_to(&mut self) -> bool {
+        let target = match self.get_restore_target() {
+            Ok((v, _)) => v,
+            Err(e) => {
+                self.notify_error(e);
+                return false;
+            }
+        };
+        let trash_file = self.trashed_files[self.selected].clone();
+        let file_name = trash_file
+            .original_file
+            .file_name()
+            .unwrap_or_default()
+            .to_string_lossy()
+            .to_string();
+
+        match trash_file.restore_to(&target) {
+            Ok(v) => {
+                self.notify(format!("{file_name} restored to '{}'", v.display()), false);
+                self.push_undo(vec![Restored {
+                    trash_file,
+                    target: v,
+                    child: None,
+                }]);
+            }
+            Err(e) => self.notify_error(format!("cannot restore '{file_name}': {e}")),
+        }
+        true
+    }
+
+    // the directory stays in the trash, with the list of what's left in it
+    // refreshed
+    fn restore_selected_child(&mut self) {
+        let Some(browser) = &self.browser else {
+            return;
+        };
+        let Some(entry) = browser.get_selected() else {
+            return;
+        };
+
+        let selected = browser.selected;
+        let trash_file = browser.trash_file.clone();
+        let path = browser.path.clone();
+        match trash_file.restore_child(&entry) {
+            Ok(target) => {
+                self.notify(
+                    format!("{} restored to '{}'", entry.display(), target.display()),
+                    false,
+                );
+                self.push_undo(vec![Restored {
+                    trash_file: trash_file.clone(),
+                    target,
+                    child: Some(entry),
+                }]);
+            }
+            Err(e) => self.notify_error(format!("cannot restore '{}': {e}", entry.display())),
+        }
+
+        if let Err(e) = self.browse(trash_file, path) {
+            self.notify_error(format!("cannot browse: {e}"));
+        } else if let Some(browser) = self.browser.as_mut() {
+            browser.select(selected, self.max_visible_items);
+        }
+    }
+
+    // what's wrong goes to the error log, one line each
+    fn check_root(&mut self, index: usize) {
+        let trash_dir = self.roots[index].trash_dir.clone();
+        let check = match trash_dir.check() {
+            Ok(v) => v,
+            Err(e) => {
+                self.notify_error(format!("cannot check '{}': {e}", trash_dir.home.display()));
+                return;
+            }
+        };
+        if let Err(e) = trash_dir.repair(&check) {
+            self.notify_error(format!(
+                "cannot clean up '{}': {e}",
+                trash_dir.home.display()
+            ));
+            return;
+        }
+
+        for v in &check.orphaned_files {
+            self.log_error(format!("'{}' has no trashinfo, left as is", v.display()));
+        }
+        for v in &check.orphaned_trashinfos {
+            self.log_error(format!("'{}' had no files entry, removed", v.display()));
+        }
+        for v in &check.invalid_trashinfos {
+            self.log_error(format!("'{}' can't be read, left as is", v.display()));
+        }
+
+        let home = trash_dir.home.display();
+        if check.is_ok() {
+            self.notify(format!("'{home}' has no broken entries"), false);
+        } else if check.orphaned_files.is_empty() && check.invalid_trashinfos.is_empty() {
+            let count = check.orphaned_trashinfos.len();
+            self.notify(
+                format!("removed {count} trashinfo files of deleted entries in '{home}'"),
+                false,
+            );
+        } else {
+            let count = check.orphaned_files.len() + check.invalid_trashinfos.len();
+            self.notify(
+                format!("'{home}' has {count} broken entries, see the error log"),
+                true,
+            );
+        }
+    }
+
+    fn notify(&mut self, message: String, is_error: bool) {
+        self.notification = Some(Notification {
+            message,
+            is_error,
+            shown_at: Instant::now(),
+        });
+    }
+
+    // errors are kept in the error log too
+    pub fn notify_error(&mut self, message: String) {
+        self.log_error(message.clone());
+        self.notify(message, true);
+    }
+
+    pub fn log_error(&mut self, message: String) {
+        let time = (self.now)().format("%H:%M:%S").to_string();
+        self.error_log.push((time, message));
+    }
+
+    pub fn expire_notification(&mut self) {
+        if let Some(notification) = &self.notification {
+            if notification.shown_at.elapsed() >= NOTIFICATION_TIMEOUT {
+                self.notification = None;
+            }
+        }
+    }
+
+    fn sort_files(&mut self) {
+        sort_file_list(&mut self.all_files, &self.sort_type, &self.sizes);
+        if self.sort_reversed {
+            self.all_files.reverse();
+        }
+    }
+
+    pub fn refresh(&mut self) {
+        // indexes don't survive a refresh, the files entries do
+        self.end_visual_selection();
+        let (trash_roots, mut errors) = match get_trash_roots() {
+            Ok(v) => v,
+            Err(e) => {
+                self.notify_error(format!("cannot list trashed files: {e}"));
+                return;
+            }
+        };
+        let (files, file_errors) = get_trashed_files(&trash_roots);
+        errors.extend(file_errors);
+
+        // the same broken entries show up on every refresh, only log them
+        // once
+        let new_errors: Vec<String> = errors
+            .into_iter()
+            .filter(|e| !self.error_log.iter().any(|(_, v)| v == e))
+            .collect();
+        if !new_errors.is_empty() {
+            let count = new_errors.len();
+            for e in new_errors {
+                self.log_error(e);
+            }
+            self.notify(format!("{count} trash entries couldn't be read"), true);
+        }
+
+        self.all_files = files;
+
+        // sizes are worked out again, the ones known are shown until then
+        let files_entries: HashSet<PathBuf> = self
+            .all_files
+            .iter()
+            .filter_map(|f| f.files_entry.clone())
+            .collect();
+        self.sizes.retain(|k, _| files_entries.contains(k));
+        for files_entry in &files_entries {
+            self.request_size(files_entry);
+        }
+
+        self.sort_files();
+        self.roots = trash_roots
+            .into_iter()
+            .map(|mut trash_dir| {
+                // the device name and the mount point for the sidebar
+                let _ = trash_dir.device.resolve_mount();
+                RootSummary {
+                    trash_dir,
+                    count: 0,
+                    size: None,
+                }
+            })
+            .collect();
+        for i in 0..self.roots.len() {
+            self.count_root(i);
+        }
+
+        // what happens to the trash after this shows up as it happens
+        self.pending_changes.clear();
+        self.pending_refresh = false;
+        if let Some(watcher) = &mut self.watcher {
+            for root in &self.roots {
+                let _ = watcher.watch(&root.trash_dir.files);
+                let _ = watcher.watch(&root.trash_dir.info);
+            }
+        }
+        self.marked.retain(|v| {
+            self.all_files
+                .iter()
+                .any(|f| f.files_entry.as_ref() == Some(v))
+        });
+        self.apply_filters();
+    }
+
+    // directories are sized on the worker threads, anything else is quick
+    // to size here
+    fn request_size(&mut self, path: &Path) {
+        if self.sizes_pending.contains(path) {
+            return;
+        }
+
+        match path.symlink_metadata() {
+            Ok(v) if v.is_dir() => {
+                if self.size_worker.requests.send(path.to_path_buf()).is_ok() {
+                    self.sizes_pending.insert(path.to_path_buf());
+                }
+            }
+            Ok(v) => {
+                self.sizes.insert(path.to_path_buf(), Some(v.len()));
+            }
+            Err(_) => {
+                self.sizes.insert(path.to_path_buf(), None);
+            }
+        }
+    }
+
+    // None until it's known, or if it can't be
+    fn get_size(&self, trash_file: &TrashFile) -> Option<u64> {
+        trash_file
+            .files_entry
+            .as_ref()
+            .and_then(|v| self.sizes.get(v).copied().flatten())
+    }
+
+    // the sizes of the files added up, None while any of them is still being
+    // calculated. sizes that can't be known count as nothing
+    fn get_total_size<'a>(&self, files: impl IntoIterator<Item = &'a TrashFile>) -> Option<u64> {
+        files
+            .into_iter()
+            .map(|f| match &f.files_entry {
+                Some(v) => self.sizes.get(v).map(|v| v.unwrap_or(0)),
+                None => Some(0),
+            })
+            .sum()
+    }
+
+    fn get_size_display(&self, path: &Path) -> String {
+        match self.sizes.get(path) {
+            Some(Some(v)) => format_size(*v),
+            Some(None) => "unknown".to_string(),
+            None => "calculating…".to_string(),
+        }
+    }
+
+    // take the sizes the worker threads are done with, and redo what depends
+    // on them. true if any came in
+    pub fn receive_sizes(&mut self) -> bool {
+        let mut received = false;
+        while let Ok((path, size)) = self.size_worker.results.try_recv() {
+            self.sizes_pending.remove(&path);
+            self.sizes.insert(path, size);
+            received = true;
+        }
+        if !received {
+            return false;
+        }
+
+        for i in 0..self.roots.len() {
+            self.count_root(i);
+        }
+        if self.stats.is_some() {
+            self.stats = Some(TrashStats::from(&self.all_files, &self.sizes));
+        }
+        // a dialog works on the list as it was when it opened
+        if matches!(self.state, AppState::MainScreen | AppState::Search) {
+            if self.sort_type == SortType::Size {
+                self.end_visual_selection();
+                self.sort_files();
+            }
+            self.apply_filters();
+        }
+        true
+    }
+
+    fn count_root(&mut self, index: usize) {
+        let home = &self.roots[index].trash_dir.home;
+        let files: Vec<&TrashFile> = self
+            .all_files
+            .iter()
+            .filter(|f| f.trashroot.home == *home)
+            .collect();
+        let count = files.len();
+        let size = self.get_total_size(files.iter().copied());
+        self.roots[index].count = count;
+        self.roots[index].size = size;
+    }
+
+    // pick up what other programs did to the trash, rereading only the
+    // entries changed. a dialog works on the list as it was when it opened,
+    // so the changes wait for the main screen. true if the list changed
+    pub fn apply_trash_changes(&mut self) -> bool {
+        let Some(watcher) = &mut self.watcher else {
+            return false;
+        };
+        let (changes, overflowed) = watcher.read_changes();
+        self.pending_changes.extend(changes);
+        self.pending_refresh |= overflowed;
+        if !matches!(self.state, AppState::MainScreen | AppState::Search) {
+            return false;
+        }
+        if self.pending_refresh {
+            self.state = AppState::RefreshFileList;
+            return true;
+        }
+        if self.pending_changes.is_empty() {
+            return false;
+        }
+
+        // the files entries of the changed trashinfo files and files entries
+        let mut changed: Vec<(usize, PathBuf)> = vec![];
+        for path in std::mem::take(&mut self.pending_changes) {
+            let (Some(dir), Some(name)) = (path.parent(), path.file_name()) else {
+                continue;
+            };
+            for (i, root) in self.roots.iter().enumerate() {
+                let name = if dir == root.trash_dir.files {
+                    name.to_os_string()
+                } else if dir == root.trash_dir.info {
+                    match name.as_bytes().strip_suffix(b".trashinfo") {
+                        Some(v) => OsStr::from_bytes(v).to_os_string(),
+                        None => continue,
+                    }
+                } else {
+                    continue;
+                };
+                let files_entry = root.trash_dir.files.join(name);
+                if !changed.iter().any(|(_, v)| *v == files_entry) {
+                    changed.push((i, files_entry));
+                }
+            }
+        }
+        if changed.is_empty() {
+            return false;
+        }
+
+        // indexes don't survive the change, the files entries do
+        self.end_visual_selection();
+        for (i, files_entry) in &changed {
+            self.all_files
+                .retain(|f| f.files_entry.as_ref() != Some(files_entry));
+            self.sizes.remove(files_entry);
+            // half written entries show up with the next change
+            if files_entry.symlink_metadata().is_ok() {
+                if let Ok(v) = TrashFile::from(files_entry.clone(), &self.roots[*i].trash_dir) {
+                    self.all_files.push(v);
+                    self.request_size(files_entry);
+                }
+            }
+        }
+
+        self.sort_files();
+        let mut roots: Vec<usize> = changed.iter().map(|(i, _)| *i).collect();
+        roots.dedup();
+        for i in roots {
+            self.count_root(i);
+        }
+        self.marked.retain(|v| {
+            self.all_files
+                .iter()
+                .any(|f| f.files_entry.as_ref() == Some(v))
+        });
+        self.apply_filters();
+        true
+    }
+
+    // delete everything, not just what's shown, or everything in the root
+    // being emptied
+    fn empty_bin(&mut self) {
+        match self.empty_root.clone() {
+            Some(home) => self.delete_forever_where(|f| f.trashroot.home == home),
+            None => self.delete_forever_where(|_| true),
+        }
+    }
+
+    // delete the files matching, of all the files. one error doesn't stop the
+    // rest
+    fn delete_forever_where(&mut self, matches: impl Fn(&TrashFile) -> bool) {
+        let mut deleted = 0;
+        let mut errors = vec![];
+        for trash_file in self.all_files.iter().filter(|f| matches(f)) {
+            match trash_file.delete_forever() {
+                Ok(_) => deleted += 1,
+                Err(e) => errors.push(format!(
+                    "cannot delete '{}': {e}",
+                    trash_file.original_file.display()
+                )),
+            }
+        }
+
+        if errors.is_empty() {
+            self.notify(format!("deleted {deleted} files forever"), false);
+        } else {
+            let count = errors.len();
+            for e in errors {
+                self.log_error(e);
+            }
+            self.notify(format!("{count} files couldn't be deleted"), true);
+        }
+    }
+
+    // open the files entry of the selected file, waits until the pager or
+    // the editor exits
+    pub fn open_selected(&self, opener: Opener) -> Result<(), Box<dyn Error>> {
+        let files_entry = match self
+            .trashed_files
+            .get(self.selected)
+            .and_then(|f| f.files_entry.as_ref())
+        {
+            Some(v) => v,
+            None => return Err(Box::<dyn Error>::from("no file selected")),
+        };
+
+        let mut command = match opener {
+            Opener::Pager => {
+                if files_entry.is_dir() {
+                    return Err(Box::<dyn Error>::from(
+                        "a directory can't be opened in a pager, try e or x",
+                    ));
+                }
+                get_command((self.var)("PAGER"), DEFAULT_PAGER)
+            }
+            Opener::Editor => {
+                let mut command = get_command((self.var)("EDITOR"), DEFAULT_EDITOR);
+                let program = Path::new(command.get_program())
+                    .file_name()
+                    .unwrap_or_default()
+                    .to_string_lossy()
+                    .to_string();
+                match READ_ONLY_EDITOR_ARGS.iter().find(|(v, _)| *v == program) {
+                    Some((_, args)) => {
+                        command.args(*args);
+                    }
+                    None => {
+                        return Err(Box::<dyn Error>::from(format!(
+                            "don't know how to open files read-only in '{program}', try o"
+                        )));
+                    }
+                }
+                command
+            }
+            Opener::Default => {
+                if (self.var)("DISPLAY").is_none() && (self.var)("WAYLAND_DISPLAY").is_none() {
+                    return Err(Box::<dyn Error>::from(
+                        "no display to open the file on, try o or e",
+                    ));
+                }
+
+                // the application outlives the TUI, so don't wait for it
+                Command::new("xdg-open")
+                    .arg(files_entry)
+                    .stdin(Stdio::null())
+                    .stdout(Stdio::null())
+                    .stderr(Stdio::null())
+                    .spawn()
+                    .map_err(|e| format!("cannot run xdg-open: {e}"))?;
+                return Ok(());
+            }
+        };
+
+        let program = command.get_program().to_string_lossy().to_string();
+        let status = command
+            .arg(files_entry)
+            .status()
+            .map_err(|e| format!("cannot run '{program}': {e}"))?;
+        if !status.success() {
+            return Err(Box::<dyn Error>::from(format!("'{program}' {status}")));
+        }
+
+        Ok(())
+    }
+
+    // top directories of the mounts with trashed files, for the filter dialog
+    fn get_top_dirs(&self) -> Vec<PathBuf> {
+        let mut top_dirs: Vec<PathBuf> = self
+            .all_files
+            .iter()
+            .filter_map(|f| f.trashroot.get_top_dir())
+            .collect();
+        top_dirs.sort();
+        top_dirs.dedup();
+        top_dirs
+    }
+
+    // show only the files matching the search and the filters, keeping the
+    // selected file selected if it's still there
+    fn apply_filters(&mut self) {
+        let selected_entry = self
+            .trashed_files
+            .get(self.selected)
+            .and_then(|f| f.files_entry.clone());
+        self.trashed_files = self
+            .all_files
+            .iter()
+            .filter(|f| is_search_match(&self.search, f))
+            .filter(|f| self.filters.matches(f, self.get_size(f)))
+            .cloned()
+            .collect();
+        self.selected = selected_entry
+            .and_then(|v| {
+                self.trashed_files
+                    .iter()
+                    .position(|f| f.files_entry.as_ref() == Some(&v))
+            })
+            .unwrap_or(
+                self.selected
+                    .min(self.trashed_files.len().saturating_sub(1)),
+            );
+        self.scroll_to_selected();
+    }
+
+    fn is_marked(&self, index: usize) -> bool {
+        if let Some(anchor) = self.visual_anchor {
+            if (anchor.min(self.selected)..=anchor.max(self.selected)).contains(&index) {
+                return true;
+            }
+        }
+
+        match &self.trashed_files[index].files_entry {
+            Some(v) => self.marked.contains(v),
+            None => false,
+        }
+    }
+
+    fn get_marked_count(&self) -> usize {
+        (0..self.trashed_files.len())
+            .filter(|i| self.is_marked(*i))
+            .count()
+    }
+
+    // the files an action applies to, the marked files if there are any,
+    // otherwise the selected file
+    fn get_selection(&self) -> Vec<usize> {
+        let marked: Vec<usize> = (0..self.trashed_files.len())
+            .filter(|i| self.is_marked(*i))
+            .collect();
+        if marked.is_empty() && self.selected < self.trashed_files.len() {
+            vec![self.selected]
+        } else {
+            marked
+        }
+    }
+
+    fn get_selection_size(&self, selection: &[usize]) -> String {
+        match self.get_total_size(selection.iter().map(|i| &self.trashed_files[*i])) {
+            Some(v) => format_size(v),
+            None => "calculating…".to_string(),
+        }
+    }
+
+    // mark the files in the range being marked
+    fn end_visual_selection(&mut self) {
+        if let Some(anchor) = self.visual_anchor.take() {
+            for i in anchor.min(self.selected)..=anchor.max(self.selected) {
+                if let Some(v) = self
+                    .trashed_files
+                    .get(i)
+                    .and_then(|f| f.files_entry.clone())
+                {
+                    self.marked.insert(v);
+                }
+            }
+        }
+    }
+
+    // run the action on each selected file, one failure doesn't stop the
+    // rest. the outcomes are shown when there's more than one file, or
+    // something failed
+    fn run_batch(&mut self, action: BatchAction) {
+        self.end_visual_selection();
+        let selection = self.get_selection();
+        let mut restored = vec![];
+        self.report = selection
+            .iter()
+            .map(|i| {
+                let trash_file = &self.trashed_files[*i];
+                let file_name = trash_file
+                    .original_file
+                    .file_name()
+                    .unwrap_or_default()
+                    .to_string_lossy()
+                    .to_string();
+                let result = match action {
+                    // rename replaces an existing file silently
+                    BatchAction::Restore if trash_file.original_file.symlink_metadata().is_ok() => {
+                        Err(Box::<dyn Error>::from(format!(
+                            "'{}' exists",
+                            trash_file.original_file.display()
+                        )))
+                    }
+                    BatchAction::Restore => trash_file.restore().map(|v| {
+                        restored.push(Restored {
+                            trash_file: trash_file.clone(),
+                            target: v.clone(),
+                            child: None,
+                        });
+                        format!("restored to '{}'", v.display())
+                    }),
+                    BatchAction::Delete => trash_file
+                        .delete_forever()
+                        .map(|_| "deleted forever".to_string()),
+                };
+                (file_name, result.map_err(|e| e.to_string()))
+            })
+            .collect();
+        self.report_scroll = 0;
+        self.marked.clear();
+        self.push_undo(restored);
+
+        let errors: Vec<String> = self
+            .report
+            .iter()
+            .filter_map(|(file_name, result)| match (action, result) {
+                (BatchAction::Restore, Err(e)) => {
+                    Some(format!("cannot restore '{file_name}': {e}"))
+                }
+                (BatchAction::Delete, Err(e)) => Some(format!("cannot delete '{file_name}': {e}")),
+                (_, Ok(_)) => None,
+            })
+            .collect();
+        for e in &errors {
+            self.log_error(e.clone());
+        }
+
+        if self.report.len() > 1 || !errors.is_empty() {
+            self.state = AppState::BatchReport(action);
+        } else {
+            if let Some((file_name, Ok(outcome))) = self.report.first() {
+                self.notify(format!("{file_name} {outcome}"), false);
+            }
+            self.state = AppState::RefreshFileList;
+        }
+    }
+
+    // a new restore step, which can't be redone past
+    fn push_undo(&mut self, step: Vec<Restored>) {
+        if !step.is_empty() {
+            self.undo_stack.push(step);
+            self.redo_stack.clear();
+        }
+    }
+
+    // undo the last restore step, or redo the last undone one. what can't be
+    // undone or redone stays in its step, to try again
+    fn undo_restore(&mut self, redo: bool) {
+        let (step, verb) = if redo {
+            (self.redo_stack.pop(), "redo")
+        } else {
+            (self.undo_stack.pop(), "undo")
+        };
+        let Some(step) = step else {
+            self.notify(format!("nothing to {verb}"), false);
+            return;
+        };
+
+        let mut done = vec![];
+        let mut failed = vec![];
+        let mut errors = vec![];
+        for restored in step {
+            let result = if redo {
+                restored.redo()
+            } else {
+                restored.undo()
+            };
+            match result {
+                Ok(_) => done.push(restored),
+                Err(e) => {
+                    errors.push(format!(
+                        "cannot {verb} the restore of '{}': {e}",
+                        restored.get_file_name()
+                    ));
+                    failed.push(restored);
+                }
+            }
+        }
+
+        let message = match done.as_slice() {
+            [] => None,
+            [v] if redo => Some(format!(
+                "{} restored to '{}' again",
+                v.get_file_name(),
+                v.target.display()
+            )),
+            [v] => Some(format!("{} is back in the trash", v.get_file_name())),
+            v if redo => Some(format!("{} restores redone", v.len())),
+            v => Some(format!("{} restores undone, back in the trash", v.len())),
+        };
+        let (from, to) = if redo {
+            (&mut self.redo_stack, &mut self.undo_stack)
+        } else {
+            (&mut self.undo_stack, &mut self.redo_stack)
+        };
+        if !done.is_empty() {
+            to.push(done);
+        }
+        if !failed.is_empty() {
+            from.push(failed);
+        }
+
+        match (errors.len(), message) {
+            (0, Some(v)) => self.notify(v, false),
+            (0, None) => {}
+            (1, _) => self.notify_error(errors.remove(0)),
+            (n, _) => {
+                for e in errors {
+                    self.log_error(e);
+                }
+                self.notify(format!("cannot {verb} {n} of the restores"), true);
+            }
+        }
+    }
+}
+
+// a command from an env var that can have args, like PAGER="less -R"
+fn get_command(value: Option<String>, default: &str) -> Command {
+    let value = value.unwrap_or_default();
+    let mut words = value.split_whitespace();
+    let mut command = match words.next() {
+        Some(v) => Command::new(v),
+        None => Command::new(default),
+    };
+    command.args(words);
+    command
+}
+
+// a path as typed, with ~ for the home directory, and relative to the current
+// directory
+fn expand_path(input: &str) -> PathBuf {
+    let path = match input.strip_prefix('~') {
+        Some(rest) if rest.is_empty() || rest.starts_with('/') => match get_home_dir() {
+            Ok(home) => home.join(rest.trim_start_matches('/')),
+            Err(_) => PathBuf::from(input),
+        },
+        _ => PathBuf::from(input),
+    };
+
+    if path.is_absolute() {
+        path
+    } else {
+        env::current_dir().unwrap_or_default().join(path)
+    }
+}
+
+// complete the last part of a path being typed, as far as the entries it
+// matches agree. the matches are returned if there's more than one
+fn complete_path(input: &str) -> (String, Vec<String>) {
+    let (dir, prefix) = match input.rfind('/') {
+        Some(i) => input.split_at(i + 1),
+        None => ("", input),
+    };
+    let Ok(entries) = read_dir(expand_path(if dir.is_empty() { "." } else { dir })) else {
+        return (input.to_string(), vec![]);
+    };
+
+    // hidden entries only when asked for
+    let mut matches: Vec<String> = entries
+        .flatten()
+        .filter_map(|entry| {
+            let name = entry.file_name().to_string_lossy().to_string();
+            if !name.starts_with(prefix) || (name.starts_with('.') && !prefix.starts_with('.')) {
+                return None;
+            }
+            if entry.path().is_dir() {
+                Some(format!("{name}/"))
+            } else {
+                Some(name)
+            }
+        })
+        .collect();
+    matches.sort();
+
+    match matches.len() {
+        0 => (input.to_string(), vec![]),
+        1 => (format!("{dir}{}", matches[0]), vec![]),
+        _ => {
+            let mut common = matches[0].clone();
+            for v in &matches[1..] {
+                let len = common
+                    .char_indices()
+                    .zip(v.chars())
+                    .take_while(|((_, a), b)| a == b)
+                    .last()
+                    .map(|((i, a), _)| i + a.len_utf8())
+                    .unwrap_or(0);
+                common.truncate(len);
+            }
+            (format!("{dir}{common}"), matches)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::filters::RootFilter;
+    use crate::views::LAYOUT_ROOT_HEIGHT;
+    use chrono::TimeZone;
+    use crossterm::event::KeyModifiers;
+    use crossterm::event::{KeyCode, KeyEvent, MouseButton, MouseEvent, MouseEventKind};
+    use ratatui::backend::TestBackend;
+    use ratatui::text::Span;
+    use ratatui::Terminal;
+    use std::fs::{self, File};
+
+    // an app showing a trash with a text file, a directory, and a log file
+    // deleted days apart, at a fixed time. it's removed with the directory
+    // returned
+    fn get_test_app(name: &str) -> (App, PathBuf) {
+        let test_dir = env::temp_dir()
+            .join("trash-rs")
+            .join(format!("{name}-{}", std::process::id()));
+        let trash_dir = TrashDirectory {
+            device: Device::for_path(&env::temp_dir()).unwrap(),
+            home: test_dir.join("Trash"),
+            files: test_dir.join("Trash").join("files"),
+            info: test_dir.join("Trash").join("info"),
+            root_type: TrashRootType::Home,
+        };
+        let _ = fs::remove_dir_all(&test_dir);
+        let _ = fs::create_dir_all(trash_dir.files.join("photos"));
+        let _ = fs::create_dir_all(&trash_dir.info);
+        let entries = [
+            ("notes.txt", "2024-03-09T12:00:00"),
+            ("photos", "2024-03-05T08:30:00"),
+            ("old.log", "2024-01-01T00:00:00"),
+        ];
+        let _ = fs::write(trash_dir.files.join("notes.txt"), "remember the milk\n");
+        let _ = fs::write(trash_dir.files.join("photos").join("beach.txt"), "sand\n");
+        let _ = fs::write(trash_dir.files.join("old.log"), "started\nstopped\n");
+        for (name, date) in entries {
+            let _ = fs::write(
+                trash_dir.info.join(format!("{name}.trashinfo")),
+                format!("[Trash Info]\nPath=/data/{name}\nDeletionDate={date}\n"),
+            );
+        }
+
+        let mut app = App::new(Theme::no_color(), Keymap::default());
+        app.now = || Local.with_ymd_and_hms(2024, 3, 10, 12, 0, 0).unwrap();
+        for (name, _) in entries {
+            let trash_file = TrashFile::from(trash_dir.files.join(name), &trash_dir).unwrap();
+            app.request_size(trash_file.files_entry.as_ref().unwrap());
+            app.all_files.push(trash_file);
+        }
+        sort_file_list(&mut app.all_files, &app.sort_type, &app.sizes);
+        app.apply_filters();
+        app.state = AppState::MainScreen;
+        (app, test_dir)
+    }
+
+    // the screen against its snapshot in src/bin/restore/snapshots, which is written
+    // instead with UPDATE_SNAPSHOTS set
+    fn assert_snapshot(name: &str, app: &mut App) {
+        let mut terminal = Terminal::new(TestBackend::new(100, 60)).unwrap();
+        app.draw(&mut terminal).unwrap();
+        let buffer = terminal.backend().buffer();
+
+        // without the cells hidden by wide characters, or trailing spaces
+        let mut screen = String::new();
+        for y in 0..buffer.area.height {
+            let mut line = String::new();
+            let mut hidden = 0;
+            for x in 0..buffer.area.width {
+                let symbol = buffer[(x, y)].symbol();
+                if hidden == 0 {
+                    line.push_str(symbol);
+                }
+                hidden = hidden.max(Span::raw(symbol).width()).saturating_sub(1);
+            }
+            screen.push_str(line.trim_end());
+            screen.push('\n');
+        }
+
+        let path = Path::new(env!("CARGO_MANIFEST_DIR"))
+            .join("src")
+            .join("bin")
+            .join("restore")
+            .join("snapshots")
+            .join(format!("{name}.txt"));
+        if env::var_os("UPDATE_SNAPSHOTS").is_some() {
+            fs::create_dir_all(path.parent().unwrap()).unwrap();
+            fs::write(&path, &screen).unwrap();
+            return;
+        }
+        let snapshot = fs::read_to_string(&path).unwrap_or_default();
+        assert!(
+            screen == snapshot,
+            "{name} doesn't match {}:\n{screen}",
+            path.display()
+        );
+    }
+
+    fn press(app: &mut App, code: KeyCode) {
+        app.handle_input(KeyEvent::new(code, KeyModifiers::NONE));
+    }
+
+    fn click(app: &mut App, kind: MouseEventKind, column: u16, row: u16) {
+        app.handle_mouse(MouseEvent {
+            kind,
+            column,
+            row,
+            modifiers: KeyModifiers::NONE,
+        });
+    }
+
+    // the areas the mouse is matched against are laid out when drawing
+    fn draw(app: &mut App) {
+        let mut terminal = Terminal::new(TestBackend::new(100, 60)).unwrap();
+        app.draw(&mut terminal).unwrap();
+    }
+
+    #[test]
+    fn test_render_main_screen() {
+        let (mut app, test_dir) = get_test_app("render-main");
+        assert_snapshot("main_screen", &mut app);
+
+        press(&mut app, KeyCode::Char('j'));
+        press(&mut app, KeyCode::Char('j'));
+        press(&mut app, KeyCode::Char(' '));
+        app.sort_type = SortType::Size;
+        assert_snapshot("main_screen_marked", &mut app);
+
+        app.marked.clear();
+        press(&mut app, KeyCode::Char('/'));
+        press(&mut app, KeyCode::Char('o'));
+        press(&mut app, KeyCode::Char('l'));
+        assert_snapshot("search", &mut app);
+
+        press(&mut app, KeyCode::Char('x'));
+        assert_snapshot("search_no_matches", &mut app);
+
+        app.all_files.clear();
+        app.search.clear();
+        app.apply_filters();
+        app.state = AppState::MainScreen;
+        assert_snapshot("main_screen_empty", &mut app);
+
+        let _ = fs::remove_dir_all(test_dir);
+    }
+
+    #[test]
+    fn test_render_dialogs() {
+        let (mut app, test_dir) = get_test_app("render-dialogs");
+        let dialogs = [
+            ("restore_confirmation", AppState::RestoreConfirmation(0)),
+            ("deletion_confirmation", AppState::DeletionConfirmation(1)),
+            ("empty_bin_confirmation", AppState::EmptyBinConfirmation(0)),
+            ("sort_dialog", AppState::SortListDialog(SortType::Size)),
+            ("filter_dialog", AppState::FilterListDialog(1)),
+            ("error_log", AppState::ErrorLog),
+        ];
+        app.log_error("cannot read old.log.trashinfo".to_string());
+        for (name, state) in dialogs {
+            app.state = state;
+            assert_snapshot(name, &mut app);
+        }
+
+        app.state = AppState::MainScreen;
+        press(&mut app, KeyCode::Char('R'));
+        app.restore_to_input = "/dev/null/notes.txt".to_string();
+        assert_snapshot("restore_to_dialog", &mut app);
+
+        app.report = vec![
+            ("notes.txt".to_string(), Ok("/data/notes.txt".to_string())),
+            ("photos".to_string(), Err("/data doesn't exist".to_string())),
+        ];
+        app.state = AppState::BatchReport(BatchAction::Restore);
+        assert_snapshot("batch_report", &mut app);
+
+        app.state = AppState::MainScreen;
+        press(&mut app, KeyCode::Char('S'));
+        assert_snapshot("stats", &mut app);
+        press(&mut app, KeyCode::Char('2'));
+        assert_snapshot("purge_confirmation", &mut app);
+
+        // past the version
+        app.state = AppState::MainScreen;
+        press(&mut app, KeyCode::Char('h'));
+        app.help_scroll = 10;
+        assert_snapshot("help_screen", &mut app);
+
+        let _ = fs::remove_dir_all(test_dir);
+    }
+
+    #[test]
+    fn test_render_browse() {
+        let (mut app, test_dir) = get_test_app("render-browse");
+        press(&mut app, KeyCode::Char('j'));
+        press(&mut app, KeyCode::Char('l'));
+        assert_snapshot("browse", &mut app);
+        press(&mut app, KeyCode::Enter);
+        assert_snapshot("restore_child_confirmation", &mut app);
+
+        let _ = fs::remove_dir_all(test_dir);
+    }
+
+    #[test]
+    fn test_handle_input() {
+        let (mut app, test_dir) = get_test_app("handle-input");
+
+        // dialogs go back through a refresh
+        press(&mut app, KeyCode::Enter);
+        assert!(app.state == AppState::RestoreConfirmation(0));
+        press(&mut app, KeyCode::Right);
+        assert!(app.state == AppState::RestoreConfirmation(1));
+        press(&mut app, KeyCode::Enter);
+        assert!(app.state == AppState::RefreshFileList);
+        app.state = AppState::MainScreen;
+        press(&mut app, KeyCode::Delete);
+        assert!(app.state == AppState::DeletionConfirmation(0));
+        press(&mut app, KeyCode::Esc);
+        assert!(app.state == AppState::RefreshFileList);
+        app.state = AppState::MainScreen;
+
+        // marks
+        press(&mut app, KeyCode::Char(' '));
+        press(&mut app, KeyCode::Char('j'));
+        assert!(app.selected == 1 && app.get_selection() == vec![0]);
+        press(&mut app, KeyCode::Char('a'));
+        assert!(app.get_marked_count() == 3);
+        press(&mut app, KeyCode::Esc);
+        assert!(app.get_selection() == vec![1]);
+
+        // the original path matches too, and is highlighted where it does
+        press(&mut app, KeyCode::Char('/'));
+        for c in "ta/n".chars() {
+            press(&mut app, KeyCode::Char(c));
+        }
+        assert!(app.trashed_files.len() == 1);
+        let view = app.get_main_view(Rect::new(0, 0, 40, 20), Rect::new(40, 0, 40, 20));
+        assert!(view.rows[0].name == "notes.txt" && view.rows[0].matches.is_empty());
+        assert!(view.details.path_matches == vec![3, 4, 5, 6]);
+        press(&mut app, KeyCode::Esc);
+
+        // n and N go through the matches, wrapping around
+        press(&mut app, KeyCode::Char('/'));
+        press(&mut app, KeyCode::Char('t'));
+        press(&mut app, KeyCode::Enter);
+        press(&mut app, KeyCode::Char('g'));
+        let count = app.trashed_files.len();
+        assert!(count > 1 && app.selected == 0);
+        press(&mut app, KeyCode::Char('N'));
+        assert!(app.selected == count - 1);
+        press(&mut app, KeyCode::Char('n'));
+        assert!(app.selected == 0);
+        press(&mut app, KeyCode::Char('n'));
+        assert!(app.selected == 1);
+        press(&mut app, KeyCode::Esc);
+        assert!(app.trashed_files.len() == 3);
+
+        // search
+        press(&mut app, KeyCode::Char('/'));
+        assert!(app.state == AppState::Search);
+        press(&mut app, KeyCode::Char('o'));
+        press(&mut app, KeyCode::Char('l'));
+        assert!(app.trashed_files.len() == 1 && app.selected == 0);
+        press(&mut app, KeyCode::Enter);
+        assert!(app.state == AppState::MainScreen && app.search == "ol");
+        press(&mut app, KeyCode::Esc);
+        assert!(app.trashed_files.len() == 3);
+
+        // restore to
+        press(&mut app, KeyCode::Char('R'));
+        assert!(app.state == AppState::RestoreToDialog);
+        assert!(app.restore_to_input == "/data/old.log");
+        app.handle_input(KeyEvent::new(KeyCode::Char('w'), KeyModifiers::CONTROL));
+        assert!(app.restore_to_input == "/data/");
+        press(&mut app, KeyCode::Esc);
+        assert!(app.state == AppState::MainScreen);
+
+        // stats
+        press(&mut app, KeyCode::Char('S'));
+        assert!(app.state == AppState::Stats && app.stats.is_some());
+        press(&mut app, KeyCode::Char('w'));
+        assert!(app.stats_by_week);
+        press(&mut app, KeyCode::Char('3'));
+        assert!(app.state == AppState::PurgeConfirmation(1) && app.purge_days == 90);
+        press(&mut app, KeyCode::Esc);
+        assert!(app.state == AppState::Stats);
+        press(&mut app, KeyCode::Char('q'));
+        assert!(app.state == AppState::MainScreen && app.stats.is_none());
+
+        // browsing only opens directories
+        press(&mut app, KeyCode::Char('l'));
+        assert!(app.state == AppState::MainScreen);
+        press(&mut app, KeyCode::Char('g'));
+        press(&mut app, KeyCode::Char('j'));
+        press(&mut app, KeyCode::Char('l'));
+        assert!(app.state == AppState::Browse);
+        assert!(app.browser.as_ref().unwrap().entries == vec![PathBuf::from("beach.txt")]);
+        press(&mut app, KeyCode::Char('q'));
+        assert!(app.state == AppState::RefreshFileList && app.browser.is_none());
+        app.state = AppState::MainScreen;
+
+        press(&mut app, KeyCode::Char('h'));
+        assert!(app.state == AppState::HelpScreen);
+        press(&mut app, KeyCode::Char('j'));
+        assert!(app.help_scroll == 1);
+        press(&mut app, KeyCode::Esc);
+        assert!(app.state == AppState::RefreshFileList);
+        app.state = AppState::MainScreen;
+
+        // the dialogs follow the keymap too
+        app.keymap = Rc::new(Keymap::parse("confirm = y\ncancel = n\n").0);
+        press(&mut app, KeyCode::Char('s'));
+        assert!(app.state == AppState::SortListDialog(SortType::DeletionDate));
+        press(&mut app, KeyCode::Enter);
+        press(&mut app, KeyCode::Char('j'));
+        assert!(app.state == AppState::SortListDialog(SortType::TrashRoot));
+        press(&mut app, KeyCode::Char('y'));
+        assert!(app.state == AppState::RefreshFileList && app.sort_type == SortType::TrashRoot);
+        app.state = AppState::MainScreen;
+        press(&mut app, KeyCode::Delete);
+        press(&mut app, KeyCode::Char('n'));
+        assert!(app.state == AppState::RefreshFileList && app.trashed_files.len() == 3);
+        app.state = AppState::MainScreen;
+
+        // and so do the screens over the list
+        app.keymap = Rc::new(Keymap::parse("preset = emacs\npurge-month = m\n").0);
+        press(&mut app, KeyCode::Char('?'));
+        app.handle_input(KeyEvent::new(KeyCode::Char('n'), KeyModifiers::CONTROL));
+        assert!(app.help_scroll == 1);
+        app.handle_input(KeyEvent::new(KeyCode::Char('g'), KeyModifiers::CONTROL));
+        assert!(app.state == AppState::RefreshFileList);
+        app.state = AppState::MainScreen;
+        press(&mut app, KeyCode::Char('S'));
+        press(&mut app, KeyCode::Char('m'));
+        assert!(app.state == AppState::PurgeConfirmation(1) && app.purge_days == 30);
+        press(&mut app, KeyCode::Esc);
+        press(&mut app, KeyCode::Char('q'));
+        assert!(app.state == AppState::MainScreen && app.stats.is_none());
+        app.keymap = Rc::new(Keymap::default());
+
+        press(&mut app, KeyCode::Char('q'));
+        assert!(app.state == AppState::Exiting);
+
+        let _ = fs::remove_dir_all(test_dir);
+    }
+
+    #[test]
+    fn test_batch_delete() {
+        let (mut app, test_dir) = get_test_app("batch-delete");
+        let entries: Vec<(PathBuf, PathBuf)> = app
+            .trashed_files
+            .iter()
+            .map(|f| {
+                let trashinfo = f.trashinfo.as_ref().unwrap().path.clone();
+                (f.files_entry.clone().unwrap(), trashinfo)
+            })
+            .collect();
+
+        // cancelled, nothing is deleted
+        press(&mut app, KeyCode::Delete);
+        assert!(app.state == AppState::DeletionConfirmation(0));
+        press(&mut app, KeyCode::Char('l'));
+        press(&mut app, KeyCode::Enter);
+        assert!(app.state == AppState::RefreshFileList);
+        assert!(entries.iter().all(|(f, i)| f.exists() && i.exists()));
+        app.state = AppState::MainScreen;
+
+        // the marked files go, the selected one stays, and the report lists
+        // each of them
+        press(&mut app, KeyCode::Char(' '));
+        press(&mut app, KeyCode::Char('j'));
+        press(&mut app, KeyCode::Char(' '));
+        press(&mut app, KeyCode::Char('j'));
+        press(&mut app, KeyCode::Delete);
+        press(&mut app, KeyCode::Enter);
+        assert!(app.state == AppState::BatchReport(BatchAction::Delete));
+        assert!(app.report.len() == 2);
+        assert!(app
+            .report
+            .iter()
+            .all(|(_, result)| result.as_deref() == Ok("deleted forever")));
+        assert!(app.marked.is_empty());
+        for (files_entry, trashinfo) in &entries[..2] {
+            assert!(!files_entry.exists() && !trashinfo.exists());
+        }
+        assert!(entries[2].0.exists() && entries[2].1.exists());
+        press(&mut app, KeyCode::Enter);
+        assert!(app.state == AppState::RefreshFileList);
+        app.state = AppState::MainScreen;
+
+        // a file that can't be deleted shows up in the report and the log
+        let _ = fs::remove_file(&entries[2].0);
+        press(&mut app, KeyCode::Delete);
+        press(&mut app, KeyCode::Enter);
+        assert!(app.state == AppState::BatchReport(BatchAction::Delete));
+        assert!(app.report.len() == 1 && app.report[0].1.is_err());
+        assert!(app.error_log.last().unwrap().1.starts_with("cannot delete"));
+
+        let _ = fs::remove_dir_all(test_dir);
+    }
+
+    #[test]
+    fn test_open_file() {
+        let (mut app, test_dir) = get_test_app("open-file");
+        let position = |app: &App, name: &str| {
+            app.trashed_files
+                .iter()
+                .position(|f| f.original_file.ends_with(name))
+                .unwrap()
+        };
+
+        // the keys pick the opener, the loop opens the file
+        app.selected = position(&app, "notes.txt");
+        press(&mut app, KeyCode::Char('o'));
+        assert!(app.state == AppState::OpenFile(Opener::Pager));
+        app.state = AppState::MainScreen;
+        press(&mut app, KeyCode::Char('e'));
+        assert!(app.state == AppState::OpenFile(Opener::Editor));
+        app.state = AppState::MainScreen;
+        press(&mut app, KeyCode::Char('x'));
+        assert!(app.state == AppState::OpenFile(Opener::Default));
+        app.state = AppState::MainScreen;
+
+        // the files entry is given to the command, with its args
+        app.var = |v| (v == "PAGER").then(|| "test -f".to_string());
+        assert!(app.open_selected(Opener::Pager).is_ok());
+        app.var = |v| (v == "PAGER").then(|| "false".to_string());
+        let e = app.open_selected(Opener::Pager).unwrap_err();
+        assert!(e.to_string().starts_with("'false' exit status"));
+        app.var = |v| (v == "EDITOR").then(|| "ed".to_string());
+        let e = app.open_selected(Opener::Editor).unwrap_err();
+        assert!(e.to_string() == "don't know how to open files read-only in 'ed', try o");
+        app.var = |_| None;
+        let e = app.open_selected(Opener::Default).unwrap_err();
+        assert!(e.to_string().starts_with("no display"));
+
+        app.selected = position(&app, "photos");
+        let e = app.open_selected(Opener::Pager).unwrap_err();
+        assert!(e
+            .to_string()
+            .starts_with("a directory can't be opened in a pager"));
+
+        // nothing to open
+        app.all_files.clear();
+        app.apply_filters();
+        press(&mut app, KeyCode::Char('o'));
+        assert!(app.state == AppState::MainScreen);
+        assert!(app.open_selected(Opener::Pager).is_err());
+
+        let _ = fs::remove_dir_all(test_dir);
+    }
+
+    #[test]
+    fn test_handle_mouse() {
+        let (mut app, test_dir) = get_test_app("handle-mouse");
+        let left = MouseEventKind::Down(MouseButton::Left);
+        draw(&mut app);
+        let (list, preview) = (app.list_area, app.preview_area);
+
+        // the border isn't a row, below the last file neither
+        click(&mut app, left, list.x + 2, list.y);
+        click(&mut app, left, list.x + 2, list.y + 10);
+        assert!(app.selected == 0 && app.last_click.is_none());
+
+        // a click selects, a second one on the same file restores it
+        click(&mut app, left, list.x + 2, list.y + 2);
+        assert!(app.selected == 1 && app.state == AppState::MainScreen);
+        click(&mut app, left, list.x + 2, list.y + 2);
+        assert!(app.state == AppState::RestoreConfirmation(0));
+
+        // the buttons of a dialog go ahead with the clicked one
+        draw(&mut app);
+        let (cancel, _) = *app.click_targets.iter().find(|(_, v)| *v == 1).unwrap();
+        click(&mut app, left, cancel.x, cancel.y);
+        assert!(app.state == AppState::RefreshFileList);
+        assert!(app
+            .trashed_files
+            .iter()
+            .all(|f| f.files_entry.as_ref().unwrap().exists()));
+        app.state = AppState::MainScreen;
+
+        press(&mut app, KeyCode::Char('s'));
+        draw(&mut app);
+        let (size, _) = *app.click_targets.iter().find(|(_, v)| *v == 2).unwrap();
+        click(&mut app, left, size.x, size.y);
+        assert!(app.state == AppState::RefreshFileList && app.sort_type == SortType::Size);
+        app.state = AppState::MainScreen;
+
+        // the wheel scrolls what it's over
+        click(&mut app, MouseEventKind::ScrollDown, list.x + 2, list.y + 2);
+        assert!(app.selected == 2);
+        click(&mut app, MouseEventKind::ScrollUp, list.x + 2, list.y + 2);
+        assert!(app.selected == 1);
+        click(
+            &mut app,
+            MouseEventKind::ScrollDown,
+            preview.x + 2,
+            preview.y + 2,
+        );
+        assert!(app.preview_scroll == 1 && app.selected == 1);
+
+        // and a click focuses the preview, or the list
+        click(&mut app, left, preview.x + 2, preview.y + 2);
+        assert!(app.preview_focused);
+        click(&mut app, left, list.x + 2, list.y + 1);
+        assert!(!app.preview_focused && app.selected == 0);
+
+        let _ = fs::remove_dir_all(test_dir);
+    }
+
+    #[test]
+    fn test_roots_sidebar() {
+        let (mut app, test_dir) = get_test_app("roots-sidebar");
+        let home = app.all_files[0].trashroot.clone();
+        let mut usb = home.clone();
+        usb.home = PathBuf::from("/media/usb/.Trash-1000");
+        usb.root_type = TrashRootType::TopDirUser;
+        app.roots = vec![
+            RootSummary {
+                trash_dir: home.clone(),
+                count: 3,
+                size: None,
+            },
+            RootSummary {
+                trash_dir: usb,
+                count: 0,
+                size: None,
+            },
+        ];
+
+        // no size for a root until all of its entries have one
+        app.count_root(0);
+        assert!(app.roots[0].count == 3 && app.roots[0].size.is_none());
+        let photos = home.files.join("photos");
+        app.sizes.insert(photos, Some(5));
+        app.count_root(0);
+        assert!(app.roots[0].size == Some(39));
+
+        press(&mut app, KeyCode::Char('b'));
+        assert!(app.roots_shown && app.roots_focused);
+
+        // all the roots, then each root, and no further
+        press(&mut app, KeyCode::Char('j'));
+        press(&mut app, KeyCode::Char('j'));
+        press(&mut app, KeyCode::Char('j'));
+        assert!(app.roots_selected == 2);
+        press(&mut app, KeyCode::Enter);
+        assert!(app.filters.root == Some(RootFilter::TopDir(PathBuf::from("/media/usb"))));
+        assert!(app.trashed_files.is_empty());
+        press(&mut app, KeyCode::Char('k'));
+        press(&mut app, KeyCode::Enter);
+        assert!(app.filters.root == Some(RootFilter::Home) && app.trashed_files.len() == 3);
+        press(&mut app, KeyCode::Char('g'));
+        press(&mut app, KeyCode::Enter);
+        assert!(app.filters.root.is_none());
+
+        // the per root actions need a root, not all of them
+        let shift_del = KeyEvent::new(KeyCode::Delete, KeyModifiers::SHIFT);
+        app.handle_input(shift_del);
+        assert!(app.state == AppState::MainScreen);
+        press(&mut app, KeyCode::Char('j'));
+        app.handle_input(shift_del);
+        assert!(app.state == AppState::EmptyBinConfirmation(0));
+        assert!(app.empty_root.as_ref() == Some(&home.home));
+        press(&mut app, KeyCode::Esc);
+        assert!(app.state == AppState::RefreshFileList);
+        app.state = AppState::MainScreen;
+        press(&mut app, KeyCode::Char('c'));
+        assert!(app.state == AppState::RefreshFileList);
+        assert!(app
+            .notification
+            .as_ref()
+            .unwrap()
+            .message
+            .ends_with("has no broken entries"));
+        app.state = AppState::MainScreen;
+
+        // the wheel over the sidebar stops at the last root too, and a click
+        // scopes to the root clicked
+        draw(&mut app);
+        let roots = app.roots_area;
+        for _ in 0..5 {
+            click(
+                &mut app,
+                MouseEventKind::ScrollDown,
+                roots.x + 2,
+                roots.y + 2,
+            );
+        }
+        assert!(app.roots_selected == app.roots.len());
+        click(&mut app, MouseEventKind::ScrollUp, roots.x + 2, roots.y + 2);
+        assert!(app.roots_selected == 1);
+        let row = roots.y + 1 + 2 * LAYOUT_ROOT_HEIGHT;
+        click(
+            &mut app,
+            MouseEventKind::Down(MouseButton::Left),
+            roots.x + 2,
+            row,
+        );
+        assert!(app.roots_selected == 2 && app.trashed_files.is_empty());
+
+        // back to the list, then hidden
+        press(&mut app, KeyCode::Tab);
+        assert!(!app.roots_focused && app.roots_shown);
+        press(&mut app, KeyCode::Char('b'));
+        press(&mut app, KeyCode::Char('b'));
+        assert!(!app.roots_focused && !app.roots_shown);
+
+        let _ = fs::remove_dir_all(test_dir);
+    }
+
+    #[test]
+    fn test_bottom() {
+        let (mut app, test_dir) = get_test_app("bottom");
+        app.max_visible_items = 10;
+        press(&mut app, KeyCode::Char('G'));
+        assert!(app.selected == 2 && app.scroll_offset == 0);
+
+        app.max_visible_items = 2;
+        press(&mut app, KeyCode::Char('g'));
+        press(&mut app, KeyCode::Char('G'));
+        assert!(app.selected == 2 && app.scroll_offset == 1);
+
+        // a search without matches
+        app.search = String::from("nothing like it");
+        app.apply_filters();
+        assert!(app.trashed_files.is_empty());
+        press(&mut app, KeyCode::Char('G'));
+        press(&mut app, KeyCode::PageDown);
+        assert!(app.state == AppState::MainScreen);
+
+        let _ = fs::remove_dir_all(test_dir);
+    }
+
+    #[test]
+    fn test_undo_restore() {
+        let (mut app, test_dir) = get_test_app("undo");
+        // restored into the test directory rather than /data
+        for trash_file in app.trashed_files.iter_mut() {
+            trash_file.original_file = test_dir.join(trash_file.original_file.file_name().unwrap());
+        }
+        let trashinfo = test_dir
+            .join("Trash")
+            .join("info")
+            .join("notes.txt.trashinfo");
+        let contents = fs::read_to_string(&trashinfo).unwrap();
+
+        press(&mut app, KeyCode::Char('u'));
+        assert!(app.notification.as_ref().unwrap().message == "nothing to undo");
+
+        app.state = AppState::MainScreen;
+        press(&mut app, KeyCode::Enter);
+        press(&mut app, KeyCode::Enter);
+        assert!(test_dir.join("notes.txt").exists() && !trashinfo.exists());
+
+        app.state = AppState::MainScreen;
+        press(&mut app, KeyCode::Char('u'));
+        assert!(!test_dir.join("notes.txt").exists());
+        assert!(fs::read_to_string(&trashinfo).unwrap() == contents);
+        assert!(app.undo_stack.is_empty() && app.redo_stack.len() == 1);
+
+        app.state = AppState::MainScreen;
+        app.handle_input(KeyEvent::new(KeyCode::Char('r'), KeyModifiers::CONTROL));
+        assert!(test_dir.join("notes.txt").exists() && !trashinfo.exists());
+        assert!(app.undo_stack.len() == 1 && app.redo_stack.is_empty());
+
+        let _ = fs::remove_dir_all(test_dir);
+    }
+
+    #[test]
+    fn test_filter_original_dir() {
+        let (mut app, test_dir) = get_test_app("original-dir");
+        app.filters.original_dir = Some(PathBuf::from("/data/photos"));
+        app.apply_filters();
+        assert!(app.trashed_files.len() == 1);
+        assert!(app.filters.describe() == vec![String::from("under /data/photos")]);
+
+        // not a prefix of the name, a parent directory
+        app.filters.original_dir = Some(PathBuf::from("/data/note"));
+        app.apply_filters();
+        assert!(app.trashed_files.is_empty());
+
+        app.sort_type = SortType::FileName;
+        app.sort_reversed = true;
+        app.filters = Filters::default();
+        app.sort_files();
+        app.apply_filters();
+        let names: Vec<&Path> = app
+            .trashed_files
+            .iter()
+            .map(|v| v.original_file.as_path())
+            .collect();
+        assert!(
+            names
+                == [
+                    Path::new("/data/photos"),
+                    Path::new("/data/old.log"),
+                    Path::new("/data/notes.txt")
+                ]
+        );
+
+        let _ = fs::remove_dir_all(test_dir);
+    }
+
+    #[test]
+    fn test_complete_path() {
+        let temp_dir = env::temp_dir().join("trash-rs").join(format!(
+            "complete-{}",
+            std::time::SystemTime::now()
+                .duration_since(std::time::UNIX_EPOCH)
+                .unwrap()
+                .as_nanos()
+        ));
+        let _ = fs::create_dir_all(temp_dir.join("projects"));
+        let _ = fs::create_dir_all(temp_dir.join("proposals"));
+        let _ = fs::create_dir_all(temp_dir.join(".profile"));
+        let _ = File::create(temp_dir.join("notes.txt"));
+        let dir = format!("{}/", temp_dir.display());
+
+        assert_eq!(
+            complete_path(&format!("{dir}pro")),
+            (
+                format!("{dir}pro"),
+                vec!["projects/".to_string(), "proposals/".to_string()]
+            )
+        );
+        assert_eq!(
+            complete_path(&format!("{dir}proj")),
+            (format!("{dir}projects/"), vec![])
+        );
+        assert_eq!(
+            complete_path(&format!("{dir}n")),
+            (format!("{dir}notes.txt"), vec![])
+        );
+        assert_eq!(
+            complete_path(&format!("{dir}.p")),
+            (format!("{dir}.profile/"), vec![])
+        );
+        assert_eq!(
+            complete_path(&format!("{dir}x")),
+            (format!("{dir}x"), vec![])
+        );
+
+        let _ = fs::remove_dir_all(temp_dir);
+    }
+}
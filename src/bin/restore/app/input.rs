@@ -0,0 +1,790 @@
+use crate::app::{
+    complete_path, App, AppState, BatchAction, Opener, DOUBLE_CLICK_INTERVAL,
+    FILELIST_SCROLL_VIEW_OFFSET,
+};
+use crate::filters::{Filters, RootFilter, SortType, FILTER_ROWS, SORT_TYPES};
+use crate::keymap::{Action, KeyContext};
+use crate::stats::{get_age_days, TrashStats};
+use crate::views::LAYOUT_ROOT_HEIGHT;
+use crossterm::event::KeyModifiers;
+use crossterm::event::{KeyCode, KeyEvent, MouseButton, MouseEvent, MouseEventKind};
+use ratatui::layout::{Margin, Position};
+use std::path::{Path, PathBuf};
+use std::time::Instant;
+
+impl App {
+    pub fn handle_input(&mut self, key: KeyEvent) {
+        // the focused preview takes its keys first, the rest go to the list
+        if matches!(self.state, AppState::MainScreen | AppState::Browse) && self.preview_focused {
+            if let Some(action) = self.keymap.get_action(&key, KeyContext::Preview) {
+                self.handle_preview_action(action);
+                return;
+            }
+        }
+        // and so does the focused sidebar
+        if self.state == AppState::MainScreen && self.roots_focused {
+            if let Some(action) = self.keymap.get_action(&key, KeyContext::Roots) {
+                self.handle_roots_action(action);
+                return;
+            }
+        }
+
+        match self.state {
+            AppState::MainScreen => match self.keymap.get_action(&key, KeyContext::Main) {
+                Some(Action::Up) => self.select_previous(),
+                Some(Action::Down) => self.select_next(),
+                Some(Action::Restore) if !self.trashed_files.is_empty() => {
+                    self.state = AppState::RestoreConfirmation(0);
+                }
+                Some(Action::RestoreTo) if !self.trashed_files.is_empty() => {
+                    self.restore_to_input = self.trashed_files[self.selected]
+                        .original_file
+                        .display()
+                        .to_string();
+                    self.restore_to_completions.clear();
+                    self.state = AppState::RestoreToDialog;
+                }
+                Some(Action::EmptyBin) => {
+                    self.empty_root = None;
+                    self.state = AppState::EmptyBinConfirmation(0);
+                }
+                Some(Action::Stats) => {
+                    self.stats = Some(TrashStats::from(&self.all_files, &self.sizes));
+                    self.stats_scroll = 0;
+                    self.state = AppState::Stats;
+                }
+                Some(Action::Roots) => {
+                    self.roots_shown = true;
+                    self.roots_focused = true;
+                    self.preview_focused = false;
+                }
+                Some(Action::Delete) if !self.trashed_files.is_empty() => {
+                    // delete marked files, or the selected file
+                    self.state = AppState::DeletionConfirmation(0);
+                }
+                Some(Action::Mark) => {
+                    if let Some(files_entry) = self
+                        .trashed_files
+                        .get(self.selected)
+                        .and_then(|f| f.files_entry.clone())
+                    {
+                        if !self.marked.remove(&files_entry) {
+                            self.marked.insert(files_entry);
+                        }
+                    }
+                }
+                Some(Action::MarkRange) => {
+                    if self.visual_anchor.is_some() {
+                        self.end_visual_selection();
+                    } else if !self.trashed_files.is_empty() {
+                        self.visual_anchor = Some(self.selected);
+                    }
+                }
+                Some(Action::MarkAll) => {
+                    self.end_visual_selection();
+                    // the list is what's shown, so this marks everything
+                    // shown
+                    if self.get_marked_count() == self.trashed_files.len() {
+                        self.marked.clear();
+                    } else {
+                        self.marked = self
+                            .trashed_files
+                            .iter()
+                            .filter_map(|f| f.files_entry.clone())
+                            .collect();
+                    }
+                }
+                Some(Action::Search) => {
+                    self.state = AppState::Search;
+                }
+                Some(Action::NextMatch) if !self.trashed_files.is_empty() => {
+                    // the list only has matches, wrap around at the ends
+                    self.selected = (self.selected + 1) % self.trashed_files.len();
+                    self.scroll_to_selected();
+                }
+                Some(Action::PreviousMatch) if !self.trashed_files.is_empty() => {
+                    self.selected = self
+                        .selected
+                        .checked_sub(1)
+                        .unwrap_or(self.trashed_files.len() - 1);
+                    self.scroll_to_selected();
+                }
+                Some(Action::Clear) => {
+                    if self.visual_anchor.is_some() {
+                        self.visual_anchor = None;
+                    } else if !self.marked.is_empty() {
+                        self.marked.clear();
+                    } else if !self.search.is_empty() {
+                        self.search.clear();
+                        self.apply_filters();
+                    }
+                }
+                Some(Action::Refresh) => {
+                    self.state = AppState::RefreshFileList;
+                }
+                Some(Action::Sort) => {
+                    self.state = AppState::SortListDialog(self.sort_type);
+                }
+                Some(Action::OpenPager) if !self.trashed_files.is_empty() => {
+                    self.state = AppState::OpenFile(Opener::Pager);
+                }
+                Some(Action::OpenEditor) if !self.trashed_files.is_empty() => {
+                    self.state = AppState::OpenFile(Opener::Editor);
+                }
+                Some(Action::OpenDefault) if !self.trashed_files.is_empty() => {
+                    self.state = AppState::OpenFile(Opener::Default);
+                }
+                Some(Action::Browse) => {
+                    let trash_file = self.trashed_files.get(self.selected).cloned();
+                    if let Some(trash_file) = trash_file {
+                        let files_entry = trash_file.files_entry.clone().unwrap_or_default();
+                        if !files_entry.is_symlink() && files_entry.is_dir() {
+                            match self.browse(trash_file, PathBuf::new()) {
+                                Ok(_) => self.state = AppState::Browse,
+                                Err(e) => self.notify_error(format!("cannot browse: {e}")),
+                            }
+                        }
+                    }
+                }
+                Some(Action::ErrorLog) => {
+                    self.error_log_scroll = 0;
+                    self.state = AppState::ErrorLog;
+                }
+                Some(Action::Filter) => {
+                    self.filters_draft = self.filters.clone();
+                    self.state = AppState::FilterListDialog(0);
+                }
+                Some(Action::Top) => {
+                    // go to absolute top
+                    self.selected = 0;
+                    self.scroll_offset = 0;
+                }
+                Some(Action::Bottom) if !self.trashed_files.is_empty() => {
+                    // go to absolute bottom, the list may be shorter than the
+                    // window
+                    self.selected = self.trashed_files.len() - 1;
+                    self.scroll_offset = (self.selected + 1).saturating_sub(self.max_visible_items);
+                }
+                Some(Action::FocusPreview) => {
+                    self.preview_focused = true;
+                    self.roots_focused = false;
+                }
+                Some(Action::Undo) => {
+                    self.undo_restore(false);
+                    self.state = AppState::RefreshFileList;
+                }
+                Some(Action::Redo) => {
+                    self.undo_restore(true);
+                    self.state = AppState::RefreshFileList;
+                }
+                Some(Action::Help) => {
+                    self.help_scroll = 0;
+                    self.state = AppState::HelpScreen;
+                }
+                Some(Action::Quit) => {
+                    self.state = AppState::Exiting;
+                }
+                _ => {}
+            },
+
+            AppState::RestoreConfirmation(choice) => {
+                match self.keymap.get_action(&key, KeyContext::Dialog) {
+                    Some(Action::NextChoice | Action::PreviousChoice) => {
+                        // toggle between Yes (0) and No (1)
+                        if let AppState::RestoreConfirmation(choice) = &mut self.state {
+                            *choice = if *choice == 0 { 1 } else { 0 };
+                        }
+                    }
+                    Some(Action::Confirm) => {
+                        // confirm the action if Yes is selected
+                        if choice == 0 {
+                            self.run_batch(BatchAction::Restore);
+                        } else {
+                            self.state = AppState::RefreshFileList;
+                        }
+                    }
+                    Some(Action::Cancel) => {
+                        // close the dialog without performing any action
+                        self.state = AppState::RefreshFileList;
+                    }
+                    _ => {}
+                }
+            }
+
+            AppState::RestoreToDialog => {
+                // editing the path drops the completions shown
+                if key.code != KeyCode::Tab {
+                    self.restore_to_completions.clear();
+                }
+                match key.code {
+                    KeyCode::Enter if self.restore_selected_to() => {
+                        self.state = AppState::RefreshFileList;
+                    }
+                    KeyCode::Esc => self.state = AppState::MainScreen,
+                    KeyCode::Tab => {
+                        (self.restore_to_input, self.restore_to_completions) =
+                            complete_path(&self.restore_to_input);
+                    }
+                    KeyCode::Backspace => {
+                        self.restore_to_input.pop();
+                    }
+                    KeyCode::Char('u') if key.modifiers == KeyModifiers::CONTROL => {
+                        self.restore_to_input.clear();
+                    }
+                    // back to the last /, or the one before if it's the last
+                    // character
+                    KeyCode::Char('w') if key.modifiers == KeyModifiers::CONTROL => {
+                        let trimmed = self.restore_to_input.trim_end_matches('/');
+                        let end = trimmed.rfind('/').map(|i| i + 1).unwrap_or(0);
+                        self.restore_to_input.truncate(end);
+                    }
+                    KeyCode::Char(c) if !key.modifiers.contains(KeyModifiers::CONTROL) => {
+                        self.restore_to_input.push(c);
+                    }
+                    _ => {}
+                }
+            }
+
+            AppState::DeletionConfirmation(choice) => {
+                match self.keymap.get_action(&key, KeyContext::Dialog) {
+                    Some(Action::NextChoice | Action::PreviousChoice) => {
+                        // toggle between Yes (0) and No (1)
+                        if let AppState::DeletionConfirmation(choice) = &mut self.state {
+                            *choice = if *choice == 0 { 1 } else { 0 };
+                        }
+                    }
+                    Some(Action::Confirm) => {
+                        // confirm the action if Yes is selected
+                        if choice == 0 {
+                            self.run_batch(BatchAction::Delete);
+                        } else {
+                            self.state = AppState::RefreshFileList;
+                        }
+                    }
+                    Some(Action::Cancel) => {
+                        // close the dialog without performing any action
+                        self.state = AppState::RefreshFileList;
+                    }
+                    _ => {}
+                }
+            }
+
+            AppState::EmptyBinConfirmation(choice) => {
+                match self.keymap.get_action(&key, KeyContext::Dialog) {
+                    Some(Action::NextChoice | Action::PreviousChoice) => {
+                        // toggle between Yes (0) and No (1)
+                        if let AppState::EmptyBinConfirmation(choice) = &mut self.state {
+                            *choice = if *choice == 0 { 1 } else { 0 };
+                        }
+                    }
+                    Some(Action::Confirm) => {
+                        // confirm the action if Yes is selected
+                        if choice == 0 {
+                            self.empty_bin();
+                        }
+
+                        // refresh and return to file list after action or cancel
+                        self.state = AppState::RefreshFileList;
+                    }
+                    Some(Action::Cancel) => {
+                        // close the dialog without performing any action
+                        self.state = AppState::RefreshFileList;
+                    }
+                    _ => {}
+                }
+            }
+
+            AppState::Search => match key.code {
+                KeyCode::Up => self.select_previous(),
+                KeyCode::Down => self.select_next(),
+                KeyCode::Enter => {
+                    self.state = AppState::MainScreen;
+                }
+                KeyCode::Esc => {
+                    self.search.clear();
+                    self.apply_filters();
+                    self.state = AppState::MainScreen;
+                }
+                KeyCode::Backspace => {
+                    self.search.pop();
+                    self.apply_filters();
+                }
+                KeyCode::Char(c) if !key.modifiers.contains(KeyModifiers::CONTROL) => {
+                    self.search.push(c);
+                    self.apply_filters();
+                }
+                _ => {}
+            },
+
+            AppState::FilterListDialog(row) => {
+                match self.keymap.get_action(&key, KeyContext::Dialog) {
+                    Some(Action::NextRow) => {
+                        self.state = AppState::FilterListDialog((row + 1) % FILTER_ROWS);
+                    }
+                    Some(Action::PreviousRow) => {
+                        self.state =
+                            AppState::FilterListDialog((row + FILTER_ROWS - 1) % FILTER_ROWS);
+                    }
+                    Some(action @ (Action::NextChoice | Action::PreviousChoice)) => {
+                        let top_dirs = self.get_top_dirs();
+                        self.filters_draft
+                            .cycle(row, action == Action::NextChoice, &top_dirs);
+                    }
+                    Some(Action::ClearFilters) => {
+                        self.filters_draft = Filters::default();
+                    }
+                    Some(Action::Confirm) => {
+                        self.filters = self.filters_draft.clone();
+                        self.apply_filters();
+                        self.state = AppState::MainScreen;
+                    }
+                    Some(Action::Cancel) => {
+                        self.state = AppState::MainScreen;
+                    }
+                    _ => {}
+                }
+            }
+
+            AppState::Browse => {
+                let Some(browser) = self.browser.as_mut() else {
+                    self.state = AppState::MainScreen;
+                    return;
+                };
+                match self.keymap.get_action(&key, KeyContext::Browse) {
+                    Some(Action::Down) => {
+                        browser.select(browser.selected + 1, self.max_visible_items);
+                    }
+                    Some(Action::Up) => {
+                        browser.select(browser.selected.saturating_sub(1), self.max_visible_items);
+                    }
+                    Some(Action::Top) => {
+                        browser.select(0, self.max_visible_items);
+                    }
+                    Some(Action::Bottom) => {
+                        browser.select(usize::MAX, self.max_visible_items);
+                    }
+                    Some(Action::Browse) => self.browse_selected(),
+                    Some(Action::BrowseUp) => {
+                        if browser.path.as_os_str().is_empty() {
+                            self.browser = None;
+                            self.state = AppState::RefreshFileList;
+                        } else {
+                            // back to the directory, with the one left selected
+                            let left = browser.path.clone();
+                            let trash_file = browser.trash_file.clone();
+                            let parent = left.parent().unwrap_or(Path::new("")).to_path_buf();
+                            if let Err(e) = self.browse(trash_file, parent) {
+                                self.notify_error(format!("cannot browse: {e}"));
+                            } else if let Some(browser) = self.browser.as_mut() {
+                                let index = browser.entries.iter().position(|v| *v == left);
+                                browser.select(index.unwrap_or(0), self.max_visible_items);
+                            }
+                        }
+                    }
+                    Some(Action::Restore) if !browser.entries.is_empty() => {
+                        self.state = AppState::RestoreChildConfirmation(0);
+                    }
+                    Some(Action::FocusPreview) => self.preview_focused = true,
+                    Some(action @ (Action::Undo | Action::Redo)) => {
+                        let selected = browser.selected;
+                        let trash_file = browser.trash_file.clone();
+                        let path = browser.path.clone();
+                        self.undo_restore(action == Action::Redo);
+                        // the directory may have left the trash with a redo
+                        if self.browse(trash_file, path).is_err() {
+                            self.browser = None;
+                            self.state = AppState::RefreshFileList;
+                        } else if let Some(browser) = self.browser.as_mut() {
+                            browser.select(selected, self.max_visible_items);
+                        }
+                    }
+                    Some(Action::Back) => {
+                        self.browser = None;
+                        self.state = AppState::RefreshFileList;
+                    }
+                    _ => {}
+                }
+            }
+
+            AppState::RestoreChildConfirmation(choice) => {
+                match self.keymap.get_action(&key, KeyContext::Dialog) {
+                    Some(Action::NextChoice | Action::PreviousChoice) => {
+                        // toggle between Yes (0) and No (1)
+                        self.state =
+                            AppState::RestoreChildConfirmation(if choice == 0 { 1 } else { 0 });
+                    }
+                    Some(Action::Confirm) => {
+                        if choice == 0 {
+                            self.restore_selected_child();
+                        }
+                        self.state = AppState::Browse;
+                    }
+                    Some(Action::Cancel) => {
+                        self.state = AppState::Browse;
+                    }
+                    _ => {}
+                }
+            }
+
+            AppState::ErrorLog => match self.keymap.get_action(&key, KeyContext::Dialog) {
+                Some(Action::NextRow) if self.error_log_scroll + 1 < self.error_log.len() => {
+                    self.error_log_scroll += 1;
+                }
+                Some(Action::PreviousRow) => {
+                    self.error_log_scroll = self.error_log_scroll.saturating_sub(1);
+                }
+                Some(Action::Cancel) => {
+                    self.state = AppState::MainScreen;
+                }
+                _ => {}
+            },
+
+            AppState::BatchReport(_) => match self.keymap.get_action(&key, KeyContext::Dialog) {
+                Some(Action::NextRow) if self.report_scroll + 1 < self.report.len() => {
+                    self.report_scroll += 1;
+                }
+                Some(Action::PreviousRow) => {
+                    self.report_scroll = self.report_scroll.saturating_sub(1);
+                }
+                Some(Action::Confirm | Action::Cancel) => {
+                    self.state = AppState::RefreshFileList;
+                }
+                _ => {}
+            },
+
+            AppState::Stats => match self.keymap.get_action(&key, KeyContext::Stats) {
+                Some(Action::NextRow) => self.stats_scroll += 1,
+                Some(Action::PreviousRow) => {
+                    self.stats_scroll = self.stats_scroll.saturating_sub(1);
+                }
+                Some(Action::PerWeek) => self.stats_by_week = !self.stats_by_week,
+                Some(Action::PurgeOlderThan(days)) => {
+                    self.purge_days = days;
+                    self.state = AppState::PurgeConfirmation(1);
+                }
+                Some(Action::Cancel) => {
+                    self.stats = None;
+                    self.state = AppState::MainScreen;
+                }
+                _ => {}
+            },
+
+            AppState::PurgeConfirmation(choice) => {
+                match self.keymap.get_action(&key, KeyContext::Dialog) {
+                    Some(Action::NextChoice | Action::PreviousChoice) => {
+                        // toggle between Yes (0) and No (1)
+                        if let AppState::PurgeConfirmation(choice) = &mut self.state {
+                            *choice = if *choice == 0 { 1 } else { 0 };
+                        }
+                    }
+                    Some(Action::Confirm) if choice == 0 => {
+                        let now = (self.now)();
+                        let days = self.purge_days;
+                        self.delete_forever_where(|f| {
+                            f.trashinfo
+                                .as_ref()
+                                .is_some_and(|v| get_age_days(v.get_deletion_date(), now) > days)
+                        });
+                        self.stats = None;
+                        self.state = AppState::RefreshFileList;
+                    }
+                    Some(Action::Confirm | Action::Cancel) => {
+                        self.state = AppState::Stats;
+                    }
+                    _ => {}
+                }
+            }
+
+            AppState::HelpScreen => {
+                match self.keymap.get_action(&key, KeyContext::Dialog) {
+                    Some(Action::NextRow) => self.help_scroll += 1,
+                    Some(Action::PreviousRow) => {
+                        self.help_scroll = self.help_scroll.saturating_sub(1);
+                    }
+                    Some(Action::Cancel) => {
+                        // close the dialog without performing any action
+                        self.state = AppState::RefreshFileList;
+                    }
+                    _ => {}
+                }
+            }
+
+            AppState::SortListDialog(choice) => {
+                match self.keymap.get_action(&key, KeyContext::Dialog) {
+                    Some(Action::NextRow | Action::NextChoice) => {
+                        let next_choice = match choice {
+                            SortType::DeletionDate => SortType::TrashRoot,
+                            SortType::TrashRoot => SortType::Size,
+                            SortType::Size => SortType::FileName,
+                            SortType::FileName => SortType::FileName,
+                        };
+                        self.state = AppState::SortListDialog(next_choice);
+                    }
+                    Some(Action::PreviousRow | Action::PreviousChoice) => {
+                        let prev_choice = match choice {
+                            SortType::DeletionDate => SortType::DeletionDate,
+                            SortType::TrashRoot => SortType::DeletionDate,
+                            SortType::Size => SortType::TrashRoot,
+                            SortType::FileName => SortType::Size,
+                        };
+                        self.state = AppState::SortListDialog(prev_choice);
+                    }
+                    Some(Action::Confirm) => {
+                        self.sort_type = choice;
+                        self.state = AppState::RefreshFileList;
+                    }
+                    Some(Action::Cancel) => {
+                        self.state = AppState::RefreshFileList;
+                    }
+                    _ => {}
+                }
+            }
+            _ => {}
+        }
+    }
+
+    fn handle_roots_action(&mut self, action: Action) {
+        match action {
+            Action::Down => self.roots_selected = (self.roots_selected + 1).min(self.roots.len()),
+            Action::Up => self.roots_selected = self.roots_selected.saturating_sub(1),
+            Action::Top => self.roots_selected = 0,
+            Action::Bottom => self.roots_selected = self.roots.len(),
+            // the same as choosing the root in the filter dialog
+            Action::ScopeRoot => {
+                self.filters.root = match self.roots_selected {
+                    0 => None,
+                    i => Some(RootFilter::for_root(&self.roots[i - 1].trash_dir)),
+                };
+                self.apply_filters();
+            }
+            Action::EmptyRoot if self.roots_selected > 0 => {
+                self.empty_root = Some(self.roots[self.roots_selected - 1].trash_dir.home.clone());
+                self.state = AppState::EmptyBinConfirmation(0);
+            }
+            Action::CheckRoot if self.roots_selected > 0 => {
+                self.check_root(self.roots_selected - 1);
+                self.state = AppState::RefreshFileList;
+            }
+            Action::FocusList => self.roots_focused = false,
+            Action::Roots => {
+                self.roots_shown = false;
+                self.roots_focused = false;
+            }
+            _ => {}
+        }
+    }
+
+    fn handle_preview_action(&mut self, action: Action) {
+        // -4 for the border and the padding
+        let page = (self.preview_area.height.saturating_sub(4) as usize).max(1);
+        // clamped to the preview length when rendering
+        match action {
+            Action::ScrollDown => self.preview_scroll += 1,
+            Action::ScrollUp => self.preview_scroll = self.preview_scroll.saturating_sub(1),
+            Action::PageDown => self.preview_scroll += page,
+            Action::PageUp => self.preview_scroll = self.preview_scroll.saturating_sub(page),
+            Action::HalfPageDown => self.preview_scroll += page / 2,
+            Action::HalfPageUp => {
+                self.preview_scroll = self.preview_scroll.saturating_sub(page / 2);
+            }
+            Action::ScrollTop => self.preview_scroll = 0,
+            Action::ScrollBottom => self.preview_scroll = usize::MAX,
+            Action::FocusList => self.preview_focused = false,
+            _ => {}
+        }
+    }
+
+    pub fn handle_mouse(&mut self, mouse: MouseEvent) {
+        let position = Position::new(mouse.column, mouse.row);
+        match self.state {
+            AppState::MainScreen | AppState::Search => match mouse.kind {
+                MouseEventKind::ScrollDown if self.roots_area.contains(position) => {
+                    self.roots_selected = (self.roots_selected + 1).min(self.roots.len());
+                }
+                MouseEventKind::ScrollUp if self.roots_area.contains(position) => {
+                    self.roots_selected = self.roots_selected.saturating_sub(1);
+                }
+                MouseEventKind::Down(MouseButton::Left) if self.roots_area.contains(position) => {
+                    self.roots_focused = true;
+                    self.preview_focused = false;
+                    if !self.roots_area.inner(Margin::new(1, 1)).contains(position) {
+                        return;
+                    }
+                    let index = self.roots_scroll
+                        + ((mouse.row - self.roots_area.y - 1) / LAYOUT_ROOT_HEIGHT) as usize;
+                    if index <= self.roots.len() {
+                        self.roots_selected = index;
+                        self.handle_roots_action(Action::ScopeRoot);
+                    }
+                }
+                MouseEventKind::ScrollDown if self.preview_area.contains(position) => {
+                    // clamped to the preview length when rendering
+                    self.preview_scroll += 1;
+                }
+                MouseEventKind::ScrollUp if self.preview_area.contains(position) => {
+                    self.preview_scroll = self.preview_scroll.saturating_sub(1);
+                }
+                MouseEventKind::ScrollDown if self.list_area.contains(position) => {
+                    self.select_next();
+                }
+                MouseEventKind::ScrollUp if self.list_area.contains(position) => {
+                    self.select_previous();
+                }
+                MouseEventKind::Down(MouseButton::Left) => {
+                    self.preview_focused = self.preview_area.contains(position);
+                    self.roots_focused = false;
+                    // inside the border of the list
+                    if !self.list_area.inner(Margin::new(1, 1)).contains(position) {
+                        return;
+                    }
+                    let index = self.scroll_offset + (mouse.row - self.list_area.y - 1) as usize;
+                    if index >= self.trashed_files.len() {
+                        return;
+                    }
+
+                    let double_click = matches!(
+                        self.last_click,
+                        Some((i, at)) if i == index && at.elapsed() <= DOUBLE_CLICK_INTERVAL
+                    );
+                    self.selected = index;
+                    if double_click {
+                        self.last_click = None;
+                        self.state = AppState::RestoreConfirmation(0);
+                    } else {
+                        self.last_click = Some((index, Instant::now()));
+                    }
+                }
+                _ => {}
+            },
+
+            AppState::Browse => match mouse.kind {
+                MouseEventKind::ScrollDown if self.preview_area.contains(position) => {
+                    self.preview_scroll += 1;
+                }
+                MouseEventKind::ScrollUp if self.preview_area.contains(position) => {
+                    self.preview_scroll = self.preview_scroll.saturating_sub(1);
+                }
+                MouseEventKind::ScrollDown if self.list_area.contains(position) => {
+                    if let Some(browser) = self.browser.as_mut() {
+                        browser.select(browser.selected + 1, self.max_visible_items);
+                    }
+                }
+                MouseEventKind::ScrollUp if self.list_area.contains(position) => {
+                    if let Some(browser) = self.browser.as_mut() {
+                        browser.select(browser.selected.saturating_sub(1), self.max_visible_items);
+                    }
+                }
+                MouseEventKind::Down(MouseButton::Left) => {
+                    self.preview_focused = self.preview_area.contains(position);
+                    if !self.list_area.inner(Margin::new(1, 1)).contains(position) {
+                        return;
+                    }
+                    let Some(browser) = self.browser.as_mut() else {
+                        return;
+                    };
+                    let index = browser.scroll_offset + (mouse.row - self.list_area.y - 1) as usize;
+                    if index >= browser.entries.len() {
+                        return;
+                    }
+
+                    // a double click opens a directory, or restores a file
+                    let double_click = matches!(
+                        self.last_click,
+                        Some((i, at)) if i == index && at.elapsed() <= DOUBLE_CLICK_INTERVAL
+                    );
+                    browser.select(index, self.max_visible_items);
+                    if double_click {
+                        self.last_click = None;
+                        let files_entry = browser.get_files_entry(&browser.entries[index]);
+                        if !files_entry.is_symlink() && files_entry.is_dir() {
+                            self.browse_selected();
+                        } else {
+                            self.state = AppState::RestoreChildConfirmation(0);
+                        }
+                    } else {
+                        self.last_click = Some((index, Instant::now()));
+                    }
+                }
+                _ => {}
+            },
+
+            AppState::RestoreConfirmation(_)
+            | AppState::DeletionConfirmation(_)
+            | AppState::EmptyBinConfirmation(_)
+            | AppState::PurgeConfirmation(_)
+            | AppState::RestoreChildConfirmation(_)
+            | AppState::SortListDialog(_) => {
+                if mouse.kind != MouseEventKind::Down(MouseButton::Left) {
+                    return;
+                }
+                let Some(choice) = self
+                    .click_targets
+                    .iter()
+                    .find(|(area, _)| area.contains(position))
+                    .map(|(_, v)| *v)
+                else {
+                    return;
+                };
+
+                // select the clicked choice, then go ahead as with the
+                // confirm key
+                self.state = match self.state {
+                    AppState::RestoreConfirmation(_) => AppState::RestoreConfirmation(choice),
+                    AppState::DeletionConfirmation(_) => AppState::DeletionConfirmation(choice),
+                    AppState::EmptyBinConfirmation(_) => AppState::EmptyBinConfirmation(choice),
+                    AppState::PurgeConfirmation(_) => AppState::PurgeConfirmation(choice),
+                    AppState::RestoreChildConfirmation(_) => {
+                        AppState::RestoreChildConfirmation(choice)
+                    }
+                    _ => AppState::SortListDialog(SORT_TYPES[choice]),
+                };
+                if let Some(key) = self.keymap.get_keys(Action::Confirm).first() {
+                    self.handle_input(KeyEvent::new(key.code, key.modifiers));
+                }
+            }
+            _ => {}
+        }
+    }
+
+    fn select_previous(&mut self) {
+        if self.selected > 0 {
+            self.selected -= 1;
+        }
+
+        // if selected item is close to a position to start scrolling up
+        // unless it's close to the top
+        if self.selected >= FILELIST_SCROLL_VIEW_OFFSET
+            && self.selected - FILELIST_SCROLL_VIEW_OFFSET < self.scroll_offset
+        {
+            self.scroll_offset = self.selected - FILELIST_SCROLL_VIEW_OFFSET;
+        }
+    }
+
+    fn select_next(&mut self) {
+        if self.selected + 1 < self.trashed_files.len() {
+            self.selected += 1;
+        }
+
+        // if the selected item is close to a position to start scrolling down
+        // unless it results in overscrolling
+        if self.selected + FILELIST_SCROLL_VIEW_OFFSET < self.trashed_files.len()
+            && self.selected + FILELIST_SCROLL_VIEW_OFFSET
+                >= self.scroll_offset + self.max_visible_items
+        {
+            // since scroll_offset is usize, need to offset first before substracting
+            self.scroll_offset =
+                self.selected + FILELIST_SCROLL_VIEW_OFFSET - self.max_visible_items + 1;
+        }
+    }
+
+    // scroll just enough to show the selected file
+    pub fn scroll_to_selected(&mut self) {
+        if self.selected < self.scroll_offset {
+            self.scroll_offset = self.selected;
+        } else if self.max_visible_items > 0
+            && self.selected >= self.scroll_offset + self.max_visible_items
+        {
+            self.scroll_offset = self.selected + 1 - self.max_visible_items;
+        }
+    }
+}
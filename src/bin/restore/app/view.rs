@@ -0,0 +1,1168 @@
+use crate::app::{App, AppState};
+use crate::dialogs::{
+    get_button_areas, get_help_lines, get_stats_lines, ConfirmationView, ErrorLogView, FilterView,
+    HelpView, ReportView, RestoreToView, SortView, StatsView, CONFIRMATION_BUTTONS,
+};
+use crate::filters::{fuzzy_match, RootFilter, SortType, SORT_TYPES};
+use crate::keymap::{Action, KeyContext, Keymap, ACTIONS, FIXED_SHORTCUTS};
+use crate::preview::{
+    format_hex_line, get_file_type, read_file_magic, BinaryPreview, SyntaxToken, TextPreview,
+};
+use crate::sizes::format_size;
+use crate::stats::{
+    get_deletion_histogram, PURGE_OLDER_THAN_DAYS, STATS_HISTOGRAM_DAYS, STATS_HISTOGRAM_WEEKS,
+};
+use crate::theme::ThemeColor;
+use crate::views::{
+    get_frame_areas, get_screen_areas, popup_area, render, BrowseView, DetailsView, EntryKind,
+    ListRow, MainView, RootRow, RootsView, Screen, View, LAYOUT_ROOT_HEIGHT, LAYOUT_SUBTITLE_WIDTH,
+};
+use chrono::{DateTime, Local};
+use libtrash::*;
+use ratatui::backend::Backend;
+use ratatui::layout::{Margin, Rect};
+use ratatui::style::{Color, Modifier, Style};
+use ratatui::text::{Line, Span, Text};
+use ratatui::Terminal;
+use std::fs::read_dir;
+use std::fs::{self, File};
+use std::io::{self, Read, Seek, SeekFrom};
+use std::path::{Path, PathBuf};
+
+impl App {
+    // draw the app, with what it shows worked out beforehand
+    pub fn draw<B: Backend>(&mut self, terminal: &mut Terminal<B>) -> io::Result<()> {
+        let size = terminal.size()?;
+        let view = self.get_view(Rect::new(0, 0, size.width, size.height));
+        terminal.draw(|f| render(f, &view, &self.theme))?;
+        Ok(())
+    }
+
+    // everything the screen shows. this is where the areas the mouse is
+    // matched against are laid out, and where the previews are read from the
+    // filesystem
+    fn get_view(&mut self, area: Rect) -> View {
+        let [_, midsection_area, _] = get_frame_areas(area);
+        self.click_targets.clear();
+
+        let screen = match self.state {
+            AppState::MainScreen | AppState::Search => {
+                let areas = get_screen_areas(midsection_area, self.roots_shown);
+                self.list_area = areas.list;
+                self.preview_area = areas.details[1];
+                self.roots_area = areas.roots;
+                let roots = self.roots_shown.then(|| self.get_roots_view(areas.roots));
+                let view = self.get_main_view(areas.list, areas.details[1]);
+                Screen::Main(areas, roots, view)
+            }
+
+            AppState::Browse => {
+                let areas = get_screen_areas(midsection_area, false);
+                self.list_area = areas.list;
+                self.preview_area = areas.details[1];
+                match self.get_browse_view(areas.list, areas.details[1]) {
+                    Some(view) => Screen::Browse(areas, view),
+                    None => Screen::Blank,
+                }
+            }
+
+            AppState::RestoreConfirmation(_)
+            | AppState::DeletionConfirmation(_)
+            | AppState::EmptyBinConfirmation(_)
+            | AppState::RestoreChildConfirmation(_) => match self.get_confirmation_view(area) {
+                Some(view) => Screen::Confirmation(view),
+                None => Screen::Blank,
+            },
+
+            AppState::RestoreToDialog => Screen::RestoreTo(self.get_restore_to_view(area)),
+
+            AppState::SortListDialog(choice) => {
+                let view = SortView {
+                    sort_type: self.sort_type,
+                    choice,
+                    area: popup_area(area, 30, 15),
+                };
+                // a row for each choice, after the question and a blank line
+                let inner = view.area.inner(Margin::new(1, 1));
+                for i in 0..SORT_TYPES.len() {
+                    let row = Rect {
+                        y: inner.y + 2 + i as u16,
+                        height: 1,
+                        ..inner
+                    };
+                    self.click_targets.push((row.intersection(inner), i));
+                }
+                Screen::Sort(view)
+            }
+
+            AppState::FilterListDialog(selected) => Screen::Filter(FilterView {
+                rows: self.filters_draft.describe_rows().to_vec(),
+                selected,
+                area: popup_area(area, 40, 30),
+            }),
+
+            AppState::BatchReport(action) => Screen::Report(ReportView {
+                action,
+                report: self.report.clone(),
+                scroll: self.report_scroll,
+                area: popup_area(area, 60, 60),
+            }),
+
+            AppState::Stats | AppState::PurgeConfirmation(_) => match self.get_stats_view(area) {
+                Some(view) => Screen::Stats(view, self.get_confirmation_view(area)),
+                None => Screen::Blank,
+            },
+
+            AppState::ErrorLog => Screen::ErrorLog(ErrorLogView {
+                entries: self.error_log.clone(),
+                scroll: self.error_log_scroll,
+                area: popup_area(area, 60, 60),
+            }),
+
+            AppState::HelpScreen => Screen::Help(self.get_help_view(area)),
+
+            _ => Screen::Blank,
+        };
+
+        // the buttons of the yes/no dialog
+        if let Screen::Confirmation(view) | Screen::Stats(_, Some(view)) = &screen {
+            self.click_targets = get_button_areas(view.area, &CONFIRMATION_BUTTONS)
+                .into_iter()
+                .zip(0..)
+                .collect();
+        }
+
+        View {
+            filters: if self.filters.is_active() {
+                format!("Filters: {} ", self.filters.describe().join(" · "))
+            } else {
+                String::new()
+            },
+            screen,
+            search_bar: (self.state == AppState::Search).then(|| format!("/{}", self.search)),
+            directions: self.get_directions(),
+            notification: self
+                .notification
+                .as_ref()
+                .map(|v| (v.message.clone(), v.is_error)),
+        }
+    }
+
+    // the shortcuts in the footer, from the keymap in use
+    fn get_directions(&self) -> Vec<(String, &'static str)> {
+        let keymap = &self.keymap;
+        let mut directions: Vec<(String, &'static str)> = vec![];
+        match self.state {
+            AppState::Search => {
+                directions.push(("↓↑".to_string(), "navigate list"));
+                directions.push(("enter".to_string(), "done"));
+                directions.push(("esc".to_string(), "clear search"));
+            }
+            AppState::MainScreen | AppState::Browse if self.preview_focused => {
+                push_preview_directions(&mut directions, keymap);
+            }
+            AppState::MainScreen if self.roots_focused => {
+                directions.push((
+                    keymap.get_pair_label(Action::Down, Action::Up),
+                    "navigate roots",
+                ));
+                directions.push((keymap.get_label(Action::ScopeRoot), "show files"));
+                directions.push((keymap.get_label(Action::EmptyRoot), "empty root"));
+                directions.push((keymap.get_label(Action::CheckRoot), "check root"));
+                directions.push((keymap.get_label(Action::FocusList), "back to the list"));
+                directions.push((keymap.get_label(Action::Roots), "hide"));
+            }
+            AppState::MainScreen => {
+                let open = [Action::OpenPager, Action::OpenEditor, Action::OpenDefault]
+                    .map(|v| keymap.get_label(v));
+                directions.push((keymap.get_label(Action::Help), "help"));
+                directions.push((
+                    keymap.get_pair_label(Action::Down, Action::Up),
+                    "navigate list",
+                ));
+                directions.push((keymap.get_label(Action::Mark), "mark"));
+                directions.push((keymap.get_label(Action::Restore), "restore"));
+                directions.push((keymap.get_label(Action::Delete), "del"));
+                directions.push((keymap.get_label(Action::EmptyBin), "empty trash bin"));
+                directions.push((keymap.get_label(Action::Search), "search"));
+                directions.push((keymap.get_label(Action::Filter), "filter"));
+                directions.push((keymap.get_label(Action::Roots), "roots"));
+                directions.push((open.join("/"), "open"));
+                directions.push((keymap.get_label(Action::Quit), "quit"));
+                directions.push((keymap.get_label(Action::Sort), "sort"));
+            }
+            AppState::Browse => {
+                directions.push((keymap.get_pair_label(Action::Down, Action::Up), "navigate"));
+                directions.push((keymap.get_label(Action::Browse), "open directory"));
+                directions.push((keymap.get_label(Action::BrowseUp), "go up"));
+                directions.push((keymap.get_label(Action::Restore), "restore"));
+                directions.push((keymap.get_label(Action::FocusPreview), "focus preview"));
+                directions.push((keymap.get_label(Action::Back), "back to the trash"));
+            }
+            AppState::RestoreConfirmation(_)
+            | AppState::DeletionConfirmation(_)
+            | AppState::EmptyBinConfirmation(_)
+            | AppState::PurgeConfirmation(_)
+            | AppState::RestoreChildConfirmation(_) => {
+                push_confirmation_directions(&mut directions, keymap);
+            }
+            AppState::RestoreToDialog => {
+                directions.push(("tab".to_string(), "complete"));
+                directions.push(("enter".to_string(), "restore"));
+                directions.push(("ctrl+w/ctrl+u".to_string(), "delete word/all"));
+                directions.push(("esc".to_string(), "go back"));
+            }
+            AppState::SortListDialog(_) => {
+                directions.push((
+                    keymap.get_pair_label(Action::NextRow, Action::PreviousRow),
+                    "select",
+                ));
+                directions.push((keymap.get_label(Action::Confirm), "confirm selection"));
+                directions.push((keymap.get_label(Action::Cancel), "go back"));
+            }
+            AppState::FilterListDialog(_) => {
+                directions.push((
+                    keymap.get_pair_label(Action::NextRow, Action::PreviousRow),
+                    "select",
+                ));
+                directions.push((
+                    keymap.get_pair_label(Action::PreviousChoice, Action::NextChoice),
+                    "change",
+                ));
+                directions.push((keymap.get_label(Action::ClearFilters), "clear"));
+                directions.push((keymap.get_label(Action::Confirm), "apply"));
+                directions.push((keymap.get_label(Action::Cancel), "go back"));
+            }
+            AppState::BatchReport(_) => {
+                let back = [Action::Confirm, Action::Cancel].map(|v| keymap.get_label(v));
+                directions.push((
+                    keymap.get_pair_label(Action::NextRow, Action::PreviousRow),
+                    "scroll",
+                ));
+                directions.push((back.join("/"), "go back"));
+            }
+            AppState::Stats => {
+                let purges: Vec<String> = PURGE_OLDER_THAN_DAYS
+                    .iter()
+                    .map(|v| keymap.get_label(Action::PurgeOlderThan(*v)))
+                    .collect();
+                directions.push((
+                    keymap.get_pair_label(Action::NextRow, Action::PreviousRow),
+                    "scroll",
+                ));
+                directions.push((keymap.get_label(Action::PerWeek), "per day/week"));
+                directions.push((purges.join("/"), "purge"));
+                directions.push((keymap.get_label(Action::Cancel), "go back"));
+            }
+            AppState::ErrorLog | AppState::HelpScreen => {
+                directions.push((
+                    keymap.get_pair_label(Action::NextRow, Action::PreviousRow),
+                    "scroll",
+                ));
+                directions.push((keymap.get_label(Action::Cancel), "go back"));
+            }
+            _ => {}
+        }
+        directions
+    }
+
+    // the question of the open yes/no dialog, None if what it asks about is
+    // gone
+    fn get_confirmation_view(&self, area: Rect) -> Option<ConfirmationView> {
+        let plain = |v: &str| (v.to_string(), false);
+        let (title, question, area, choice) = match self.state {
+            AppState::RestoreConfirmation(choice) => {
+                let selection = self.get_selection();
+                let selection_size = self.get_selection_size(&selection);
+                let question = match selection[..] {
+                    [i] => {
+                        let file = self.trashed_files.get(i)?;
+                        vec![
+                            plain("This will restore "),
+                            (
+                                format!(
+                                    "'{}' ({selection_size}) ",
+                                    file.original_file
+                                        .file_name()
+                                        .unwrap_or_default()
+                                        .to_string_lossy(),
+                                ),
+                                true,
+                            ),
+                            plain("to "),
+                            (format!("'{}' ", file.original_file.display()), true),
+                            plain("?"),
+                        ]
+                    }
+                    _ => vec![
+                        plain("This will restore "),
+                        (
+                            format!("{} files ({selection_size}) ", selection.len()),
+                            true,
+                        ),
+                        plain("to their original paths?"),
+                    ],
+                };
+                (
+                    "Confirm Restore",
+                    question,
+                    popup_area(area, 40, 20),
+                    choice,
+                )
+            }
+
+            AppState::DeletionConfirmation(choice) => {
+                let selection = self.get_selection();
+                let selection_size = self.get_selection_size(&selection);
+                let target = match selection[..] {
+                    [i] => format!(
+                        "'{}' ({selection_size}) ",
+                        self.trashed_files
+                            .get(i)?
+                            .original_file
+                            .file_name()
+                            .unwrap_or_default()
+                            .to_string_lossy(),
+                    ),
+                    _ => format!("{} files ({selection_size}) ", selection.len()),
+                };
+                let question = vec![
+                    plain("This will permanently delete "),
+                    (target, true),
+                    plain(" forever?"),
+                ];
+                (
+                    "Confirm Deletion",
+                    question,
+                    popup_area(area, 40, 20),
+                    choice,
+                )
+            }
+
+            // room for the path of the root
+            AppState::EmptyBinConfirmation(choice) => match &self.empty_root {
+                None => (
+                    "Confirm Empty Bin",
+                    vec![plain(
+                        "This will permanently delete ALL files in the trash bin forever",
+                    )],
+                    popup_area(area, 30, 10),
+                    choice,
+                ),
+                Some(v) => (
+                    "Confirm Empty Bin",
+                    vec![
+                        plain("This will permanently delete ALL files in "),
+                        (format!("'{}'", v.display()), true),
+                        plain(" forever"),
+                    ],
+                    popup_area(area, 50, 15),
+                    choice,
+                ),
+            },
+
+            AppState::PurgeConfirmation(choice) => {
+                let stats = self.stats.as_ref()?;
+                let (count, size) = stats.get_purge_projection(self.purge_days, (self.now)());
+                let entries = match count {
+                    1 => "1 entry".to_string(),
+                    v => format!("{v} entries"),
+                };
+                let question = format!(
+                    "This will permanently delete the {entries} ({}) trashed more than {} days ago forever",
+                    size.map(format_size)
+                        .unwrap_or_else(|| "calculating…".to_string()),
+                    self.purge_days
+                );
+                (
+                    "Confirm Purge",
+                    vec![(question, false)],
+                    popup_area(area, 50, 15),
+                    choice,
+                )
+            }
+
+            AppState::RestoreChildConfirmation(choice) => {
+                let browser = self.browser.as_ref()?;
+                let child = browser.get_selected().unwrap_or_default();
+                let target = browser.trash_file.original_file.join(&child);
+                let dir_name = browser
+                    .trash_file
+                    .original_file
+                    .file_name()
+                    .unwrap_or_default()
+                    .to_string_lossy();
+                let question = vec![
+                    plain("This will restore "),
+                    (format!("'{}' ", child.display()), true),
+                    plain("to "),
+                    (format!("'{}'", target.display()), true),
+                    (
+                        format!(", the rest of '{dir_name}' stays in the trash?"),
+                        false,
+                    ),
+                ];
+                (
+                    "Confirm Restore",
+                    question,
+                    popup_area(area, 40, 20),
+                    choice,
+                )
+            }
+
+            _ => return None,
+        };
+
+        Some(ConfirmationView {
+            title,
+            question,
+            area,
+            choice,
+        })
+    }
+
+    // the restore to dialog, with where what's typed restores to
+    fn get_restore_to_view(&self, area: Rect) -> RestoreToView {
+        let file_name = self
+            .trashed_files
+            .get(self.selected)
+            .map(|f| f.original_file.file_name().unwrap_or_default())
+            .unwrap_or_default()
+            .to_string_lossy()
+            .to_string();
+        let target = match self.get_restore_target() {
+            Ok((target, false)) => Ok(format!("restores to '{}'", target.display())),
+            Ok((target, true)) => Ok(format!(
+                "restores to '{}', copied as it's on another filesystem",
+                target.display()
+            )),
+            Err(e) => Err(e),
+        };
+        RestoreToView {
+            file_name,
+            input: self.restore_to_input.clone(),
+            target,
+            completions: self.restore_to_completions.clone(),
+            area: popup_area(area, 60, 40),
+        }
+    }
+
+    // the figures of the stats screen, scrolled no further than its end
+    fn get_stats_view(&mut self, area: Rect) -> Option<StatsView> {
+        let stats = self.stats.as_ref()?;
+        let now = (self.now)();
+        let (histogram_title, buckets) = if self.stats_by_week {
+            ("Deletions per week", STATS_HISTOGRAM_WEEKS)
+        } else {
+            ("Deletions per day", STATS_HISTOGRAM_DAYS)
+        };
+        let dates: Vec<DateTime<Local>> = stats.entries.iter().map(|(v, _)| *v).collect();
+        let mut view = StatsView {
+            entry_count: stats.entries.len(),
+            total_size: stats.entries.iter().filter_map(|(_, v)| *v).sum(),
+            files: stats.files,
+            dirs: stats.dirs,
+            links: stats.links,
+            calculating: stats.calculating,
+            roots: self
+                .roots
+                .iter()
+                .map(|v| (v.size, v.count, v.trash_dir.home.clone()))
+                .collect(),
+            largest: stats.largest.clone(),
+            oldest: stats.oldest.clone(),
+            histogram_title,
+            histogram: get_deletion_histogram(&dates, now, self.stats_by_week, buckets),
+            purges: PURGE_OLDER_THAN_DAYS
+                .iter()
+                .map(|days| {
+                    let (count, size) = stats.get_purge_projection(*days, now);
+                    let key = self.keymap.get_label(Action::PurgeOlderThan(*days));
+                    (key, *days, count, size)
+                })
+                .collect(),
+            scroll: 0,
+            area: popup_area(area, 70, 80),
+        };
+
+        let line_count = get_stats_lines(&view, &self.theme).len();
+        self.stats_scroll = self.stats_scroll.min(line_count.saturating_sub(1));
+        view.scroll = self.stats_scroll;
+        Some(view)
+    }
+
+    // the shortcuts of the keymap in use, scrolled no further than their end
+    fn get_help_view(&mut self, area: Rect) -> HelpView {
+        let contexts = [
+            ("In the trash", KeyContext::Main),
+            ("Browsing a directory", KeyContext::Browse),
+            ("In the focused preview", KeyContext::Preview),
+            ("In the roots sidebar", KeyContext::Roots),
+            ("In the dialogs", KeyContext::Dialog),
+            ("In the stats screen", KeyContext::Stats),
+        ];
+        let mut sections: Vec<(&'static str, Vec<(String, &'static str)>)> = contexts
+            .into_iter()
+            .map(|(title, context)| {
+                let shortcuts = ACTIONS
+                    .iter()
+                    .filter(|(_, _, contexts, _)| contexts.contains(&context))
+                    .map(|(action, _, _, desc)| (self.keymap.get_label(*action), *desc))
+                    .filter(|(label, _)| !label.is_empty())
+                    .collect();
+                (title, shortcuts)
+            })
+            .collect();
+        sections.push((
+            "Everywhere",
+            FIXED_SHORTCUTS
+                .iter()
+                .map(|(shortcut, desc)| (shortcut.to_string(), *desc))
+                .collect(),
+        ));
+
+        let mut view = HelpView {
+            sections,
+            scroll: 0,
+            area: popup_area(area, 60, 60),
+        };
+        let line_count = get_help_lines(&view, &self.theme).len();
+        self.help_scroll = self.help_scroll.min(line_count.saturating_sub(1));
+        view.scroll = self.help_scroll;
+        view
+    }
+
+    // the roots with their entries, and all of them at the top, scrolled to
+    // the selected one
+    fn get_roots_view(&mut self, area: Rect) -> RootsView {
+        let total_count: usize = self.roots.iter().map(|v| v.count).sum();
+        let total_size: Option<u64> = self.roots.iter().map(|v| v.size).sum();
+        let mut rows = vec![RootRow {
+            name: "All roots".to_string(),
+            location: match self.roots.len() {
+                1 => "1 root".to_string(),
+                v => format!("{v} roots"),
+            },
+            count: total_count,
+            size: total_size,
+            is_scoped: self.filters.root.is_none(),
+        }];
+        for root in &self.roots {
+            rows.push(RootRow {
+                name: root.get_name(),
+                location: root.get_location(),
+                count: root.count,
+                size: root.size,
+                is_scoped: self.filters.root == Some(RootFilter::for_root(&root.trash_dir)),
+            });
+        }
+
+        let visible_rows = (area.height.saturating_sub(2) / LAYOUT_ROOT_HEIGHT).max(1) as usize;
+        self.roots_selected = self.roots_selected.min(self.roots.len());
+        if self.roots_selected < self.roots_scroll {
+            self.roots_scroll = self.roots_selected;
+        } else if self.roots_selected >= self.roots_scroll + visible_rows {
+            self.roots_scroll = self.roots_selected + 1 - visible_rows;
+        }
+
+        RootsView {
+            rows: rows
+                .into_iter()
+                .skip(self.roots_scroll)
+                .take(visible_rows)
+                .collect(),
+            selected: self.roots_selected - self.roots_scroll,
+            focused: self.roots_focused,
+        }
+    }
+
+    // another file's preview starts from the top
+    fn follow_preview(&mut self, path: Option<PathBuf>) {
+        if path != self.preview_entry {
+            self.preview_entry = path;
+            self.preview_scroll = 0;
+        }
+    }
+
+    // the main screen for the list and the preview areas. this is where
+    // what it shows is read from the filesystem
+    pub fn get_main_view(&mut self, list_area: Rect, preview_area: Rect) -> MainView {
+        let selected_entry = self
+            .trashed_files
+            .get(self.selected)
+            .and_then(|f| f.files_entry.clone());
+        self.follow_preview(selected_entry.clone());
+        self.max_visible_items = list_area.height.saturating_sub(2) as usize; // -2 for the border
+
+        let (preview, preview_position) = match &selected_entry {
+            Some(v) => self.get_preview(v, preview_area),
+            None => (Text::default(), None),
+        };
+        // for the right side title
+        let sort_label = match (self.sort_type, self.sort_reversed) {
+            (SortType::DeletionDate, false) => "[Deleted On ↑]",
+            (SortType::DeletionDate, true) => "[Deleted On ↓]",
+            (SortType::TrashRoot, false) => "[Original Path A-Z]",
+            (SortType::TrashRoot, true) => "[Original Path Z-A]",
+            (SortType::Size, false) => "[File Size ↑]",
+            (SortType::Size, true) => "[File Size ↓]",
+            (SortType::FileName, false) => "[File Name A-Z]",
+            (SortType::FileName, true) => "[File Name Z-A]",
+        };
+        let mut view = MainView {
+            title: String::new(),
+            sort_label,
+            empty_note: None,
+            rows: vec![],
+            selected: self.selected.saturating_sub(self.scroll_offset),
+            subtitle: None,
+            count: self.trashed_files.len(),
+            position: self.selected,
+            is_scrollable: self.trashed_files.len() > self.max_visible_items,
+            details: DetailsView {
+                description: vec![],
+                path_matches: vec![],
+                preview,
+                preview_position,
+                preview_focused: self.preview_focused,
+            },
+        };
+
+        // if empty bin, show kitty
+        if self.trashed_files.is_empty() {
+            let empty_note = r#"
+
+
+
+          |\      _,,,---,,_
+    ZZZzz /,`.-'`'    -.  ;-;;,_
+         |,4-  ) )-,_. ,\ (  `'-'
+        '---''(_/--'  `-'\_)
+                        "#;
+            if self.all_files.is_empty() {
+                view.empty_note = Some(empty_note.to_string());
+                view.title = " Files in Trash [Empty] ".to_string();
+            } else {
+                let mut conditions = self.filters.describe();
+                if !self.search.is_empty() {
+                    conditions.insert(0, format!("'{}'", self.search));
+                }
+                view.empty_note = Some(format!("\n no files match {}", conditions.join(", ")));
+                view.title = format!(" Files in Trash [0/{}] ", self.all_files.len());
+            }
+            return view;
+        }
+
+        let scroll_end =
+            (self.scroll_offset + self.max_visible_items).min(self.trashed_files.len());
+        view.rows = (self.scroll_offset..scroll_end)
+            .map(|i| {
+                let file = &self.trashed_files[i];
+                let name = file
+                    .original_file
+                    .file_name()
+                    .unwrap_or_default()
+                    .to_string_lossy()
+                    .to_string();
+                ListRow {
+                    // files matching by their path are highlighted in the
+                    // original path of the description instead
+                    matches: fuzzy_match(&self.search, &name).unwrap_or_default(),
+                    name,
+                    kind: EntryKind::for_path(file.files_entry.as_ref().unwrap()),
+                    is_external: file.trashroot.root_type != TrashRootType::Home,
+                    is_marked: self.is_marked(i),
+                }
+            })
+            .collect();
+
+        let file = &self.trashed_files[self.selected];
+        let files_entry = file.files_entry.as_ref().unwrap();
+        let size_display = self.get_size_display(files_entry);
+        // topdir entries are already resolved against the mount's top
+        // directory
+        // also replace home with ~
+        let original_path_display = match (&file.trashroot.root_type, get_home_dir()) {
+            (TrashRootType::Home, Ok(v)) => file
+                .original_file
+                .display()
+                .to_string()
+                .replace(v.display().to_string().as_str(), "~"),
+            _ => file.original_file.display().to_string(),
+        };
+        let trashinfo = file.trashinfo.as_ref().unwrap();
+
+        view.subtitle = match self.sort_type {
+            SortType::DeletionDate => {
+                let diff = (self.now)() - trashinfo.get_deletion_date();
+                Some(if diff.num_days() != 0 {
+                    format!("{} days ago", diff.num_days())
+                } else if diff.num_hours() != 0 {
+                    format!("{} hours ago", diff.num_hours())
+                } else if diff.num_minutes() != 0 {
+                    format!("{} minutes ago", diff.num_minutes())
+                } else {
+                    format!("{} seconds ago", diff.num_seconds())
+                })
+            }
+            SortType::TrashRoot if original_path_display.len() > LAYOUT_SUBTITLE_WIDTH => {
+                Some(format!(
+                    "{:>width$}..",
+                    &original_path_display[..LAYOUT_SUBTITLE_WIDTH - 2],
+                    width = LAYOUT_SUBTITLE_WIDTH - 2
+                ))
+            }
+            SortType::TrashRoot => Some(original_path_display.clone()),
+            SortType::Size => Some(size_display.clone()),
+            SortType::FileName => None,
+        };
+
+        view.details.path_matches =
+            fuzzy_match(&self.search, &original_path_display).unwrap_or_default();
+        view.details.description = vec![
+            ("Original path", original_path_display),
+            ("Deleted on", trashinfo.deletion_date.clone()),
+            ("File Type", get_file_type(files_entry)),
+            ("File Size", size_display),
+        ];
+
+        // summary of the marked files on top of the description
+        let marked_count = self.get_marked_count();
+        if marked_count > 0 {
+            let marked_size = self.get_selection_size(&self.get_selection());
+            view.details.description.splice(
+                0..0,
+                [
+                    ("Marked", format!("{marked_count} files, {marked_size}")),
+                    ("", String::new()),
+                ],
+            );
+        }
+
+        let marked_value = match (self.visual_anchor, marked_count) {
+            (Some(_), _) => format!("[{marked_count} marked, range] "),
+            (None, 0) => "".to_string(),
+            (None, _) => format!("[{marked_count} marked] "),
+        };
+        let search_value = match (self.search.is_empty(), self.filters.is_active()) {
+            (true, false) => "".to_string(),
+            (true, true) => format!("[filtered, of {}] ", self.all_files.len()),
+            (false, _) => format!("[/{} of {}] ", self.search, self.all_files.len()),
+        };
+        view.title = format!(
+            " Files in Trash [{}/{}] {search_value}{marked_value}",
+            self.selected + 1,
+            self.trashed_files.len(),
+        );
+        view
+    }
+
+    // the browse screen, like the main screen. None if nothing's browsed
+    fn get_browse_view(&mut self, list_area: Rect, preview_area: Rect) -> Option<BrowseView> {
+        self.max_visible_items = list_area.height.saturating_sub(2) as usize; // -2 for the border
+        let selected_entry = self
+            .browser
+            .as_ref()
+            .and_then(|b| b.get_selected().map(|v| b.get_files_entry(&v)));
+        self.follow_preview(selected_entry.clone());
+        if let Some(v) = &selected_entry {
+            self.request_size(v);
+        }
+
+        let (preview, preview_position) = match &selected_entry {
+            Some(v) => self.get_preview(v, preview_area),
+            None => (Text::default(), None),
+        };
+        let browser = self.browser.as_ref()?;
+
+        let scroll_end =
+            (browser.scroll_offset + self.max_visible_items).min(browser.entries.len());
+        let rows = browser.entries[browser.scroll_offset..scroll_end]
+            .iter()
+            .map(|entry| ListRow {
+                name: entry
+                    .file_name()
+                    .unwrap_or_default()
+                    .to_string_lossy()
+                    .to_string(),
+                kind: EntryKind::for_path(&browser.get_files_entry(entry)),
+                is_external: false,
+                is_marked: false,
+                matches: vec![],
+            })
+            .collect();
+
+        let dir_name = browser
+            .trash_file
+            .original_file
+            .file_name()
+            .unwrap_or_default()
+            .to_string_lossy();
+        // joining an empty path would add a trailing separator
+        let dir_path = if browser.path.as_os_str().is_empty() {
+            dir_name.to_string()
+        } else {
+            Path::new(&*dir_name)
+                .join(&browser.path)
+                .display()
+                .to_string()
+        };
+        let title = if browser.entries.is_empty() {
+            format!(" {dir_path} [Empty] ")
+        } else {
+            format!(
+                " {dir_path} [{}/{}] ",
+                browser.selected + 1,
+                browser.entries.len()
+            )
+        };
+
+        let mut description = vec![];
+        if let (Some(entry), Some(files_entry)) = (browser.get_selected(), &selected_entry) {
+            description = vec![
+                (
+                    "Restores to",
+                    browser
+                        .trash_file
+                        .original_file
+                        .join(&entry)
+                        .display()
+                        .to_string(),
+                ),
+                ("File Type", get_file_type(files_entry)),
+                ("File Size", self.get_size_display(files_entry)),
+            ];
+        }
+
+        Some(BrowseView {
+            title,
+            rows,
+            selected: browser.selected - browser.scroll_offset,
+            details: DetailsView {
+                description,
+                path_matches: vec![],
+                preview,
+                preview_position,
+                preview_focused: self.preview_focused,
+            },
+        })
+    }
+
+    // the summary of a binary file, the members of an archive, or the lines
+    // of a hex dump shown at the preview scroll, with the position in them
+    fn get_binary_preview(
+        &mut self,
+        path: &Path,
+        visible_lines: usize,
+        width: usize,
+    ) -> (Text<'static>, Option<String>) {
+        let label_style = Style::default()
+            .fg(self.get_color(ThemeColor::BoldText))
+            .add_modifier(Modifier::BOLD);
+        let value_style = Style::default().fg(self.get_color(ThemeColor::Text));
+        let dim_style = Style::default().fg(self.get_color(ThemeColor::SyntaxComment));
+        let err_message_style = Style::default()
+            .fg(self.get_color(ThemeColor::ErrorText))
+            .add_modifier(Modifier::ITALIC);
+
+        if self.binary_preview.as_ref().map(|v| v.path.as_path()) != Some(path) {
+            self.binary_preview = BinaryPreview::open(path).ok();
+        }
+        let Some(binary_preview) = self.binary_preview.as_ref() else {
+            return (Text::styled("couldn't read file", err_message_style), None);
+        };
+
+        // the summary, then the members or the hex dump after a blank line
+        let mut head: Vec<Line<'static>> = binary_preview
+            .summary
+            .iter()
+            .map(|(label, value)| {
+                Line::from(vec![
+                    Span::styled(format!("{label}: "), label_style),
+                    Span::styled(value.clone(), value_style),
+                ])
+            })
+            .collect();
+        if !head.is_empty() {
+            head.push(Line::default());
+        }
+        let bytes_per_line = BinaryPreview::hex_dump_width(width);
+        let body_len = if binary_preview.hex_dump {
+            binary_preview.len.div_ceil(bytes_per_line as u64) as usize
+        } else {
+            binary_preview.members.len()
+        };
+
+        let line_count = head.len() + body_len;
+        self.preview_scroll = self
+            .preview_scroll
+            .min(line_count.saturating_sub(visible_lines));
+        let first = self.preview_scroll;
+        let last = (first + visible_lines).min(line_count);
+
+        let mut lines: Vec<Line> = head.into_iter().skip(first).take(last - first).collect();
+        let body_first = first.saturating_sub(line_count - body_len);
+        let body_last = last.saturating_sub(line_count - body_len);
+        if binary_preview.hex_dump {
+            let offset = (body_first * bytes_per_line) as u64;
+            let mut bytes = vec![];
+            let read = File::open(path).and_then(|mut f| {
+                f.seek(SeekFrom::Start(offset))?;
+                f.take(((body_last - body_first) * bytes_per_line) as u64)
+                    .read_to_end(&mut bytes)
+            });
+            if read.is_err() {
+                return (Text::styled("couldn't read file", err_message_style), None);
+            }
+            for (i, chunk) in bytes.chunks(bytes_per_line).enumerate() {
+                let (hex, ascii) = format_hex_line(chunk, bytes_per_line);
+                lines.push(Line::from(vec![
+                    Span::styled(
+                        format!("{:08x}  ", offset as usize + i * bytes_per_line),
+                        dim_style,
+                    ),
+                    Span::styled(hex, value_style),
+                    Span::styled(ascii, label_style),
+                ]));
+            }
+        } else {
+            for (size, name) in &binary_preview.members[body_first..body_last] {
+                lines.push(Line::from(vec![
+                    Span::styled(format!("{:>6} ", format_size(*size)), dim_style),
+                    Span::styled(name.clone(), value_style),
+                ]));
+            }
+        }
+
+        (
+            Text::from(lines),
+            Some(format!("{}-{last}/{line_count}", first + 1)),
+        )
+    }
+
+    // the lines of a text file shown at the preview scroll, read as far as
+    // needed, with the position in the file
+    fn get_text_preview(
+        &mut self,
+        path: &Path,
+        visible_lines: usize,
+        width: usize,
+    ) -> (Text<'static>, Option<String>) {
+        let err_message_style = Style::default()
+            .fg(self.get_color(ThemeColor::ErrorText))
+            .add_modifier(Modifier::ITALIC);
+
+        if self.text_preview.as_ref().map(|v| v.path.as_path()) != Some(path) {
+            self.text_preview = TextPreview::open(path).ok();
+        }
+        let Some(text_preview) = self.text_preview.as_mut() else {
+            return (Text::styled("couldn't read file", err_message_style), None);
+        };
+
+        // one more line than shown, to tell if there's more
+        text_preview.read_to(self.preview_scroll.saturating_add(visible_lines + 1));
+        if text_preview.is_binary {
+            return self.get_binary_preview(path, visible_lines, width);
+        }
+        if text_preview.lines.is_empty() {
+            return (Text::styled("couldn't read file", err_message_style), None);
+        }
+
+        let line_count = text_preview.lines.len();
+        self.preview_scroll = self
+            .preview_scroll
+            .min(line_count.saturating_sub(visible_lines));
+        let first = self.preview_scroll;
+        let last = (first + visible_lines).min(line_count);
+        let more = if text_preview.reader.is_some() {
+            "+"
+        } else {
+            ""
+        };
+        let tokens = text_preview.lines[first..last].to_vec();
+
+        let lines: Vec<Line> = tokens
+            .into_iter()
+            .map(|line| {
+                Line::from(
+                    line.into_iter()
+                        .map(|(token, text)| {
+                            let style =
+                                match token {
+                                    SyntaxToken::Plain => {
+                                        Style::default().fg(self.get_color(ThemeColor::Text))
+                                    }
+                                    SyntaxToken::Keyword => Style::default()
+                                        .fg(self.get_color(ThemeColor::SyntaxKeyword))
+                                        .add_modifier(Modifier::BOLD),
+                                    SyntaxToken::String => Style::default()
+                                        .fg(self.get_color(ThemeColor::SyntaxString)),
+                                    SyntaxToken::Comment => Style::default()
+                                        .fg(self.get_color(ThemeColor::SyntaxComment))
+                                        .add_modifier(Modifier::ITALIC),
+                                    SyntaxToken::Number => Style::default()
+                                        .fg(self.get_color(ThemeColor::SyntaxNumber)),
+                                };
+                            Span::styled(text, style)
+                        })
+                        .collect::<Vec<Span>>(),
+                )
+            })
+            .collect();
+
+        (
+            Text::from(lines),
+            Some(format!("{}-{last}/{line_count}{more}", first + 1)),
+        )
+    }
+
+    // preview of a file, symlink, or directory from the preview scroll. text
+    // files and hex dumps are read as far as they're scrolled, and the
+    // position in them is returned for the title
+    fn get_preview(&mut self, path: &Path, area: Rect) -> (Text<'static>, Option<String>) {
+        // -4 for the border and the padding
+        let visible_lines = area.height.saturating_sub(4) as usize;
+        let width = area.width.saturating_sub(4) as usize;
+        if !path.is_symlink()
+            && path.is_file()
+            && !matches!(path.metadata().map(|m| m.len()), Ok(0))
+        {
+            return if read_file_magic(path).is_some() {
+                self.get_binary_preview(path, visible_lines, width)
+            } else {
+                self.get_text_preview(path, visible_lines, width)
+            };
+        }
+
+        let message_style = Style::default()
+            .fg(self.get_color(ThemeColor::Text))
+            .add_modifier(Modifier::ITALIC);
+        let err_message_style = Style::default()
+            .fg(self.get_color(ThemeColor::ErrorText))
+            .add_modifier(Modifier::ITALIC);
+        let mut preview = if path.is_symlink() {
+            match fs::read_link(path) {
+                Ok(target_path) => {
+                    let target_path_str = target_path.to_string_lossy().to_string();
+                    Text::from(vec![Line::from(vec![
+                        Span::styled(
+                            "original target: ",
+                            Style::default()
+                                .add_modifier(Modifier::BOLD)
+                                .fg(self.get_color(ThemeColor::Text)),
+                        ),
+                        Span::styled(
+                            target_path_str,
+                            Style::default().fg(self.get_color(ThemeColor::BoldText)),
+                        ),
+                    ])])
+                }
+                Err(_e) => Text::styled("couldn't read link", err_message_style),
+            }
+        } else if path.is_dir() {
+            let mut lines = vec![];
+            let entries = read_dir(path).and_then(|v| {
+                v.map(|res| res.map(|e| e.path()))
+                    .collect::<Result<Vec<_>, io::Error>>()
+            });
+            match entries {
+                Err(e) => Text::styled(format!("couldn't read directory: {e}"), err_message_style),
+                Ok(entries) => {
+                    let item_count = entries.len();
+                    if item_count == 0 {
+                        lines.push(Line::from(vec![Span::styled(
+                            "empty directory",
+                            message_style,
+                        )]));
+                    } else {
+                        // show a tree -L 1 output
+                        lines.push(Line::styled("directory contents", message_style));
+                        lines.push(Line::from("."));
+                        for (i, entry) in entries.into_iter().enumerate() {
+                            let indicator = if i + 1 < item_count {
+                                Span::styled("├── ", Style::default())
+                            } else {
+                                Span::styled("└── ", Style::default())
+                            };
+                            let item = if entry.is_symlink() {
+                                Span::styled(
+                                    entry
+                                        .file_name()
+                                        .unwrap_or_default()
+                                        .to_string_lossy()
+                                        .to_string(),
+                                    Style::default()
+                                        .fg(self.get_color(ThemeColor::UnselectedFGLink)),
+                                )
+                            } else if entry.is_dir() {
+                                Span::styled(
+                                    entry
+                                        .file_name()
+                                        .unwrap_or_default()
+                                        .to_string_lossy()
+                                        .to_string(),
+                                    Style::default().fg(self.get_color(ThemeColor::SelectedFGDir)),
+                                )
+                            } else {
+                                Span::styled(
+                                    entry
+                                        .file_name()
+                                        .unwrap_or_default()
+                                        .to_string_lossy()
+                                        .to_string(),
+                                    Style::default()
+                                        .fg(self.get_color(ThemeColor::UnselectedFGFile)),
+                                )
+                            };
+                            lines.push(Line::from(vec![indicator, item]));
+                        }
+                    }
+                    Text::from(lines)
+                }
+            }
+        } else if path.is_file() {
+            Text::styled("empty file", message_style)
+        } else {
+            Text::styled("unknown file type", err_message_style)
+        };
+
+        // everything is there, scroll up to the end
+        self.preview_scroll = self
+            .preview_scroll
+            .min(preview.lines.len().saturating_sub(visible_lines));
+        preview.lines.drain(..self.preview_scroll);
+        (preview, None)
+    }
+
+    // select color based on the current theme
+    fn get_color(&self, color: ThemeColor) -> Color {
+        self.theme.get(color)
+    }
+}
+
+// directions of the yes/no dialogs in the footer
+fn push_confirmation_directions(directions: &mut Vec<(String, &'static str)>, keymap: &Keymap) {
+    directions.push((
+        keymap.get_pair_label(Action::PreviousChoice, Action::NextChoice),
+        "select",
+    ));
+    directions.push((keymap.get_label(Action::Confirm), "confirm selection"));
+    directions.push((keymap.get_label(Action::Cancel), "go back"));
+}
+
+// directions of the focused preview in the footer
+fn push_preview_directions(directions: &mut Vec<(String, &'static str)>, keymap: &Keymap) {
+    directions.push((
+        keymap.get_pair_label(Action::ScrollDown, Action::ScrollUp),
+        "scroll",
+    ));
+    directions.push((
+        keymap.get_pair_label(Action::PageDown, Action::PageUp),
+        "page",
+    ));
+    directions.push((
+        keymap.get_pair_label(Action::ScrollTop, Action::ScrollBottom),
+        "top/bottom",
+    ));
+    directions.push((keymap.get_label(Action::FocusList), "back to the list"));
+}
@@ -0,0 +1,477 @@
+use crate::app::BatchAction;
+use crate::filters::{SortType, SORT_TYPES};
+use crate::sizes::format_size;
+use crate::stats::STATS_HISTOGRAM_WIDTH;
+use crate::theme::{Theme, ThemeColor};
+use crate::{BINARY_NAME, BINARY_VERSION};
+use chrono::{DateTime, Local};
+use ratatui::layout::{Alignment, Margin, Rect};
+use ratatui::style::{Modifier, Style};
+use ratatui::text::{Line, Span};
+use ratatui::widgets::{Block, Clear, Padding, Paragraph, Wrap};
+use ratatui::Frame;
+use std::path::PathBuf;
+
+// the buttons of the yes/no dialogs, in the order of their choices
+pub const CONFIRMATION_BUTTONS: [&str; 2] = ["[Confirm]", "[Cancel]"];
+
+// a yes/no dialog
+pub struct ConfirmationView {
+    pub title: &'static str,
+    pub question: Vec<(String, bool)>, // parts of the question, bold or not
+    pub area: Rect,
+    pub choice: usize,
+}
+
+// the restore to dialog
+pub struct RestoreToView {
+    pub file_name: String,
+    pub input: String,
+    pub target: Result<String, String>, // where the input restores to, or why it can't
+    pub completions: Vec<String>,       // of the last tab, if more than one
+    pub area: Rect,
+}
+
+pub struct SortView {
+    pub sort_type: SortType, // in use
+    pub choice: SortType,
+    pub area: Rect,
+}
+
+pub struct FilterView {
+    pub rows: Vec<(&'static str, String)>, // label, value of the draft
+    pub selected: usize,
+    pub area: Rect,
+}
+
+// the outcome of a batch restore or deletion
+pub struct ReportView {
+    pub action: BatchAction,
+    pub report: Vec<(String, Result<String, String>)>,
+    pub scroll: usize,
+    pub area: Rect,
+}
+
+pub struct StatsView {
+    pub entry_count: usize,
+    pub total_size: u64,
+    pub files: usize,
+    pub dirs: usize,
+    pub links: usize,
+    pub calculating: usize,
+    pub roots: Vec<(Option<u64>, usize, PathBuf)>, // size, entries, home
+    pub largest: Vec<(u64, PathBuf)>,
+    pub oldest: Vec<(DateTime<Local>, PathBuf)>,
+    pub histogram_title: &'static str,
+    pub histogram: Vec<(String, usize)>,
+    pub purges: Vec<(String, i64, usize, Option<u64>)>, // key, older than days, entries, size
+    pub scroll: usize,
+    pub area: Rect,
+}
+
+pub struct ErrorLogView {
+    pub entries: Vec<(String, String)>, // time, message
+    pub scroll: usize,
+    pub area: Rect,
+}
+
+pub struct HelpView {
+    pub sections: Vec<(&'static str, Vec<(String, &'static str)>)>, // title, shortcuts
+    pub scroll: usize,
+    pub area: Rect,
+}
+
+// a dialog box over the screen
+fn get_dialog_block(title: &'static str, theme: &Theme) -> Block<'static> {
+    Block::bordered()
+        .title(Span::styled(
+            title,
+            theme.get_dialog_text_style().add_modifier(Modifier::BOLD),
+        ))
+        .style(theme.get_dialog_style())
+}
+
+pub fn render_confirmation(f: &mut Frame, view: &ConfirmationView, theme: &Theme) {
+    let text_style = theme.get_dialog_text_style();
+    let question: Vec<Span> = view
+        .question
+        .iter()
+        .map(|(text, is_bold)| match is_bold {
+            true => Span::styled(text.clone(), text_style.add_modifier(Modifier::BOLD)),
+            false => Span::styled(text.clone(), text_style),
+        })
+        .collect();
+    let dialog = Paragraph::new(Line::from(question))
+        .wrap(Wrap { trim: false })
+        .alignment(Alignment::Center)
+        .block(get_dialog_block(view.title, theme));
+    f.render_widget(Clear, view.area);
+    f.render_widget(dialog, view.area);
+    render_buttons(f, view.area, view.choice, &CONFIRMATION_BUTTONS, theme);
+}
+
+// buttons centered on the last line inside a dialog, the current choice
+// highlighted
+fn render_buttons(f: &mut Frame, area: Rect, choice: usize, labels: &[&str], theme: &Theme) {
+    for (i, (label, button_area)) in labels
+        .iter()
+        .zip(get_button_areas(area, labels))
+        .enumerate()
+    {
+        let style = if i == choice {
+            theme.get_button_style()
+        } else {
+            Style::default()
+        };
+        f.render_widget(Paragraph::new(Span::styled(*label, style)), button_area);
+    }
+}
+
+// where the buttons of a dialog go, for drawing them and for the mouse
+pub fn get_button_areas(area: Rect, labels: &[&str]) -> Vec<Rect> {
+    // space between buttons
+    let spacer_width = 6;
+
+    let inner = area.inner(Margin::new(1, 1));
+    let width: usize =
+        labels.iter().map(|v| v.chars().count()).sum::<usize>() + spacer_width * (labels.len() - 1);
+    let mut x = inner.x + inner.width.saturating_sub(width as u16) / 2;
+    let y = inner.bottom().saturating_sub(1);
+    let mut areas = vec![];
+    for label in labels {
+        let label_width = label.chars().count() as u16;
+        areas.push(Rect::new(x, y, label_width, 1).intersection(inner));
+        x += label_width + spacer_width as u16;
+    }
+    areas
+}
+
+pub fn render_restore_to(f: &mut Frame, view: &RestoreToView, theme: &Theme) {
+    let text_style = theme.get_dialog_text_style();
+    let block = get_dialog_block("Restore To", theme).padding(Padding::new(2, 2, 1, 1));
+    let inner = block.inner(view.area);
+
+    let status = match &view.target {
+        Ok(v) => Line::styled(v.clone(), text_style),
+        Err(e) => Line::styled(e.clone(), text_style.fg(theme.get(ThemeColor::ErrorText))),
+    };
+    let input = format!("> {}", view.input);
+    let mut lines = vec![
+        Line::from(vec![
+            Span::styled("Restore ", text_style),
+            Span::styled(
+                format!("'{}'", view.file_name),
+                text_style.add_modifier(Modifier::BOLD),
+            ),
+            Span::styled(" to a file or directory path", text_style),
+        ]),
+        Line::default(),
+        Line::styled(input.clone(), text_style.add_modifier(Modifier::BOLD)),
+        Line::default(),
+        status,
+    ];
+    if !view.completions.is_empty() {
+        lines.push(Line::default());
+        lines.push(Line::styled(
+            view.completions.join("  "),
+            text_style.add_modifier(Modifier::ITALIC),
+        ));
+    }
+
+    let dialog = Paragraph::new(lines)
+        .wrap(Wrap { trim: false })
+        .block(block);
+    f.render_widget(Clear, view.area);
+    f.render_widget(dialog, view.area);
+    // the input wraps at the width of the dialog
+    let input_width = input.chars().count() as u16;
+    if inner.width > 0 {
+        f.set_cursor_position((
+            inner.x + input_width % inner.width,
+            inner.y + 2 + input_width / inner.width,
+        ));
+    }
+}
+
+// the sort types, the one in use checked
+pub fn render_sort(f: &mut Frame, view: &SortView, theme: &Theme) {
+    let selected_style = theme.get_button_style();
+    let mut lines = vec![Line::from("Select sort by column"), Line::default()];
+    let labels = [" Deleted on", " Origin    ", " Size      ", " File Name "];
+    for (sort_type, label) in SORT_TYPES.iter().zip(labels) {
+        let check_mark = if *sort_type == view.sort_type {
+            Span::styled("[x]", selected_style)
+        } else {
+            Span::from("[ ]")
+        };
+        let label = if *sort_type == view.choice {
+            Span::styled(label, selected_style)
+        } else {
+            Span::from(label)
+        };
+        lines.push(Line::from(vec![check_mark, label]));
+    }
+
+    let dialog = Paragraph::new(lines)
+        .wrap(Wrap { trim: false })
+        .alignment(Alignment::Center)
+        .block(get_dialog_block("Sort Files By", theme));
+    f.render_widget(Clear, view.area);
+    f.render_widget(dialog, view.area);
+}
+
+pub fn render_filter(f: &mut Frame, view: &FilterView, theme: &Theme) {
+    let mut lines = vec![Line::from("Show only the files matching"), Line::default()];
+    for (i, (label, value)) in view.rows.iter().enumerate() {
+        let row_style = if i == view.selected {
+            theme.get_button_style()
+        } else {
+            Style::default()
+        };
+        lines.push(Line::from(vec![
+            Span::styled(format!(" {label:<15}"), row_style),
+            Span::styled(format!("◂ {value} ▸ "), row_style),
+        ]));
+    }
+
+    let block = get_dialog_block("Filter Files", theme).padding(Padding::new(2, 2, 1, 1));
+    let dialog = Paragraph::new(lines)
+        .wrap(Wrap { trim: false })
+        .block(block);
+    f.render_widget(Clear, view.area);
+    f.render_widget(dialog, view.area);
+}
+
+// a line for each file, with how it went
+pub fn render_report(f: &mut Frame, view: &ReportView, theme: &Theme) {
+    let text_style = theme.get_dialog_text_style();
+    let bold_style = text_style.add_modifier(Modifier::BOLD);
+    let (title, done) = match view.action {
+        BatchAction::Restore => ("Restore Results", "restored"),
+        BatchAction::Delete => ("Deletion Results", "deleted"),
+    };
+
+    let succeeded = view.report.iter().filter(|(_, r)| r.is_ok()).count();
+    let mut lines = vec![
+        Line::styled(
+            format!("{succeeded} of {} files {done}", view.report.len()),
+            bold_style,
+        ),
+        Line::default(),
+    ];
+    for (file_name, result) in &view.report {
+        let (status, outcome) = match result {
+            Ok(v) => ("✔ ", v),
+            Err(e) => ("✘ ", e),
+        };
+        lines.push(Line::from(vec![
+            Span::styled(status, bold_style),
+            Span::styled(file_name.clone(), bold_style),
+            Span::styled(" - ", text_style),
+            Span::styled(outcome.clone(), text_style.add_modifier(Modifier::ITALIC)),
+        ]));
+    }
+
+    let block = get_dialog_block(title, theme).padding(Padding::new(2, 2, 1, 1));
+    let report = Paragraph::new(lines)
+        .wrap(Wrap { trim: false })
+        .scroll((view.scroll as u16, 0))
+        .block(block);
+    f.render_widget(Clear, view.area);
+    f.render_widget(report, view.area);
+}
+
+// the lines of the stats screen, counted to keep the scroll in them
+pub fn get_stats_lines(view: &StatsView, theme: &Theme) -> Vec<Line<'static>> {
+    let text_style = theme.get_dialog_text_style();
+    let heading_style = text_style.add_modifier(Modifier::BOLD);
+    let mut lines = vec![];
+
+    lines.push(Line::styled("Overall", heading_style));
+    lines.push(Line::styled(
+        format!(
+            "  {} entries · {}",
+            view.entry_count,
+            format_size(view.total_size)
+        ),
+        text_style,
+    ));
+    lines.push(Line::styled(
+        format!(
+            "  {} files · {} directories · {} symlinks",
+            view.files, view.dirs, view.links
+        ),
+        text_style,
+    ));
+    if view.calculating > 0 {
+        let entries = match view.calculating {
+            1 => "1 entry".to_string(),
+            v => format!("{v} entries"),
+        };
+        lines.push(Line::styled(
+            format!("  calculating the size of {entries}…"),
+            text_style.add_modifier(Modifier::ITALIC),
+        ));
+    }
+    lines.push(Line::default());
+
+    lines.push(Line::styled("Per root", heading_style));
+    for (size, count, home) in &view.roots {
+        lines.push(Line::styled(
+            format!(
+                "  {:<12} {:>6} entries  {}",
+                size.map(format_size)
+                    .unwrap_or_else(|| "calculating…".to_string()),
+                count,
+                home.display()
+            ),
+            text_style,
+        ));
+    }
+    lines.push(Line::default());
+
+    lines.push(Line::styled("Largest", heading_style));
+    for (size, path) in &view.largest {
+        lines.push(Line::styled(
+            format!("  {:<10} {}", format_size(*size), path.display()),
+            text_style,
+        ));
+    }
+    lines.push(Line::default());
+
+    lines.push(Line::styled("Oldest", heading_style));
+    for (date, path) in &view.oldest {
+        lines.push(Line::styled(
+            format!("  {} {}", date.format("%Y-%m-%d"), path.display()),
+            text_style,
+        ));
+    }
+    lines.push(Line::default());
+
+    lines.push(Line::styled(view.histogram_title, heading_style));
+    let max_count = view
+        .histogram
+        .iter()
+        .map(|(_, v)| *v)
+        .max()
+        .unwrap_or(0)
+        .max(1);
+    for (label, count) in &view.histogram {
+        let bar = "█".repeat(count * STATS_HISTOGRAM_WIDTH / max_count);
+        lines.push(Line::from(vec![
+            Span::styled(format!("  {label} "), text_style),
+            Span::styled(bar, text_style.fg(theme.get(ThemeColor::BoldText))),
+            Span::styled(format!(" {count}"), text_style),
+        ]));
+    }
+    lines.push(Line::default());
+
+    lines.push(Line::styled("Purge", heading_style));
+    for (key, days, count, size) in &view.purges {
+        lines.push(Line::from(vec![
+            Span::styled(format!("  [{key}] "), heading_style),
+            Span::styled(
+                format!(
+                    "older than {days} days: {}, {}",
+                    match count {
+                        1 => "1 entry".to_string(),
+                        v => format!("{v} entries"),
+                    },
+                    match size {
+                        Some(v) => format!("{} freed", format_size(*v)),
+                        None => "calculating…".to_string(),
+                    }
+                ),
+                text_style,
+            ),
+        ]));
+    }
+    lines
+}
+
+pub fn render_stats(f: &mut Frame, view: &StatsView, theme: &Theme) {
+    let block = get_dialog_block("Trash Statistics", theme).padding(Padding::new(2, 2, 1, 1));
+    let paragraph = Paragraph::new(get_stats_lines(view, theme))
+        .wrap(Wrap { trim: false })
+        .scroll((view.scroll as u16, 0))
+        .block(block);
+    f.render_widget(Clear, view.area);
+    f.render_widget(paragraph, view.area);
+}
+
+pub fn render_error_log(f: &mut Frame, view: &ErrorLogView, theme: &Theme) {
+    let text_style = theme.get_dialog_text_style();
+    let mut lines = vec![];
+    if view.entries.is_empty() {
+        lines.push(Line::styled(
+            "no errors so far",
+            text_style.add_modifier(Modifier::ITALIC),
+        ));
+    }
+    for (time, message) in &view.entries {
+        lines.push(Line::from(vec![
+            Span::styled(format!("{time} "), text_style.add_modifier(Modifier::BOLD)),
+            Span::styled(message.clone(), text_style),
+        ]));
+    }
+
+    let block = get_dialog_block("Error Log", theme).padding(Padding::new(2, 2, 1, 1));
+    let log = Paragraph::new(lines)
+        .wrap(Wrap { trim: false })
+        .scroll((view.scroll as u16, 0))
+        .block(block);
+    f.render_widget(Clear, view.area);
+    f.render_widget(log, view.area);
+}
+
+// the lines of the help screen, counted to keep the scroll in them
+pub fn get_help_lines(view: &HelpView, theme: &Theme) -> Vec<Line<'static>> {
+    let text_style = theme.get_dialog_text_style();
+    let empty_line = Line::default();
+    let shortcut_style = text_style.add_modifier(Modifier::BOLD);
+    let dash = Span::from(" - ");
+    let desc_style = text_style.add_modifier(Modifier::ITALIC);
+
+    let mut lines = vec![
+        Line::from(format!("{BINARY_NAME} is a freedesktop.org Trash Specification implementation written in Rust. Current version is {BINARY_VERSION}.")),
+        Line::from(format!("{BINARY_NAME} is an Open Source tool licensed under Apache License v2.")),
+        empty_line.clone(),
+        Line::from("http://www.apache.org/licenses/LICENSE-2.0"),
+        empty_line.clone(),
+        Line::styled("Unless required by applicable law or agreed to in writing, software distributed under the License is distributed on an \"AS IS\" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied. See the License for the specific language governing permissions and limitations under the License.", desc_style),
+        empty_line.clone(),
+        Line::from(vec![
+            Span::from("Please report any issues to "),
+            Span::styled("https://github.com/chamilad/trash-rs", shortcut_style),
+        ]),
+        empty_line.clone(),
+        empty_line.clone(),
+        Line::styled("Keyboard Shortcuts [Case Sensitive]", shortcut_style),
+        Line::styled("-----------------------------------", shortcut_style),
+        empty_line.clone(),
+    ];
+    for (i, (title, shortcuts)) in view.sections.iter().enumerate() {
+        if i > 0 {
+            lines.push(empty_line.clone());
+        }
+        lines.push(Line::styled(*title, shortcut_style));
+        for (shortcut, desc) in shortcuts {
+            lines.push(Line::from(vec![
+                Span::styled(format!("  {shortcut:<13}"), shortcut_style),
+                dash.clone(),
+                Span::styled(*desc, desc_style),
+            ]));
+        }
+    }
+    lines
+}
+
+pub fn render_help(f: &mut Frame, view: &HelpView, theme: &Theme) {
+    let block = get_dialog_block("Help", theme).padding(Padding::new(2, 2, 2, 1));
+    let shortcuts = Paragraph::new(get_help_lines(view, theme))
+        .wrap(Wrap { trim: false })
+        .scroll((view.scroll as u16, 0))
+        .block(block);
+    f.render_widget(Clear, view.area);
+    f.render_widget(shortcuts, view.area);
+}
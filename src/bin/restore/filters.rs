@@ -0,0 +1,325 @@
+use crate::sizes::format_size;
+use chrono::Local;
+use libtrash::*;
+use std::cmp::Ordering::Equal;
+use std::collections::HashMap;
+use std::path::PathBuf;
+
+// in the order of the sort dialog
+pub const SORT_TYPES: [SortType; 4] = [
+    SortType::DeletionDate,
+    SortType::TrashRoot,
+    SortType::Size,
+    SortType::FileName,
+];
+
+// filter dialog choices, other than off
+const FILTER_LARGER_THAN: &[u64] = &[1000000, 10000000, 100000000, 1000000000];
+
+const FILTER_DELETED_WITHIN_DAYS: &[i64] = &[1, 7, 30];
+
+pub const FILTER_ROWS: usize = 3;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum SortType {
+    DeletionDate,
+    TrashRoot,
+    Size,
+    FileName,
+    // FileType,
+}
+
+#[derive(Clone, PartialEq)]
+pub enum RootFilter {
+    Home,
+    External,
+    TopDir(PathBuf), // a specific mount
+}
+
+impl RootFilter {
+    // the filter showing the files of a trash root, as chosen in the roots
+    // sidebar
+    pub fn for_root(trash_dir: &TrashDirectory) -> Self {
+        match trash_dir.get_top_dir() {
+            Some(v) => RootFilter::TopDir(v),
+            None => RootFilter::Home,
+        }
+    }
+}
+
+// filters on top of the search, None means off
+#[derive(Clone, Default, PartialEq)]
+pub struct Filters {
+    pub original_dir: Option<PathBuf>, // trashed from under, from the command line
+    pub root: Option<RootFilter>,
+    larger_than: Option<u64>,
+    deleted_within_days: Option<i64>,
+}
+
+impl Filters {
+    pub fn is_active(&self) -> bool {
+        *self != Filters::default()
+    }
+
+    // a size not known yet doesn't match a size filter
+    pub fn matches(&self, trash_file: &TrashFile, size: Option<u64>) -> bool {
+        let dir_matches = match &self.original_dir {
+            None => true,
+            Some(v) => trash_file.original_file.starts_with(v),
+        };
+
+        let root_matches = match &self.root {
+            None => true,
+            Some(RootFilter::Home) => trash_file.trashroot.root_type == TrashRootType::Home,
+            Some(RootFilter::External) => trash_file.trashroot.root_type != TrashRootType::Home,
+            Some(RootFilter::TopDir(v)) => trash_file.trashroot.get_top_dir().as_ref() == Some(v),
+        };
+
+        let size_matches = match self.larger_than {
+            None => true,
+            Some(v) => size.unwrap_or(0) > v,
+        };
+
+        let date_matches = match (self.deleted_within_days, &trash_file.trashinfo) {
+            (None, _) => true,
+            (Some(v), Some(trashinfo)) => {
+                (Local::now() - trashinfo.get_deletion_date()).num_days() < v
+            }
+            (Some(_), None) => false,
+        };
+
+        dir_matches && root_matches && size_matches && date_matches
+    }
+
+    // labels of the rows in the filter dialog, and the current values
+    pub fn describe_rows(&self) -> [(&'static str, String); FILTER_ROWS] {
+        let root = match &self.root {
+            None => "any trash".to_string(),
+            Some(RootFilter::Home) => "home trash".to_string(),
+            Some(RootFilter::External) => "external mounts".to_string(),
+            Some(RootFilter::TopDir(v)) => v.display().to_string(),
+        };
+        let larger_than = match self.larger_than {
+            None => "any size".to_string(),
+            Some(v) => format_size(v),
+        };
+        let deleted_within = match self.deleted_within_days {
+            None => "any time".to_string(),
+            Some(1) => "1 day".to_string(),
+            Some(v) => format!("{v} days"),
+        };
+
+        [
+            ("Trash root", root),
+            ("Larger than", larger_than),
+            ("Deleted within", deleted_within),
+        ]
+    }
+
+    // active filters only, for the title bar
+    pub fn describe(&self) -> Vec<String> {
+        let rows = self.describe_rows();
+        let mut active = vec![];
+        if let Some(v) = &self.original_dir {
+            active.push(format!("under {}", v.display()));
+        }
+        if self.root.is_some() {
+            active.push(rows[0].1.clone());
+        }
+        if self.larger_than.is_some() {
+            active.push(format!("> {}", rows[1].1));
+        }
+        if self.deleted_within_days.is_some() {
+            active.push(format!("last {}", rows[2].1));
+        }
+        active
+    }
+
+    // move the value of a row to the next or previous choice, going through
+    // off at the ends
+    pub fn cycle(&mut self, row: usize, forward: bool, top_dirs: &[PathBuf]) {
+        match row {
+            0 => {
+                let mut choices = vec![RootFilter::Home, RootFilter::External];
+                choices.extend(top_dirs.iter().cloned().map(RootFilter::TopDir));
+                self.root = cycle_choice(&choices, &self.root, forward);
+            }
+            1 => self.larger_than = cycle_choice(FILTER_LARGER_THAN, &self.larger_than, forward),
+            _ => {
+                self.deleted_within_days = cycle_choice(
+                    FILTER_DELETED_WITHIN_DAYS,
+                    &self.deleted_within_days,
+                    forward,
+                )
+            }
+        }
+    }
+}
+
+// sort a given vector of files based on the sort type
+//
+// opinionated on the order,
+// date latest>oldest
+// root dev_id
+// size largest>smallest
+// filename a-z
+// sizes not known yet sort as empty files
+pub fn sort_file_list(
+    list: &mut [TrashFile],
+    sort_by: &SortType,
+    sizes: &HashMap<PathBuf, Option<u64>>,
+) {
+    let get_size = |trash_file: &TrashFile| {
+        trash_file
+            .files_entry
+            .as_ref()
+            .and_then(|v| sizes.get(v).copied().flatten())
+            .unwrap_or(0)
+    };
+    list.sort_by(|a, b| match sort_by {
+        SortType::DeletionDate => {
+            // sort by deletion date, if equal directories first
+            let a_date = a.trashinfo.clone().unwrap().deletion_date;
+            let b_date = b.trashinfo.clone().unwrap().deletion_date;
+            let cmp_date = b_date.cmp(&a_date);
+
+            // cmp_date, then by path so that the order doesn't change as
+            // entries come and go
+            match cmp_date {
+                Equal => {
+                    let a_is_dir = a.files_entry.as_deref().unwrap().is_dir();
+                    let b_is_dir = b.files_entry.as_deref().unwrap().is_dir();
+                    b_is_dir
+                        .cmp(&a_is_dir)
+                        .then_with(|| a.original_file.cmp(&b.original_file))
+                }
+                other => other,
+            }
+        }
+        SortType::TrashRoot => {
+            // compare by origin, if equal, then by deletion date
+            let a_dev = a.trashroot.device.clone().dev_num.dev_id;
+            let b_dev = b.trashroot.device.clone().dev_num.dev_id;
+            let cmp_dev = b_dev.cmp(&a_dev);
+            match cmp_dev {
+                Equal => {
+                    let a_date = a.trashinfo.clone().unwrap().deletion_date;
+                    let b_date = b.trashinfo.clone().unwrap().deletion_date;
+                    b_date.cmp(&a_date)
+                }
+                other => other,
+            }
+        }
+        SortType::Size => {
+            // compare by size, if equal, then by deletion date
+            let a_size = get_size(a);
+            let b_size = get_size(b);
+            let cmp_size = b_size.cmp(&a_size);
+
+            match cmp_size {
+                Equal => {
+                    let a_date = a.trashinfo.clone().unwrap().deletion_date;
+                    let b_date = b.trashinfo.clone().unwrap().deletion_date;
+                    b_date.cmp(&a_date)
+                }
+                other => other,
+            }
+        }
+        SortType::FileName => {
+            let a_name = a.original_file.clone();
+            let b_name = b.original_file.clone();
+            a_name
+                .file_name()
+                .unwrap_or_default()
+                .to_string_lossy()
+                .to_lowercase()
+                .cmp(
+                    &b_name
+                        .file_name()
+                        .unwrap_or_default()
+                        .to_string_lossy()
+                        .to_lowercase(),
+                )
+        }
+    });
+}
+
+// positions of the chars of the pattern in the text, in order, if all of
+// them are there. case insensitive unless the pattern has upper case chars
+pub fn fuzzy_match(pattern: &str, text: &str) -> Option<Vec<usize>> {
+    let case_sensitive = pattern.chars().any(|c| c.is_uppercase());
+    let mut pattern_chars = pattern.chars().peekable();
+    let mut positions = vec![];
+    for (i, c) in text.chars().enumerate() {
+        let Some(p) = pattern_chars.peek() else {
+            break;
+        };
+
+        let matched = if case_sensitive {
+            c == *p
+        } else {
+            c.to_lowercase().eq(p.to_lowercase())
+        };
+        if matched {
+            positions.push(i);
+            pattern_chars.next();
+        }
+    }
+
+    match pattern_chars.peek() {
+        None => Some(positions),
+        Some(_) => None,
+    }
+}
+
+// the next or the previous choice, with None before the first and after the
+// last choice
+fn cycle_choice<T: Clone + PartialEq>(
+    choices: &[T],
+    current: &Option<T>,
+    forward: bool,
+) -> Option<T> {
+    let position = current
+        .as_ref()
+        .and_then(|v| choices.iter().position(|c| c == v));
+    let next = match (position, forward) {
+        (None, true) => Some(0).filter(|i| *i < choices.len()),
+        (None, false) => choices.len().checked_sub(1),
+        (Some(i), true) => Some(i + 1).filter(|i| *i < choices.len()),
+        (Some(i), false) => i.checked_sub(1),
+    };
+    next.map(|i| choices[i].clone())
+}
+
+// the search matches the file name or the original path
+pub fn is_search_match(pattern: &str, trash_file: &TrashFile) -> bool {
+    let name = trash_file.original_file.file_name().unwrap_or_default();
+    fuzzy_match(pattern, &name.to_string_lossy()).is_some()
+        || fuzzy_match(pattern, &trash_file.original_file.to_string_lossy()).is_some()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_cycle_choice() {
+        let choices = [1, 7, 30];
+        assert_eq!(cycle_choice(&choices, &None, true), Some(1));
+        assert_eq!(cycle_choice(&choices, &Some(7), true), Some(30));
+        assert_eq!(cycle_choice(&choices, &Some(30), true), None);
+        assert_eq!(cycle_choice(&choices, &None, false), Some(30));
+        assert_eq!(cycle_choice(&choices, &Some(1), false), None);
+        assert_eq!(cycle_choice::<i64>(&[], &None, true), None);
+    }
+
+    #[test]
+    fn test_fuzzy_match() {
+        assert_eq!(fuzzy_match("", "notes.txt"), Some(vec![]));
+        assert_eq!(fuzzy_match("nt", "notes.txt"), Some(vec![0, 2]));
+        assert_eq!(fuzzy_match("NTX", "notes.txt"), None);
+        assert_eq!(fuzzy_match("ntx", "Notes.TXT"), Some(vec![0, 2, 7]));
+        assert_eq!(fuzzy_match("txn", "notes.txt"), None);
+        assert_eq!(fuzzy_match("é", "café.md"), Some(vec![3]));
+    }
+}
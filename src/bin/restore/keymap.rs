@@ -0,0 +1,845 @@
+use std::error::Error;
+
+use crossterm::event::KeyModifiers;
+use crossterm::event::{KeyCode, KeyEvent};
+use libtrash::*;
+use std::env;
+use std::fmt;
+use std::fs;
+use std::io;
+
+// shortcuts that can't be changed in the keymap, shown after the ones that can
+// in the help screen and the man page
+pub const FIXED_SHORTCUTS: &[(&str, &str)] = &[
+    (
+        "↓↑/enter/esc",
+        "select a match, stop and clear the search (while searching)",
+    ),
+    (
+        "mouse",
+        "click to select, double click to restore, wheel to scroll the list or the preview",
+    ),
+];
+
+// where an action is used. actions of different screens can share keys
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum KeyContext {
+    Main,
+    Browse,
+    Preview, // while focused
+    Roots,   // while the sidebar is focused
+    Dialog,  // the dialogs, the help screen, the error log and the batch report
+    Stats,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Action {
+    Down,
+    Up,
+    Top,
+    Bottom,
+    Restore,
+    RestoreTo,
+    Delete,
+    EmptyBin,
+    Mark,
+    MarkRange,
+    MarkAll,
+    Search,
+    NextMatch,
+    PreviousMatch,
+    Clear,
+    Refresh,
+    Sort,
+    Filter,
+    OpenPager,
+    OpenEditor,
+    OpenDefault,
+    Browse,
+    BrowseUp,
+    Back,
+    ErrorLog,
+    FocusPreview,
+    Help,
+    Quit,
+    ScrollDown,
+    ScrollUp,
+    PageDown,
+    PageUp,
+    HalfPageDown,
+    HalfPageUp,
+    ScrollTop,
+    ScrollBottom,
+    FocusList,
+    Roots,
+    ScopeRoot,
+    EmptyRoot,
+    CheckRoot,
+    Stats,
+    Undo,
+    Redo,
+    NextRow,
+    PreviousRow,
+    NextChoice,
+    PreviousChoice,
+    ClearFilters,
+    Confirm,
+    Cancel,
+    PerWeek,
+    PurgeOlderThan(i64), // days, one of the purge presets
+}
+
+const LISTS: &[KeyContext] = &[KeyContext::Main, KeyContext::Browse, KeyContext::Roots];
+
+const MAIN_AND_BROWSE: &[KeyContext] = &[KeyContext::Main, KeyContext::Browse];
+
+const MAIN_AND_ROOTS: &[KeyContext] = &[KeyContext::Main, KeyContext::Roots];
+
+const MAIN: &[KeyContext] = &[KeyContext::Main];
+
+const BROWSE: &[KeyContext] = &[KeyContext::Browse];
+
+const PREVIEW: &[KeyContext] = &[KeyContext::Preview];
+
+const PREVIEW_AND_ROOTS: &[KeyContext] = &[KeyContext::Preview, KeyContext::Roots];
+
+const ROOTS: &[KeyContext] = &[KeyContext::Roots];
+
+const DIALOG: &[KeyContext] = &[KeyContext::Dialog];
+
+const DIALOG_AND_STATS: &[KeyContext] = &[KeyContext::Dialog, KeyContext::Stats];
+
+const STATS: &[KeyContext] = &[KeyContext::Stats];
+
+// actions with their name in the keys config file, in the order of the help
+// screen
+pub const ACTIONS: &[(Action, &str, &[KeyContext], &str)] = &[
+    (Action::Down, "down", LISTS, "select the next file"),
+    (Action::Up, "up", LISTS, "select the previous file"),
+    (Action::Top, "top", LISTS, "go to the top in the list"),
+    (
+        Action::Bottom,
+        "bottom",
+        LISTS,
+        "go to the bottom in the list",
+    ),
+    (
+        Action::Restore,
+        "restore",
+        MAIN_AND_BROWSE,
+        "restore the marked files or the current file",
+    ),
+    (
+        Action::RestoreTo,
+        "restore-to",
+        MAIN,
+        "restore the current file to another path",
+    ),
+    (
+        Action::Delete,
+        "delete",
+        MAIN,
+        "delete the marked files or the current file",
+    ),
+    (Action::EmptyBin, "empty-bin", MAIN, "empty trash bin"),
+    (Action::Mark, "mark", MAIN, "mark/unmark the current file"),
+    (
+        Action::MarkRange,
+        "mark-range",
+        MAIN,
+        "start/end marking a range of files",
+    ),
+    (
+        Action::MarkAll,
+        "mark-all",
+        MAIN,
+        "mark all files, or unmark all if all are marked",
+    ),
+    (
+        Action::Search,
+        "search",
+        MAIN,
+        "search file names and original paths",
+    ),
+    (
+        Action::NextMatch,
+        "next-match",
+        MAIN,
+        "go to the next match",
+    ),
+    (
+        Action::PreviousMatch,
+        "previous-match",
+        MAIN,
+        "go to the previous match",
+    ),
+    (
+        Action::Clear,
+        "clear",
+        MAIN,
+        "clear the marks, then the search",
+    ),
+    (Action::Refresh, "refresh", MAIN, "refresh file list"),
+    (Action::Sort, "sort", MAIN, "open sort by dialog"),
+    (Action::Filter, "filter", MAIN, "open filter dialog"),
+    (
+        Action::OpenPager,
+        "open-pager",
+        MAIN,
+        "open the file in $PAGER",
+    ),
+    (
+        Action::OpenEditor,
+        "open-editor",
+        MAIN,
+        "open the file in $EDITOR, read-only",
+    ),
+    (
+        Action::OpenDefault,
+        "open-default",
+        MAIN,
+        "open the file with the default application",
+    ),
+    (
+        Action::Browse,
+        "browse",
+        MAIN_AND_BROWSE,
+        "browse inside the current directory, to restore a file or a directory in it",
+    ),
+    (Action::BrowseUp, "browse-up", BROWSE, "go up a directory"),
+    (Action::Back, "back", BROWSE, "back to the trash"),
+    (
+        Action::ErrorLog,
+        "error-log",
+        MAIN,
+        "show the errors of this session",
+    ),
+    (
+        Action::FocusPreview,
+        "focus-preview",
+        MAIN_AND_BROWSE,
+        "focus the preview to scroll it",
+    ),
+    (
+        Action::Roots,
+        "roots",
+        MAIN_AND_ROOTS,
+        "show the trash roots sidebar, or hide it",
+    ),
+    (
+        Action::Stats,
+        "stats",
+        MAIN,
+        "show the trash statistics, and purge old files",
+    ),
+    (
+        Action::Undo,
+        "undo",
+        MAIN_AND_BROWSE,
+        "undo the last restore of this session, back into the trash",
+    ),
+    (
+        Action::Redo,
+        "redo",
+        MAIN_AND_BROWSE,
+        "redo the last undone restore",
+    ),
+    (Action::Help, "help", MAIN, "show this screen (good job!)"),
+    (Action::Quit, "quit", MAIN, "exit"),
+    (
+        Action::ScrollDown,
+        "scroll-down",
+        PREVIEW,
+        "scroll down a line",
+    ),
+    (Action::ScrollUp, "scroll-up", PREVIEW, "scroll up a line"),
+    (Action::PageDown, "page-down", PREVIEW, "scroll down a page"),
+    (Action::PageUp, "page-up", PREVIEW, "scroll up a page"),
+    (
+        Action::HalfPageDown,
+        "half-page-down",
+        PREVIEW,
+        "scroll down half a page",
+    ),
+    (
+        Action::HalfPageUp,
+        "half-page-up",
+        PREVIEW,
+        "scroll up half a page",
+    ),
+    (Action::ScrollTop, "scroll-top", PREVIEW, "go to the top"),
+    (
+        Action::ScrollBottom,
+        "scroll-bottom",
+        PREVIEW,
+        "go to the bottom",
+    ),
+    (
+        Action::FocusList,
+        "focus-list",
+        PREVIEW_AND_ROOTS,
+        "back to the list",
+    ),
+    (
+        Action::ScopeRoot,
+        "scope-root",
+        ROOTS,
+        "show only the files of the current root",
+    ),
+    (
+        Action::EmptyRoot,
+        "empty-root",
+        ROOTS,
+        "empty the current root",
+    ),
+    (
+        Action::CheckRoot,
+        "check-root",
+        ROOTS,
+        "check the current root for broken entries, and clean up what's left of deleted ones",
+    ),
+    (
+        Action::NextRow,
+        "next-row",
+        DIALOG_AND_STATS,
+        "select the next row, or scroll down",
+    ),
+    (
+        Action::PreviousRow,
+        "previous-row",
+        DIALOG_AND_STATS,
+        "select the previous row, or scroll up",
+    ),
+    (
+        Action::NextChoice,
+        "next-choice",
+        DIALOG,
+        "select the next button, or the next value of a filter",
+    ),
+    (
+        Action::PreviousChoice,
+        "previous-choice",
+        DIALOG,
+        "select the previous button, or the previous value of a filter",
+    ),
+    (
+        Action::ClearFilters,
+        "clear-filters",
+        DIALOG,
+        "clear all the filters",
+    ),
+    (Action::Confirm, "confirm", DIALOG, "confirm the selection"),
+    (
+        Action::Cancel,
+        "cancel",
+        DIALOG_AND_STATS,
+        "close the dialog",
+    ),
+    (
+        Action::PerWeek,
+        "per-week",
+        STATS,
+        "show the deletions per week or per day",
+    ),
+    (
+        Action::PurgeOlderThan(7),
+        "purge-week",
+        STATS,
+        "purge the files trashed more than 7 days ago",
+    ),
+    (
+        Action::PurgeOlderThan(30),
+        "purge-month",
+        STATS,
+        "purge the files trashed more than 30 days ago",
+    ),
+    (
+        Action::PurgeOlderThan(90),
+        "purge-quarter",
+        STATS,
+        "purge the files trashed more than 90 days ago",
+    ),
+    (
+        Action::PurgeOlderThan(365),
+        "purge-year",
+        STATS,
+        "purge the files trashed more than 365 days ago",
+    ),
+];
+
+// the keys the TUI has always had
+const VIM_KEYS: &[(Action, &str)] = &[
+    (Action::Down, "down, j"),
+    (Action::Up, "up, k"),
+    (Action::Top, "g, pgup"),
+    (Action::Bottom, "G, pgdn"),
+    (Action::Restore, "enter"),
+    (Action::RestoreTo, "R"),
+    (Action::Delete, "del"),
+    (Action::EmptyBin, "shift+del"),
+    (Action::Mark, "space"),
+    (Action::MarkRange, "v"),
+    (Action::MarkAll, "a"),
+    (Action::Search, "/"),
+    (Action::NextMatch, "n"),
+    (Action::PreviousMatch, "N"),
+    (Action::Clear, "esc"),
+    (Action::Refresh, "r, f5"),
+    (Action::Sort, "s"),
+    (Action::Filter, "f"),
+    (Action::OpenPager, "o"),
+    (Action::OpenEditor, "e"),
+    (Action::OpenDefault, "x"),
+    (Action::Browse, "l, right"),
+    (Action::BrowseUp, "h, left, backspace"),
+    (Action::Back, "q, esc"),
+    (Action::ErrorLog, "L"),
+    (Action::FocusPreview, "tab"),
+    (Action::Help, "h, f1"),
+    (Action::Quit, "q"),
+    (Action::ScrollDown, "down, j"),
+    (Action::ScrollUp, "up, k"),
+    (Action::PageDown, "pgdn, space"),
+    (Action::PageUp, "pgup"),
+    (Action::HalfPageDown, "ctrl+d"),
+    (Action::HalfPageUp, "ctrl+u"),
+    (Action::ScrollTop, "g, home"),
+    (Action::ScrollBottom, "G, end"),
+    (Action::FocusList, "tab, esc"),
+    (Action::Roots, "b"),
+    (Action::Stats, "S"),
+    (Action::Undo, "u"),
+    (Action::Redo, "ctrl+r"),
+    (Action::ScopeRoot, "enter, l, right, space"),
+    (Action::EmptyRoot, "shift+del"),
+    (Action::CheckRoot, "c"),
+    (Action::NextRow, "down, j"),
+    (Action::PreviousRow, "up, k"),
+    (Action::NextChoice, "right, l, tab"),
+    (Action::PreviousChoice, "left, h"),
+    (Action::ClearFilters, "c"),
+    (Action::Confirm, "enter"),
+    (Action::Cancel, "q, esc"),
+    (Action::PerWeek, "w"),
+    (Action::PurgeOlderThan(7), "1"),
+    (Action::PurgeOlderThan(30), "2"),
+    (Action::PurgeOlderThan(90), "3"),
+    (Action::PurgeOlderThan(365), "4"),
+];
+
+// emacs movement, and dired for the rest
+const EMACS_KEYS: &[(Action, &str)] = &[
+    (Action::Down, "down, ctrl+n"),
+    (Action::Up, "up, ctrl+p"),
+    (Action::Top, "alt+<, home"),
+    (Action::Bottom, "alt+>, end"),
+    (Action::Restore, "enter"),
+    (Action::RestoreTo, "R"),
+    (Action::Delete, "D, del"),
+    (Action::EmptyBin, "shift+del"),
+    (Action::Mark, "m, space"),
+    (Action::MarkRange, "ctrl+space"),
+    (Action::MarkAll, "t"),
+    (Action::Search, "ctrl+s"),
+    (Action::NextMatch, "alt+n"),
+    (Action::PreviousMatch, "alt+p"),
+    (Action::Clear, "ctrl+g, esc"),
+    (Action::Refresh, "g, f5"),
+    (Action::Sort, "s"),
+    (Action::Filter, "f"),
+    (Action::OpenPager, "v"),
+    (Action::OpenEditor, "e"),
+    (Action::OpenDefault, "o"),
+    (Action::Browse, "i, right"),
+    (Action::BrowseUp, "^, left, backspace"),
+    (Action::Back, "q, ctrl+g, esc"),
+    (Action::ErrorLog, "L"),
+    (Action::FocusPreview, "tab"),
+    (Action::Help, "?, f1"),
+    (Action::Quit, "q"),
+    (Action::ScrollDown, "down, ctrl+n"),
+    (Action::ScrollUp, "up, ctrl+p"),
+    (Action::PageDown, "pgdn, ctrl+v, space"),
+    (Action::PageUp, "pgup, alt+v"),
+    (Action::HalfPageDown, ""),
+    (Action::HalfPageUp, ""),
+    (Action::ScrollTop, "alt+<, home"),
+    (Action::ScrollBottom, "alt+>, end"),
+    (Action::FocusList, "tab, ctrl+g, esc"),
+    (Action::Roots, "b"),
+    (Action::Stats, "S"),
+    (Action::Undo, "u"),
+    (Action::Redo, "ctrl+r"),
+    (Action::ScopeRoot, "enter, i, right, space"),
+    (Action::EmptyRoot, "shift+del"),
+    (Action::CheckRoot, "c"),
+    (Action::NextRow, "down, ctrl+n"),
+    (Action::PreviousRow, "up, ctrl+p"),
+    (Action::NextChoice, "right, ctrl+f, tab"),
+    (Action::PreviousChoice, "left, ctrl+b"),
+    (Action::ClearFilters, "c"),
+    (Action::Confirm, "enter"),
+    (Action::Cancel, "q, ctrl+g, esc"),
+    (Action::PerWeek, "w"),
+    (Action::PurgeOlderThan(7), "1"),
+    (Action::PurgeOlderThan(30), "2"),
+    (Action::PurgeOlderThan(90), "3"),
+    (Action::PurgeOlderThan(365), "4"),
+];
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Key {
+    pub code: KeyCode,
+    pub modifiers: KeyModifiers,
+}
+
+impl Key {
+    // a key like "ctrl+n", "shift+del", "G", or "f1"
+    fn parse(value: &str) -> Result<Self, Box<dyn Error>> {
+        let (prefix, name) = match value.strip_suffix("++") {
+            Some(v) => (v, "+"),
+            None => match value.rsplit_once('+') {
+                Some((prefix, name)) if !name.is_empty() => (prefix, name),
+                _ => ("", value),
+            },
+        };
+
+        let mut modifiers = KeyModifiers::NONE;
+        for modifier in prefix.split('+').filter(|v| !v.is_empty()) {
+            modifiers |= match modifier.to_lowercase().as_str() {
+                "ctrl" | "control" => KeyModifiers::CONTROL,
+                "alt" | "meta" => KeyModifiers::ALT,
+                "shift" => KeyModifiers::SHIFT,
+                _ => {
+                    return Err(Box::<dyn Error>::from(format!(
+                        "unknown modifier '{modifier}'"
+                    )))
+                }
+            };
+        }
+
+        let mut chars = name.chars();
+        let code = match (chars.next(), chars.next()) {
+            (Some(c), None) => KeyCode::Char(c),
+            _ => match name.to_lowercase().as_str() {
+                "enter" | "return" => KeyCode::Enter,
+                "esc" | "escape" => KeyCode::Esc,
+                "tab" => KeyCode::Tab,
+                "backtab" => KeyCode::BackTab,
+                "backspace" => KeyCode::Backspace,
+                "del" | "delete" => KeyCode::Delete,
+                "insert" => KeyCode::Insert,
+                "home" => KeyCode::Home,
+                "end" => KeyCode::End,
+                "pgup" | "pageup" => KeyCode::PageUp,
+                "pgdn" | "pagedown" => KeyCode::PageDown,
+                "up" => KeyCode::Up,
+                "down" => KeyCode::Down,
+                "left" => KeyCode::Left,
+                "right" => KeyCode::Right,
+                "space" => KeyCode::Char(' '),
+                "comma" => KeyCode::Char(','),
+                v => match v.strip_prefix('f').and_then(|n| n.parse::<u8>().ok()) {
+                    Some(n) if (1..=12).contains(&n) => KeyCode::F(n),
+                    _ => return Err(Box::<dyn Error>::from(format!("unknown key '{name}'"))),
+                },
+            },
+        };
+
+        Ok(Key { code, modifiers }.normalized())
+    }
+
+    // a character already has the case shift gives it
+    fn normalized(mut self) -> Self {
+        if matches!(self.code, KeyCode::Char(_) | KeyCode::BackTab) {
+            self.modifiers.remove(KeyModifiers::SHIFT);
+        }
+        self
+    }
+}
+
+impl From<&KeyEvent> for Key {
+    fn from(key: &KeyEvent) -> Self {
+        Key {
+            code: key.code,
+            modifiers: key.modifiers,
+        }
+        .normalized()
+    }
+}
+
+impl fmt::Display for Key {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        if self.modifiers.contains(KeyModifiers::CONTROL) {
+            write!(f, "ctrl+")?;
+        }
+        if self.modifiers.contains(KeyModifiers::ALT) {
+            write!(f, "alt+")?;
+        }
+        if self.modifiers.contains(KeyModifiers::SHIFT) {
+            write!(f, "shift+")?;
+        }
+        match self.code {
+            KeyCode::Char(' ') => write!(f, "space"),
+            KeyCode::Char(c) => write!(f, "{c}"),
+            KeyCode::Up => write!(f, "↑"),
+            KeyCode::Down => write!(f, "↓"),
+            KeyCode::Left => write!(f, "←"),
+            KeyCode::Right => write!(f, "→"),
+            KeyCode::Enter => write!(f, "enter"),
+            KeyCode::Esc => write!(f, "esc"),
+            KeyCode::Tab => write!(f, "tab"),
+            KeyCode::BackTab => write!(f, "backtab"),
+            KeyCode::Backspace => write!(f, "backspace"),
+            KeyCode::Delete => write!(f, "del"),
+            KeyCode::Insert => write!(f, "insert"),
+            KeyCode::Home => write!(f, "home"),
+            KeyCode::End => write!(f, "end"),
+            KeyCode::PageUp => write!(f, "pgup"),
+            KeyCode::PageDown => write!(f, "pgdn"),
+            KeyCode::F(n) => write!(f, "f{n}"),
+            _ => write!(f, "?"),
+        }
+    }
+}
+
+// the keys of each action, from a preset and the keys config file
+#[derive(Debug)]
+pub struct Keymap {
+    bindings: Vec<(Action, Vec<Key>)>,
+}
+
+impl Default for Keymap {
+    fn default() -> Self {
+        Keymap::preset(VIM_KEYS)
+    }
+}
+
+impl Keymap {
+    fn preset(keys: &[(Action, &str)]) -> Self {
+        let mut keymap = Keymap { bindings: vec![] };
+        for (action, keys) in keys {
+            let keys = parse_keys(keys).expect("preset keys are valid");
+            keymap.bindings.push((*action, keys));
+        }
+        keymap
+    }
+
+    // $XDG_CONFIG_HOME/trash-rs/keys.conf, the default keys if there's none
+    pub fn load() -> (Self, Vec<String>) {
+        let binary_name = env!("CARGO_PKG_NAME");
+        let path = match get_xdg_config_home() {
+            Ok(v) => v.join(binary_name).join("keys.conf"),
+            Err(e) => return (Keymap::default(), vec![format!("cannot load keys: {e}")]),
+        };
+
+        match fs::read_to_string(&path) {
+            Ok(v) => Keymap::parse(&v),
+            Err(e) if e.kind() == io::ErrorKind::NotFound => (Keymap::default(), vec![]),
+            Err(e) => (
+                Keymap::default(),
+                vec![format!("cannot read {}: {e}", path.display())],
+            ),
+        }
+    }
+
+    // lines of "action = key, key", applied on top of the preset. lines with
+    // errors are skipped, and returned as messages
+    pub fn parse(contents: &str) -> (Self, Vec<String>) {
+        let mut errors = vec![];
+        let lines: Vec<(usize, &str, &str)> = contents
+            .lines()
+            .enumerate()
+            .filter(|(_, line)| !line.trim().is_empty() && !line.trim().starts_with('#'))
+            .filter_map(|(i, line)| match line.split_once('=') {
+                Some((name, keys)) => Some((i + 1, name.trim(), keys.trim())),
+                None => {
+                    errors.push(format!("keys.conf:{}: expected 'action = keys'", i + 1));
+                    None
+                }
+            })
+            .collect();
+
+        // the preset goes first, wherever it is
+        let mut keymap = Keymap::default();
+        for (line_number, name, value) in &lines {
+            if *name == "preset" {
+                keymap = match *value {
+                    "vim" => Keymap::preset(VIM_KEYS),
+                    "emacs" => Keymap::preset(EMACS_KEYS),
+                    _ => {
+                        errors.push(format!(
+                            "keys.conf:{line_number}: unknown preset '{value}', expected vim or emacs"
+                        ));
+                        continue;
+                    }
+                };
+            }
+        }
+
+        for (line_number, name, value) in lines {
+            if name == "preset" {
+                continue;
+            }
+            let Some((action, ..)) = ACTIONS.iter().find(|(_, v, ..)| *v == name) else {
+                errors.push(format!("keys.conf:{line_number}: unknown action '{name}'"));
+                continue;
+            };
+            match parse_keys(value) {
+                Ok(keys) => keymap.bind(*action, keys),
+                Err(e) => errors.push(format!("keys.conf:{line_number}: {e}")),
+            }
+        }
+
+        (keymap, errors)
+    }
+
+    // the keys replace the ones of the action, and are taken away from the
+    // actions it shares a screen with
+    fn bind(&mut self, action: Action, keys: Vec<Key>) {
+        let contexts = get_action_contexts(action);
+        for (other, other_keys) in self.bindings.iter_mut() {
+            if *other == action {
+                *other_keys = keys.clone();
+            } else if get_action_contexts(*other)
+                .iter()
+                .any(|v| contexts.contains(v))
+            {
+                other_keys.retain(|v| !keys.contains(v));
+            }
+        }
+    }
+
+    pub fn get_action(&self, key: &KeyEvent, context: KeyContext) -> Option<Action> {
+        let key = Key::from(key);
+        self.bindings
+            .iter()
+            .find(|(action, keys)| {
+                keys.contains(&key) && get_action_contexts(*action).contains(&context)
+            })
+            .map(|(action, _)| *action)
+    }
+
+    pub fn get_keys(&self, action: Action) -> &[Key] {
+        self.bindings
+            .iter()
+            .find(|(v, _)| *v == action)
+            .map(|(_, keys)| keys.as_slice())
+            .unwrap_or_default()
+    }
+
+    // "↓/j"
+    pub fn get_label(&self, action: Action) -> String {
+        let keys: Vec<String> = self
+            .get_keys(action)
+            .iter()
+            .map(|v| v.to_string())
+            .collect();
+        keys.join("/")
+    }
+
+    // "↓↑/jk" for the keys of two opposite actions, in pairs
+    pub fn get_pair_label(&self, a: Action, b: Action) -> String {
+        let (a, b) = (self.get_keys(a), self.get_keys(b));
+        let mut groups = vec![];
+        for i in 0..a.len().max(b.len()) {
+            let pair: Vec<String> = [a.get(i), b.get(i)]
+                .into_iter()
+                .flatten()
+                .map(|v| v.to_string())
+                .collect();
+            if pair.iter().all(|v| v.chars().count() == 1) {
+                groups.push(pair.concat());
+            } else {
+                groups.push(pair.join("/"));
+            }
+        }
+        groups.join("/")
+    }
+}
+
+fn get_action_contexts(action: Action) -> &'static [KeyContext] {
+    ACTIONS
+        .iter()
+        .find(|(v, ..)| *v == action)
+        .map(|(_, _, contexts, _)| *contexts)
+        .unwrap_or_default()
+}
+
+// "down, j"
+fn parse_keys(value: &str) -> Result<Vec<Key>, Box<dyn Error>> {
+    value
+        .split(',')
+        .map(|v| v.trim())
+        .filter(|v| !v.is_empty())
+        .map(Key::parse)
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_keymap() {
+        let key = |code, modifiers| KeyEvent::new(code, modifiers);
+
+        // the presets parse, and crossterm's shift on characters is ignored
+        let vim = Keymap::default();
+        let emacs = Keymap::preset(EMACS_KEYS);
+        assert_eq!(
+            vim.get_action(
+                &key(KeyCode::Char('G'), KeyModifiers::SHIFT),
+                KeyContext::Main
+            ),
+            Some(Action::Bottom)
+        );
+        assert_eq!(
+            vim.get_action(
+                &key(KeyCode::Char('h'), KeyModifiers::NONE),
+                KeyContext::Browse
+            ),
+            Some(Action::BrowseUp)
+        );
+        assert_eq!(
+            emacs.get_action(
+                &key(KeyCode::Char('n'), KeyModifiers::CONTROL),
+                KeyContext::Main
+            ),
+            Some(Action::Down)
+        );
+        assert_eq!(vim.get_pair_label(Action::Down, Action::Up), "↓↑/jk");
+        assert_eq!(
+            vim.get_pair_label(Action::PreviousChoice, Action::NextChoice),
+            "←→/hl/tab"
+        );
+
+        let (keymap, errors) = Keymap::parse(
+            "# comment\nquit = ctrl+c, Q\nhelp = q\nfoo = x\ntop = hyper+g\npreset = emacs\n",
+        );
+        assert_eq!(
+            errors,
+            vec![
+                "keys.conf:4: unknown action 'foo'".to_string(),
+                "keys.conf:5: unknown modifier 'hyper'".to_string(),
+            ]
+        );
+        // applied over the preset, wherever it is
+        assert_eq!(keymap.get_label(Action::Top), "alt+</home");
+        assert_eq!(keymap.get_label(Action::Quit), "ctrl+c/Q");
+        // taken away from quit in the main screen, not from back in the
+        // browser
+        assert_eq!(
+            keymap.get_action(
+                &key(KeyCode::Char('q'), KeyModifiers::NONE),
+                KeyContext::Main
+            ),
+            Some(Action::Help)
+        );
+        assert_eq!(keymap.get_label(Action::Back), "q/ctrl+g/esc");
+
+        assert_eq!(Key::parse("ctrl++").unwrap().to_string(), "ctrl++");
+        assert_eq!(Key::parse("Shift+Del").unwrap().to_string(), "shift+del");
+        assert!(Key::parse("f13").is_err());
+    }
+}
@@ -0,0 +1,724 @@
+use std::error::Error;
+
+use crate::app::{App, AppState, Opener, NOTIFICATION_TIMEOUT};
+use crate::filters::{sort_file_list, Filters, RootFilter, SortType, SORT_TYPES};
+use crate::keymap::{Keymap, ACTIONS, FIXED_SHORTCUTS};
+use crate::theme::{query_background_is_light, Theme, DARK_THEME, LIGHT_THEME};
+use crate::watcher::{TrashWatcher, WATCH_INTERVAL};
+use crossterm::event::{self, Event};
+use libtrash::cli::{
+    find_option, format_options_help, generate_completions, write_man_pages, CliOption, CliValue,
+    ManPage, ManSection, Shell, ValueHint, TRASH_RS_MAN_PAGE,
+};
+use libtrash::*;
+use ratatui::backend::CrosstermBackend;
+use ratatui::crossterm::event::DisableMouseCapture;
+use ratatui::crossterm::event::EnableMouseCapture;
+use ratatui::crossterm::execute;
+use ratatui::crossterm::terminal::{disable_raw_mode, LeaveAlternateScreen};
+use ratatui::crossterm::terminal::{enable_raw_mode, EnterAlternateScreen};
+use ratatui::{restore, Terminal};
+use std::collections::HashMap;
+use std::env;
+use std::io;
+use std::io::Stdout;
+use std::path::{Component, Path, PathBuf};
+
+mod app;
+mod dialogs;
+mod filters;
+mod keymap;
+mod preview;
+mod sizes;
+mod stats;
+mod theme;
+mod views;
+mod watcher;
+
+const VERBOSE_MODE: bool = false;
+
+const BINARY_NAME: &str = "Trash Bin";
+
+const BINARY_COMMAND: &str = "restore";
+
+// this env var needs to be present. Use Makefile to build locally
+const BINARY_VERSION: &str = env!("TAG_NAME", "TAG_NAME not defined");
+
+const EXITCODE_OK: i32 = 0;
+
+const EXITCODE_INVALID_ARGS: i32 = 1;
+
+const EXITCODE_EXTERNAL: i32 = 255;
+
+const OPTIONS: &[CliOption] = &[
+    CliOption {
+        short: Some('h'),
+        long: "help",
+        value: None,
+        desc: "display this help and exit",
+    },
+    CliOption {
+        short: Some('V'),
+        long: "version",
+        value: None,
+        desc: "output version information and exit",
+    },
+    CliOption {
+        short: None,
+        long: "list-trashed",
+        value: None,
+        desc: "print the original paths of the trashed files and exit",
+    },
+    CliOption {
+        short: None,
+        long: "here",
+        value: None,
+        desc: "show only the files trashed from the current directory, as if it was PATH",
+    },
+    CliOption {
+        short: Some('s'),
+        long: "sort",
+        value: Some(CliValue {
+            name: "KEY",
+            hint: ValueHint::Choices(SORT_NAMES),
+            optional: false,
+        }),
+        desc: "sort the files by KEY, date (the default), path, size, or name",
+    },
+    CliOption {
+        short: Some('r'),
+        long: "reverse",
+        value: None,
+        desc: "reverse the order of the sort",
+    },
+    CliOption {
+        short: None,
+        long: "root",
+        value: Some(CliValue {
+            name: "MOUNT",
+            hint: ValueHint::Path,
+            optional: false,
+        }),
+        desc: "show only the files in the trash of the device mounted at MOUNT",
+    },
+    CliOption {
+        short: None,
+        long: "theme",
+        value: Some(CliValue {
+            name: "NAME",
+            hint: ValueHint::Any,
+            optional: false,
+        }),
+        desc: "use the colour theme NAME, dark, light, or a theme file, over TRASH_RS_THEME",
+    },
+    CliOption {
+        short: None,
+        long: "completions",
+        value: Some(CliValue {
+            name: "SHELL",
+            hint: ValueHint::Choices(Shell::NAMES),
+            optional: false,
+        }),
+        desc: "print a completion script for bash, zsh or fish and exit",
+    },
+    CliOption {
+        short: None,
+        long: "generate-man",
+        value: Some(CliValue {
+            name: "DIR",
+            hint: ValueHint::Path,
+            optional: false,
+        }),
+        desc: "write the restore(1) and trash-rs(5) man pages to DIR and exit",
+    },
+];
+
+const USAGE: &str = "[OPTION]... [PATH]";
+
+const SUMMARY: &str = "Browse, restore, and delete trashed files in a TUI";
+
+// shown after the options in --help, and as the man page description
+const DESCRIPTION: &[&str] = &[
+    "Trashed files from the home trash and the trash directories of all mounted
+devices are listed together. Files trashed on other mounts are marked with an
+icon.",
+    "With PATH, only the files trashed from under PATH are shown, until the filters \
+are cleared. --list-trashed lists only those files too, in the order of --sort.",
+    "Press 'h' or F1 in the TUI for the keyboard shortcuts. The keys can be changed \
+in $XDG_CONFIG_HOME/trash-rs/keys.conf, see trash-rs(5).",
+];
+
+// the shortcuts are of the default keymap. the page is only made to be written
+// out before exiting, so the generated parts are leaked to be static
+fn get_man_page() -> ManPage {
+    let mut shortcuts: Vec<(&'static str, &'static str)> = vec![];
+    let keymap = Keymap::default();
+    for (action, _, _, desc) in ACTIONS {
+        let label = keymap.get_label(*action);
+        if !label.is_empty() {
+            shortcuts.push((String::leak(label), desc));
+        }
+    }
+    shortcuts.extend(FIXED_SHORTCUTS);
+
+    ManPage {
+        name: BINARY_COMMAND,
+        section: 1,
+        summary: "browse, restore, and delete trashed files in a TUI",
+        synopsis: Some(USAGE),
+        description: DESCRIPTION,
+        options: OPTIONS,
+        sections: Vec::leak(vec![
+            ManSection {
+                title: "KEYBOARD SHORTCUTS",
+                paragraphs: &[
+                    "Shortcuts are case sensitive. These are the default keys, see \
+trash-rs(5) to change them.",
+                ],
+                entries: Vec::leak(shortcuts),
+            },
+            ManSection {
+                title: "REPORTING BUGS",
+                paragraphs: &["Report issues at <https://github.com/chamilad/trash-rs>."],
+                entries: &[],
+            },
+        ]),
+        see_also: &["trash(1)", "trash-rs(5)"],
+    }
+}
+
+// of the sort types in the same order, for --sort
+const SORT_NAMES: &[&str] = &["date", "path", "size", "name"];
+
+fn main() -> Result<(), Box<dyn Error>> {
+    // skip the binary name, and parse rest of the args
+    let args: Vec<String> = env::args().skip(1).collect();
+    let args_conf = match Args::parse(args) {
+        Ok(v) => v,
+        Err(e) => {
+            msg_err(format!("{e}"));
+            msg_err("try '-h' for more information.");
+            std::process::exit(EXITCODE_INVALID_ARGS);
+        }
+    };
+
+    if let Some(shell) = args_conf.completions {
+        print!(
+            "{}",
+            generate_completions(shell, BINARY_COMMAND, OPTIONS, Some(ValueHint::Trashed))
+        );
+        std::process::exit(EXITCODE_OK);
+    }
+
+    if args_conf.version {
+        let version = env!("CARGO_PKG_VERSION");
+        let binary_name = env!("CARGO_PKG_NAME");
+        println!("{binary_name} ({version})");
+        std::process::exit(EXITCODE_OK);
+    }
+
+    if let Some(dir) = &args_conf.generate_man {
+        match write_man_pages(dir, &[&get_man_page(), &TRASH_RS_MAN_PAGE]) {
+            Ok(v) => {
+                for path in v {
+                    println!("{}", path.display());
+                }
+            }
+            Err(e) => {
+                msg_err(format!("cannot write man pages: {e}"));
+                std::process::exit(EXITCODE_EXTERNAL);
+            }
+        }
+        std::process::exit(EXITCODE_OK);
+    }
+
+    if args_conf.help {
+        println!();
+        println!("{BINARY_COMMAND} version {BINARY_VERSION}");
+        println!("a freedesktop.org trash spec implementation for the CLI");
+        println!();
+        println!("Usage: {BINARY_COMMAND} {USAGE}");
+        println!("{SUMMARY}, \"{BINARY_NAME}\"");
+        println!();
+        print!("{}", format_options_help(OPTIONS));
+        for paragraph in DESCRIPTION {
+            println!();
+            println!("{paragraph}");
+        }
+        println!();
+        println!("{BINARY_COMMAND} source code, documentation, and issue tracker is in Github:");
+        println!("<https://github.com/chamilad/trash-rs>");
+        std::process::exit(EXITCODE_OK);
+    }
+
+    // the scope of the listing, the same for the TUI and --list-trashed
+    let mut filters = Filters::default();
+    let original_dir = match (&args_conf.path, args_conf.here) {
+        (Some(v), _) => Some(v.clone()),
+        (None, true) => Some(".".to_string()),
+        (None, false) => None,
+    };
+    if let Some(v) = original_dir {
+        filters.original_dir = match to_abs_path(&v) {
+            Ok(v) => Some(normalize_path(&v)),
+            Err(e) => {
+                msg_err(format!("cannot resolve '{v}': {e}"));
+                std::process::exit(EXITCODE_INVALID_ARGS);
+            }
+        };
+    }
+    if let Some(v) = &args_conf.root {
+        filters.root = match get_mount_filter(v) {
+            Ok(v) => Some(v),
+            Err(e) => {
+                msg_err(format!("{e}"));
+                std::process::exit(EXITCODE_INVALID_ARGS);
+            }
+        };
+    }
+    let sort_type = args_conf.sort.unwrap_or(SortType::DeletionDate);
+
+    if args_conf.list_trashed {
+        let (trash_roots, mut errors) = match get_trash_roots() {
+            Ok(v) => v,
+            Err(e) => {
+                msg_err(format!("cannot list trashed files: {e}"));
+                std::process::exit(EXITCODE_EXTERNAL);
+            }
+        };
+        let (trashed_files, file_errors) = get_trashed_files(&trash_roots);
+        errors.extend(file_errors);
+
+        // the size filter is only in the TUI, sizes are only needed to sort
+        let mut sizes = HashMap::new();
+        let mut trashed_files: Vec<TrashFile> = trashed_files
+            .into_iter()
+            .filter(|v| filters.matches(v, None))
+            .collect();
+        if sort_type == SortType::Size {
+            for trash_file in &trashed_files {
+                if let Some(v) = &trash_file.files_entry {
+                    sizes.insert(v.clone(), trash_file.get_size().ok());
+                }
+            }
+        }
+        sort_file_list(&mut trashed_files, &sort_type, &sizes);
+        if args_conf.reverse {
+            trashed_files.reverse();
+        }
+        for trash_file in trashed_files {
+            println!("{}", trash_file.original_file.display());
+        }
+
+        if !errors.is_empty() {
+            for e in errors {
+                msg_err(e);
+            }
+            std::process::exit(EXITCODE_EXTERNAL);
+        }
+        std::process::exit(EXITCODE_OK);
+    }
+
+    // a theme by name first, then no colours if NO_COLOR is set, then by the
+    // terminal background
+    let mut theme_errors = vec![];
+    let theme_name = args_conf.theme.or_else(|| env::var("TRASH_RS_THEME").ok());
+    let theme = match theme_name {
+        Some(v) if !v.trim().is_empty() => match Theme::load(v.trim()) {
+            Ok((theme, errors)) => {
+                theme_errors = errors;
+                Some(theme)
+            }
+            Err(e) => {
+                theme_errors.push(format!("cannot load theme '{}': {e}", v.trim()));
+                None
+            }
+        },
+        _ => None,
+    };
+    let no_color = env::var("NO_COLOR").is_ok_and(|v| !v.is_empty());
+
+    // a panic would leave the terminal in raw mode otherwise, with the
+    // message lost in the alternate screen
+    let default_hook = std::panic::take_hook();
+    std::panic::set_hook(Box::new(move |info| {
+        let _ = execute!(io::stderr(), DisableMouseCapture);
+        restore();
+        default_hook(info);
+    }));
+
+    enable_raw_mode()?;
+    // the terminal answers in raw mode, before the TUI reads the input
+    let theme = match theme {
+        Some(v) => v,
+        None if no_color => Theme::no_color(),
+        None => match query_background_is_light() {
+            Some(true) => Theme::built_in(LIGHT_THEME),
+            _ => Theme::built_in(DARK_THEME),
+        },
+    };
+    let mut stderr = io::stderr();
+    execute!(stderr, EnterAlternateScreen, EnableMouseCapture)?;
+
+    let stdout = io::stdout();
+    let backend = CrosstermBackend::new(stdout);
+    let mut terminal = Terminal::new(backend)?;
+
+    let (keymap, keymap_errors) = Keymap::load();
+    let mut app = App::new(theme, keymap);
+    app.sort_type = sort_type;
+    app.sort_reversed = args_conf.reverse;
+    app.filters = filters;
+    for e in theme_errors.into_iter().chain(keymap_errors) {
+        app.notify_error(e);
+    }
+    // without it, the list is only as fresh as the last refresh
+    match TrashWatcher::new() {
+        Ok(v) => app.watcher = Some(v),
+        Err(e) => app.log_error(format!("cannot watch the trash for changes: {e}")),
+    }
+    // the terminal is restored even if the TUI fails
+    let result = run_app(&mut terminal, &mut app);
+
+    disable_raw_mode()?;
+    execute!(
+        terminal.backend_mut(),
+        LeaveAlternateScreen,
+        DisableMouseCapture,
+    )?;
+    terminal.show_cursor()?;
+
+    restore();
+    result
+}
+
+fn run_app(
+    terminal: &mut Terminal<CrosstermBackend<Stdout>>,
+    app: &mut App,
+) -> Result<(), Box<dyn Error>> {
+    let mut redraw = true;
+    loop {
+        match app.state {
+            AppState::RefreshFileList => {
+                app.refresh();
+                app.state = AppState::MainScreen;
+            }
+            AppState::OpenFile(opener) => {
+                // the pager or the editor needs the terminal, and the TUI
+                // picks up where it was afterwards
+                let suspend = opener != Opener::Default;
+                if suspend {
+                    disable_raw_mode()?;
+                    execute!(
+                        terminal.backend_mut(),
+                        LeaveAlternateScreen,
+                        DisableMouseCapture
+                    )?;
+                }
+
+                let result = app.open_selected(opener);
+
+                if suspend {
+                    enable_raw_mode()?;
+                    execute!(
+                        terminal.backend_mut(),
+                        EnterAlternateScreen,
+                        EnableMouseCapture
+                    )?;
+                    terminal.clear()?;
+                }
+
+                if let Err(e) = result {
+                    app.notify_error(format!("{e}"));
+                }
+                app.state = AppState::MainScreen;
+            }
+            AppState::Exiting => {
+                break;
+            }
+            _ => {}
+        }
+
+        if redraw {
+            app.draw(terminal)?;
+        }
+
+        // wake up to pick up changes to the trash, and to hide the
+        // notification when it times out
+        let timeout = match &app.notification {
+            Some(v) => {
+                WATCH_INTERVAL.min(NOTIFICATION_TIMEOUT.saturating_sub(v.shown_at.elapsed()))
+            }
+            None => WATCH_INTERVAL,
+        };
+        redraw = app.notification.is_some();
+        if event::poll(timeout)? {
+            redraw = true;
+            match event::read()? {
+                // ratatui records press and release
+                Event::Key(key) if key.kind != event::KeyEventKind::Release => {
+                    app.handle_input(key)
+                }
+                Event::Mouse(mouse) => app.handle_mouse(mouse),
+                _ => {}
+            }
+        }
+        app.expire_notification();
+        redraw |= app.apply_trash_changes();
+        redraw |= app.receive_sizes();
+    }
+
+    Ok(())
+}
+
+#[derive(Debug, Clone)]
+struct Args {
+    help: bool,                    // -h, --help
+    version: bool,                 // -V, --version
+    list_trashed: bool,            // --list-trashed
+    here: bool,                    // --here
+    sort: Option<SortType>,        // -s, --sort KEY
+    reverse: bool,                 // -r, --reverse
+    root: Option<PathBuf>,         // --root MOUNT
+    theme: Option<String>,         // --theme NAME
+    completions: Option<Shell>,    // --completions SHELL
+    generate_man: Option<PathBuf>, // --generate-man DIR
+    path: Option<String>,          // PATH
+}
+
+impl Args {
+    fn parse(args: Vec<String>) -> Result<Self, Box<dyn Error>> {
+        let mut help: bool = false;
+        let mut version: bool = false;
+        let mut list_trashed: bool = false;
+        let mut here: bool = false;
+        let mut sort: Option<SortType> = None;
+        let mut reverse: bool = false;
+        let mut root: Option<PathBuf> = None;
+        let mut theme: Option<String> = None;
+        let mut completions: Option<Shell> = None;
+        let mut generate_man: Option<PathBuf> = None;
+        let mut path: Option<String> = None;
+        let mut args_iter = args.into_iter();
+        while let Some(arg) = args_iter.next() {
+            // every option accepted is in OPTIONS, so that --help, the man
+            // page, and the completions can't go out of date
+            let (opt, value) = match find_option(OPTIONS, &arg) {
+                Some(v) => v,
+                None => {
+                    if arg.starts_with("-") {
+                        return Err(Box::<dyn Error>::from(format!("invalid option -- '{arg}'")));
+                    }
+
+                    // only one PATH to scope the list to
+                    if path.is_some() {
+                        return Err(Box::<dyn Error>::from(format!("extra operand '{arg}'")));
+                    }
+                    path = Some(arg);
+                    continue;
+                }
+            };
+
+            // --completions=SHELL and --completions SHELL are both accepted
+            let value = match &opt.value {
+                None => {
+                    if value.is_some() {
+                        return Err(Box::<dyn Error>::from(format!("invalid option -- '{arg}'")));
+                    }
+                    None
+                }
+                Some(_) => match value.or_else(|| args_iter.next()) {
+                    Some(v) if !v.is_empty() => Some(v),
+                    _ => {
+                        return Err(Box::<dyn Error>::from(format!(
+                            "option '--{}' requires an argument",
+                            opt.long
+                        )));
+                    }
+                },
+            };
+
+            match opt.long {
+                "help" => help = true,
+                "version" => version = true,
+                "list-trashed" => list_trashed = true,
+                "here" => here = true,
+                "sort" => {
+                    let value = value.unwrap();
+                    sort = match SORT_NAMES.iter().position(|v| *v == value) {
+                        Some(i) => Some(SORT_TYPES[i]),
+                        None => {
+                            return Err(Box::<dyn Error>::from(format!(
+                                "invalid sort key '{value}', expected one of {}",
+                                SORT_NAMES.join(", ")
+                            )));
+                        }
+                    };
+                }
+                "reverse" => reverse = true,
+                "root" => root = value.map(PathBuf::from),
+                "theme" => theme = value,
+                "completions" => completions = Some(Shell::from(&value.unwrap())?),
+                "generate-man" => generate_man = value.map(PathBuf::from),
+                _ => {
+                    return Err(Box::<dyn Error>::from(format!("invalid option -- '{arg}'")));
+                }
+            }
+        }
+
+        if here && path.is_some() {
+            return Err(Box::<dyn Error>::from("--here can't be given with a PATH"));
+        }
+
+        Ok(Args {
+            help,
+            version,
+            list_trashed,
+            here,
+            sort,
+            reverse,
+            root,
+            theme,
+            completions,
+            generate_man,
+            path,
+        })
+    }
+}
+
+// the trash directories of the home mount and other devices mounted as
+// readable
+//
+// only failing to find the home trash is an error, the mounts that can't be
+// looked at are described in the returned errors
+fn get_trash_roots() -> Result<(Vec<TrashDirectory>, Vec<String>), Box<dyn Error>> {
+    // get user trash directory
+    let user_home = get_home_dir()?;
+    let user_trash_dir = TrashDirectory::resolve_for_file(&user_home, VERBOSE_MODE)?;
+
+    let mut errors: Vec<String> = vec![];
+
+    // get all trash locations currently mounted
+    let mut trash_roots: Vec<TrashDirectory> = match TrashDirectory::get_all_trash_roots() {
+        Ok(v) => v,
+        Err(e) => {
+            errors.push(format!("cannot find the trash directories of mounts: {e}"));
+            vec![]
+        }
+    };
+    trash_roots.push(user_trash_dir);
+
+    Ok((trash_roots, errors))
+}
+
+// the filter for --root, the trash directory on the device mounted at
+// mount_point, either a top directory trash or the home trash
+fn get_mount_filter(mount_point: &Path) -> Result<RootFilter, Box<dyn Error>> {
+    let mount_point = normalize_path(&to_abs_path(mount_point)?);
+    let (trash_roots, _) = get_trash_roots()?;
+    if let Some(v) = trash_roots
+        .iter()
+        .find(|v| v.get_top_dir().as_ref() == Some(&mount_point))
+    {
+        return Ok(RootFilter::for_root(v));
+    }
+
+    // the home trash is the last of the roots
+    if let Some(v) = trash_roots.last() {
+        let mut device = v.device.clone();
+        if device.resolve_mount().is_ok() && device.get_mount_point() == Some(&mount_point) {
+            return Ok(RootFilter::Home);
+        }
+    }
+
+    Err(Box::<dyn Error>::from(format!(
+        "no trash directory on '{}'",
+        mount_point.display()
+    )))
+}
+
+// an absolute path without . and .. components, without looking at the
+// filesystem, so that the files trashed from a directory since deleted are
+// still found
+fn normalize_path(path: &Path) -> PathBuf {
+    let mut normalized = PathBuf::new();
+    for component in path.components() {
+        match component {
+            Component::CurDir => {}
+            Component::ParentDir => {
+                normalized.pop();
+            }
+            other => normalized.push(other),
+        }
+    }
+    normalized
+}
+
+// collect trashed files from the trash roots, a trash root or an entry that
+// can't be read is skipped and described in the returned errors
+fn get_trashed_files(trash_roots: &[TrashDirectory]) -> (Vec<TrashFile>, Vec<String>) {
+    let mut errors: Vec<String> = vec![];
+    let mut files: Vec<TrashFile> = vec![];
+    for trash_root in trash_roots {
+        match trash_root.get_trashed_files_and_errors() {
+            Ok((mut trash_files, entry_errors)) => {
+                files.append(&mut trash_files);
+                errors.extend(entry_errors.into_iter().map(|e| format!("{e}")));
+            }
+            Err(e) => {
+                errors.push(format!("cannot read '{}': {e}", trash_root.home.display()));
+            }
+        }
+    }
+
+    (files, errors)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_args() {
+        let i: Vec<String> = vec![];
+        let a = Args::parse(i).unwrap();
+        assert!(a.path.is_none() && !a.here && a.sort.is_none() && !a.reverse);
+
+        let i: Vec<String> = vec![String::from("-r"), String::from(".")];
+        let a = Args::parse(i).unwrap();
+        assert!(a.reverse && a.path == Some(String::from(".")));
+
+        let i: Vec<String> = vec![String::from("--sort=size"), String::from("--here")];
+        let a = Args::parse(i).unwrap();
+        assert!(a.here && a.sort == Some(SortType::Size));
+
+        let i: Vec<String> = vec![
+            String::from("-s"),
+            String::from("name"),
+            String::from("--root"),
+            String::from("/mnt/usb"),
+            String::from("--theme=light"),
+        ];
+        let a = Args::parse(i).unwrap();
+        assert!(a.sort == Some(SortType::FileName));
+        assert!(a.root == Some(PathBuf::from("/mnt/usb")));
+        assert!(a.theme == Some(String::from("light")));
+
+        // one PATH only, and not with --here
+        let i: Vec<String> = vec![String::from("a"), String::from("b")];
+        assert!(Args::parse(i).is_err());
+        let i: Vec<String> = vec![String::from("--here"), String::from("a")];
+        assert!(Args::parse(i).is_err());
+        let i: Vec<String> = vec![String::from("--sort=age")];
+        assert!(Args::parse(i).is_err());
+        let i: Vec<String> = vec![String::from("--root")];
+        assert!(Args::parse(i).is_err());
+    }
+
+    #[test]
+    fn test_normalize_path() {
+        assert!(normalize_path(Path::new("/a/./b/../c")) == Path::new("/a/c"));
+        assert!(normalize_path(Path::new("/a/..")) == Path::new("/"));
+        assert!(normalize_path(Path::new("/..")) == Path::new("/"));
+    }
+}
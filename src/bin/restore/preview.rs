@@ -0,0 +1,968 @@
+use std::error::Error;
+
+use crate::sizes::format_size;
+use chrono::{DateTime, Local};
+use std::fs::File;
+use std::io::{BufRead, BufReader, Read, Seek, SeekFrom};
+use std::path::{Path, PathBuf};
+
+// text previews stop reading long files here
+const PREVIEW_MAX_LINES: usize = 100000;
+
+// bytes read to tell the format of a file, enough for a tar header
+const MAGIC_BYTES_LEN: usize = 512;
+
+// archive members listed in the preview
+const PREVIEW_MAX_MEMBERS: usize = 1000;
+
+// longest gnu long name read from a tar, well past any path a system allows
+const TAR_MAX_LONG_NAME: u64 = 4096;
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum SyntaxToken {
+    Plain,
+    Keyword,
+    String,
+    Comment,
+    Number,
+}
+
+// what the preview highlights in a language
+struct Language {
+    extensions: &'static [&'static str],
+    interpreters: &'static [&'static str], // in a shebang, without the version
+    keywords: &'static str,                // separated by whitespace
+    line_comments: &'static [&'static str],
+    block_comment: Option<(&'static str, &'static str)>,
+    quotes: &'static [char],
+}
+
+const C_LIKE_QUOTES: &[char] = &['"', '\''];
+
+const SCRIPT_QUOTES: &[char] = &['"', '\'', '`'];
+
+const LANGUAGES: &[Language] = &[
+    // rust
+    Language {
+        extensions: &["rs"],
+        interpreters: &[],
+        keywords: "\
+            as async await break const continue crate dyn else enum extern false fn for if \
+            impl in let loop match mod move mut pub ref return self Self static struct super \
+            trait true type unsafe use where while",
+        line_comments: &["//"],
+        block_comment: Some(("/*", "*/")),
+        quotes: &['"'],
+    },
+    // c and c++
+    Language {
+        extensions: &["c", "h", "cc", "cpp", "cxx", "hpp", "hh"],
+        interpreters: &[],
+        keywords: "\
+            auto bool break case catch char class const continue default delete do double \
+            else enum extern false float for goto if include define inline int long \
+            namespace new nullptr private protected public return short signed sizeof static \
+            struct switch template this throw true try typedef union unsigned using virtual \
+            void volatile while",
+        line_comments: &["//"],
+        block_comment: Some(("/*", "*/")),
+        quotes: C_LIKE_QUOTES,
+    },
+    // go
+    Language {
+        extensions: &["go"],
+        interpreters: &[],
+        keywords: "\
+            break case chan const continue default defer else false fallthrough for func go \
+            goto if import interface map nil package range return select struct switch true \
+            type var",
+        line_comments: &["//"],
+        block_comment: Some(("/*", "*/")),
+        quotes: SCRIPT_QUOTES,
+    },
+    // java and kotlin
+    Language {
+        extensions: &["java", "kt", "kts"],
+        interpreters: &[],
+        keywords: "\
+            abstract break case catch class const continue default do else enum extends \
+            false final finally for fun if implements import interface new null object \
+            override package private protected public return static super switch this throw \
+            throws true try val var void when while",
+        line_comments: &["//"],
+        block_comment: Some(("/*", "*/")),
+        quotes: C_LIKE_QUOTES,
+    },
+    // javascript and typescript
+    Language {
+        extensions: &["js", "mjs", "cjs", "jsx", "ts", "tsx"],
+        interpreters: &["node", "deno"],
+        keywords: "\
+            async await break case catch class const continue default delete do else export \
+            extends false finally for from function if import in instanceof interface let \
+            new null of return super switch this throw true try type typeof undefined var \
+            void while yield",
+        line_comments: &["//"],
+        block_comment: Some(("/*", "*/")),
+        quotes: SCRIPT_QUOTES,
+    },
+    // python
+    Language {
+        extensions: &["py", "pyw"],
+        interpreters: &["python"],
+        keywords: "\
+            False None True and as assert async await break class continue def del elif else \
+            except finally for from global if import in is lambda nonlocal not or pass raise \
+            return try while with yield",
+        line_comments: &["#"],
+        block_comment: None,
+        quotes: C_LIKE_QUOTES,
+    },
+    // shell
+    Language {
+        extensions: &["sh", "bash", "zsh", "ksh"],
+        interpreters: &["sh", "bash", "zsh", "dash", "ksh"],
+        keywords: "\
+            case do done elif else esac export fi for function if in local return select \
+            then until while",
+        line_comments: &["#"],
+        block_comment: None,
+        quotes: SCRIPT_QUOTES,
+    },
+    // ruby
+    Language {
+        extensions: &["rb"],
+        interpreters: &["ruby"],
+        keywords: "\
+            begin break case class def do else elsif end ensure false for if in module next \
+            nil not return self super then true unless until when while yield",
+        line_comments: &["#"],
+        block_comment: None,
+        quotes: C_LIKE_QUOTES,
+    },
+    // lua
+    Language {
+        extensions: &["lua"],
+        interpreters: &["lua"],
+        keywords: "\
+            and break do else elseif end false for function if in local nil not or repeat \
+            return then true until while",
+        line_comments: &["--"],
+        block_comment: Some(("--[[", "]]")),
+        quotes: C_LIKE_QUOTES,
+    },
+    // sql
+    Language {
+        extensions: &["sql"],
+        interpreters: &[],
+        keywords: "\
+            and as by create delete drop from group insert into join not null or order \
+            select set table update values where AND AS BY CREATE DELETE DROP FROM GROUP \
+            INSERT INTO JOIN NOT NULL OR ORDER SELECT SET TABLE UPDATE VALUES WHERE",
+        line_comments: &["--"],
+        block_comment: Some(("/*", "*/")),
+        quotes: &['\''],
+    },
+    // config files
+    Language {
+        extensions: &[
+            "toml", "ini", "cfg", "conf", "yml", "yaml", "desktop", "service",
+        ],
+        interpreters: &[],
+        keywords: "true false yes no on off null",
+        line_comments: &["#", ";"],
+        block_comment: None,
+        quotes: C_LIKE_QUOTES,
+    },
+    // json
+    Language {
+        extensions: &["json"],
+        interpreters: &[],
+        keywords: "true false null",
+        line_comments: &[],
+        block_comment: None,
+        quotes: &['"'],
+    },
+    // markup
+    Language {
+        extensions: &["html", "htm", "xml", "svg"],
+        interpreters: &[],
+        keywords: "",
+        line_comments: &[],
+        block_comment: Some(("<!--", "-->")),
+        quotes: &['"'],
+    },
+];
+
+impl Language {
+    fn for_path(path: &Path) -> Option<&'static Language> {
+        let extension = path.extension()?.to_str()?.to_lowercase();
+        LANGUAGES
+            .iter()
+            .find(|l| l.extensions.contains(&extension.as_str()))
+    }
+
+    // #!/bin/sh, #!/usr/bin/env python3
+    fn for_shebang(line: &str) -> Option<&'static Language> {
+        let command = line.strip_prefix("#!")?;
+        let mut words = command.split_whitespace();
+        let mut interpreter = Path::new(words.next()?).file_name()?.to_str()?;
+        if interpreter == "env" {
+            interpreter = words.find(|v| !v.starts_with('-'))?;
+        }
+        let interpreter = interpreter.trim_end_matches(|c: char| c.is_ascii_digit() || c == '.');
+        LANGUAGES
+            .iter()
+            .find(|l| l.interpreters.contains(&interpreter))
+    }
+}
+
+// a text file, read as far as the preview has been scrolled
+pub struct TextPreview {
+    pub path: PathBuf,
+    pub reader: Option<BufReader<File>>, // None after the end of the file
+    language: Option<&'static Language>,
+    in_block_comment: bool,
+    pub lines: Vec<Vec<(SyntaxToken, String)>>,
+    pub is_binary: bool,
+}
+
+impl TextPreview {
+    pub fn open(path: &Path) -> Result<Self, Box<dyn Error>> {
+        Ok(TextPreview {
+            path: path.to_path_buf(),
+            reader: Some(BufReader::new(File::open(path)?)),
+            language: Language::for_path(path),
+            in_block_comment: false,
+            lines: vec![],
+            is_binary: false,
+        })
+    }
+
+    // read up to the given number of lines, or to the end of the file
+    pub fn read_to(&mut self, line_count: usize) {
+        let line_count = line_count.min(PREVIEW_MAX_LINES);
+        while self.lines.len() < line_count {
+            let Some(reader) = self.reader.as_mut() else {
+                break;
+            };
+            let mut line_buff = vec![];
+            if matches!(reader.read_until(b'\n', &mut line_buff), Ok(0) | Err(_)) {
+                self.reader = None;
+                break;
+            }
+
+            // some files could be non-text even though the first lines are
+            // textual
+            let Ok(line) = String::from_utf8(line_buff) else {
+                self.is_binary = true;
+                self.reader = None;
+                break;
+            };
+            let line = line.trim_end_matches(['\n', '\r']).replace('\t', "    ");
+            if self.lines.is_empty() && self.language.is_none() {
+                self.language = Language::for_shebang(&line);
+            }
+            let tokens = highlight_line(&line, self.language, &mut self.in_block_comment);
+            self.lines.push(tokens);
+        }
+
+        // the file could end right after the last line read
+        if let Some(reader) = self.reader.as_mut() {
+            if matches!(reader.fill_buf().map(|b| b.is_empty()), Ok(true)) {
+                self.reader = None;
+            }
+        }
+    }
+}
+
+// file formats told by their first bytes
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum FileMagic {
+    Elf,
+    Png,
+    Jpeg,
+    Gif,
+    Tar,
+    Zip,
+    Gzip,
+}
+
+impl FileMagic {
+    fn detect(bytes: &[u8]) -> Option<Self> {
+        if bytes.starts_with(b"\x7fELF") {
+            Some(FileMagic::Elf)
+        } else if bytes.starts_with(b"\x89PNG\r\n\x1a\n") {
+            Some(FileMagic::Png)
+        } else if bytes.starts_with(&[0xff, 0xd8, 0xff]) {
+            Some(FileMagic::Jpeg)
+        } else if bytes.starts_with(b"GIF87a") || bytes.starts_with(b"GIF89a") {
+            Some(FileMagic::Gif)
+        } else if bytes.starts_with(b"PK\x03\x04") || bytes.starts_with(b"PK\x05\x06") {
+            Some(FileMagic::Zip)
+        } else if bytes.starts_with(&[0x1f, 0x8b]) {
+            Some(FileMagic::Gzip)
+        } else if bytes.get(257..262) == Some(b"ustar") {
+            Some(FileMagic::Tar)
+        } else {
+            None
+        }
+    }
+
+    fn description(&self) -> &'static str {
+        match self {
+            FileMagic::Elf => "ELF binary",
+            FileMagic::Png => "PNG image",
+            FileMagic::Jpeg => "JPEG image",
+            FileMagic::Gif => "GIF image",
+            FileMagic::Tar => "tar archive",
+            FileMagic::Zip => "zip archive",
+            FileMagic::Gzip => "gzip archive",
+        }
+    }
+}
+
+// members of an archive as size and name, with how many there are
+type ArchiveListing = (Vec<(u64, String)>, String);
+
+// a binary file, a summary of a known format with the members of an archive,
+// and a hex dump read as far as it's scrolled
+pub struct BinaryPreview {
+    pub path: PathBuf,
+    pub len: u64,
+    pub summary: Vec<(String, String)>,
+    pub members: Vec<(u64, String)>, // size, name
+    pub hex_dump: bool,
+}
+
+impl BinaryPreview {
+    pub fn open(path: &Path) -> Result<Self, Box<dyn Error>> {
+        let mut file = File::open(path)?;
+        let len = file.metadata()?.len();
+        let mut header = vec![];
+        (&mut file)
+            .take(MAGIC_BYTES_LEN as u64)
+            .read_to_end(&mut header)?;
+
+        let mut preview = BinaryPreview {
+            path: path.to_path_buf(),
+            len,
+            summary: vec![],
+            members: vec![],
+            hex_dump: true,
+        };
+        let Some(magic) = FileMagic::detect(&header) else {
+            return Ok(preview);
+        };
+
+        preview
+            .summary
+            .push(("Format".to_string(), magic.description().to_string()));
+        let result = match magic {
+            FileMagic::Elf => read_elf_summary(&header),
+            FileMagic::Png => read_png_summary(&header),
+            FileMagic::Gif => read_gif_summary(&header),
+            FileMagic::Jpeg => read_jpeg_summary(&mut file),
+            FileMagic::Gzip => read_gzip_summary(&mut file, &header, len),
+            FileMagic::Tar | FileMagic::Zip => {
+                let members = match magic {
+                    FileMagic::Tar => read_tar_members(&mut file),
+                    _ => read_zip_members(&mut file, len),
+                };
+                members.map(|(members, count)| {
+                    let total: u64 = members.iter().map(|(size, _)| size).sum();
+                    preview.members = members;
+                    preview.hex_dump = false;
+                    vec![
+                        ("Members".to_string(), count),
+                        ("Total size".to_string(), format_size(total)),
+                    ]
+                })
+            }
+        };
+        match result {
+            Ok(v) => preview.summary.extend(v),
+            Err(e) => preview.summary.push(("Error".to_string(), e.to_string())),
+        }
+
+        Ok(preview)
+    }
+
+    // bytes shown in a hex dump line that fits in the width, in groups of 4
+    pub fn hex_dump_width(width: usize) -> usize {
+        // "00000000  " + "xx " per byte + " " per group + the byte itself
+        let bytes = width.saturating_sub(10) * 4 / 17;
+        (bytes / 4 * 4).clamp(4, 16)
+    }
+}
+
+// split a line into the tokens to highlight. block comments can carry on to
+// the next lines
+fn highlight_line(
+    line: &str,
+    language: Option<&Language>,
+    in_block_comment: &mut bool,
+) -> Vec<(SyntaxToken, String)> {
+    let Some(language) = language else {
+        return vec![(SyntaxToken::Plain, line.to_string())];
+    };
+
+    let mut tokens: Vec<(SyntaxToken, String)> = vec![];
+    let mut push = |token: SyntaxToken, text: &str| match tokens.last_mut() {
+        Some((last, last_text)) if *last == token => last_text.push_str(text),
+        _ => tokens.push((token, text.to_string())),
+    };
+
+    let mut i = 0;
+    while i < line.len() {
+        let rest = &line[i..];
+        let c = rest.chars().next().unwrap_or_default();
+
+        if *in_block_comment {
+            let end = language.block_comment.map(|(_, v)| v).unwrap_or_default();
+            match rest.find(end) {
+                Some(j) => {
+                    push(SyntaxToken::Comment, &rest[..j + end.len()]);
+                    *in_block_comment = false;
+                    i += j + end.len();
+                }
+                None => {
+                    push(SyntaxToken::Comment, rest);
+                    break;
+                }
+            }
+        } else if let Some((start, _)) = language
+            .block_comment
+            .filter(|(start, _)| rest.starts_with(start))
+        {
+            push(SyntaxToken::Comment, start);
+            *in_block_comment = true;
+            i += start.len();
+        } else if language.line_comments.iter().any(|v| rest.starts_with(v)) {
+            push(SyntaxToken::Comment, rest);
+            break;
+        } else if language.quotes.contains(&c) {
+            // up to the closing quote that isn't escaped, or the end of the
+            // line
+            let mut end = rest.len();
+            let mut escaped = false;
+            for (j, v) in rest.char_indices().skip(1) {
+                if escaped {
+                    escaped = false;
+                } else if v == '\\' {
+                    escaped = true;
+                } else if v == c {
+                    end = j + v.len_utf8();
+                    break;
+                }
+            }
+            push(SyntaxToken::String, &rest[..end]);
+            i += end;
+        } else if c.is_ascii_digit() {
+            let end = rest
+                .find(|v: char| !(v.is_ascii_alphanumeric() || v == '.' || v == '_'))
+                .unwrap_or(rest.len());
+            push(SyntaxToken::Number, &rest[..end]);
+            i += end;
+        } else if c.is_alphabetic() || c == '_' {
+            let end = rest
+                .find(|v: char| !(v.is_alphanumeric() || v == '_'))
+                .unwrap_or(rest.len());
+            let word = &rest[..end];
+            if language.keywords.split_whitespace().any(|v| v == word) {
+                push(SyntaxToken::Keyword, word);
+            } else {
+                push(SyntaxToken::Plain, word);
+            }
+            i += end;
+        } else {
+            push(SyntaxToken::Plain, &rest[..c.len_utf8()]);
+            i += c.len_utf8();
+        }
+    }
+
+    tokens
+}
+
+// the format of a file from its first bytes
+pub fn read_file_magic(path: &Path) -> Option<FileMagic> {
+    let mut bytes = vec![];
+    File::open(path)
+        .ok()?
+        .take(MAGIC_BYTES_LEN as u64)
+        .read_to_end(&mut bytes)
+        .ok()?;
+    FileMagic::detect(&bytes)
+}
+
+// the type of a file shown in the description, by the format for files
+pub fn get_file_type(path: &Path) -> String {
+    if path.is_symlink() {
+        return "Link".to_string();
+    } else if path.is_dir() {
+        return "Directory".to_string();
+    }
+
+    let mut bytes = vec![];
+    let read =
+        File::open(path).and_then(|f| f.take(MAGIC_BYTES_LEN as u64).read_to_end(&mut bytes));
+    if read.is_err() {
+        return "File".to_string();
+    }
+    if let Some(magic) = FileMagic::detect(&bytes) {
+        return magic.description().to_string();
+    }
+
+    // a character cut off at the end of the bytes read is still text
+    let is_text = match std::str::from_utf8(&bytes) {
+        Ok(v) => !v.contains('\0'),
+        Err(e) => e.error_len().is_none() && !bytes.contains(&0),
+    };
+    if bytes.is_empty() {
+        "Empty file".to_string()
+    } else if is_text {
+        "Text".to_string()
+    } else {
+        "Binary".to_string()
+    }
+}
+
+// the hex and printable ascii columns of a hex dump line, with spaces in
+// place of the bytes past the end of the file
+pub fn format_hex_line(bytes: &[u8], bytes_per_line: usize) -> (String, String) {
+    let mut hex = String::new();
+    for i in 0..bytes_per_line {
+        match bytes.get(i) {
+            Some(v) => hex.push_str(&format!("{v:02x} ")),
+            None => hex.push_str("   "),
+        }
+        if i % 4 == 3 {
+            hex.push(' ');
+        }
+    }
+    let ascii = bytes
+        .iter()
+        .map(|&v| {
+            if v.is_ascii_graphic() || v == b' ' {
+                v as char
+            } else {
+                '.'
+            }
+        })
+        .collect();
+    (hex, ascii)
+}
+
+fn read_elf_summary(header: &[u8]) -> Result<Vec<(String, String)>, Box<dyn Error>> {
+    if header.len() < 52 {
+        return Err(Box::<dyn Error>::from("truncated ELF header"));
+    }
+    let is_64_bit = header[4] == 2;
+    let is_little_endian = header[5] == 1;
+    let read_uint = |start: usize, len: usize| -> u64 {
+        let bytes = &header[start..start + len];
+        let mut value = 0;
+        for i in 0..len {
+            let byte = if is_little_endian {
+                bytes[len - 1 - i]
+            } else {
+                bytes[i]
+            };
+            value = (value << 8) | byte as u64;
+        }
+        value
+    };
+
+    let class = format!(
+        "{}-bit {}",
+        if is_64_bit { 64 } else { 32 },
+        if is_little_endian {
+            "little endian"
+        } else {
+            "big endian"
+        }
+    );
+    let elf_type = match read_uint(16, 2) {
+        1 => "relocatable".to_string(),
+        2 => "executable".to_string(),
+        3 => "shared object".to_string(),
+        4 => "core dump".to_string(),
+        v => format!("unknown ({v})"),
+    };
+    let machine = match read_uint(18, 2) {
+        0x03 => "x86".to_string(),
+        0x08 => "MIPS".to_string(),
+        0x14 => "PowerPC".to_string(),
+        0x15 => "PowerPC 64".to_string(),
+        0x28 => "ARM".to_string(),
+        0x3e => "x86-64".to_string(),
+        0xb7 => "AArch64".to_string(),
+        0xf3 => "RISC-V".to_string(),
+        v => format!("unknown ({v:#x})"),
+    };
+    let entry = read_uint(24, if is_64_bit { 8 } else { 4 });
+
+    Ok(vec![
+        ("Class".to_string(), class),
+        ("Type".to_string(), elf_type),
+        ("Machine".to_string(), machine),
+        ("Entry point".to_string(), format!("{entry:#x}")),
+    ])
+}
+
+fn read_png_summary(header: &[u8]) -> Result<Vec<(String, String)>, Box<dyn Error>> {
+    // the IHDR chunk comes first
+    if header.len() < 26 || &header[12..16] != b"IHDR" {
+        return Err(Box::<dyn Error>::from("missing PNG header chunk"));
+    }
+    let width = u32::from_be_bytes([header[16], header[17], header[18], header[19]]);
+    let height = u32::from_be_bytes([header[20], header[21], header[22], header[23]]);
+    let color = match header[25] {
+        0 => "grayscale",
+        2 => "RGB",
+        3 => "indexed",
+        4 => "grayscale with alpha",
+        6 => "RGBA",
+        _ => "unknown",
+    };
+
+    Ok(vec![
+        ("Dimensions".to_string(), format!("{width}x{height}")),
+        ("Color".to_string(), format!("{color}, {} bits", header[24])),
+    ])
+}
+
+fn read_gif_summary(header: &[u8]) -> Result<Vec<(String, String)>, Box<dyn Error>> {
+    if header.len() < 10 {
+        return Err(Box::<dyn Error>::from("truncated GIF header"));
+    }
+    let width = u16::from_le_bytes([header[6], header[7]]);
+    let height = u16::from_le_bytes([header[8], header[9]]);
+
+    Ok(vec![
+        (
+            "Version".to_string(),
+            String::from_utf8_lossy(&header[..6]).to_string(),
+        ),
+        ("Dimensions".to_string(), format!("{width}x{height}")),
+    ])
+}
+
+// the dimensions are in the start of frame segment, after the metadata ones
+fn read_jpeg_summary<R: Read + Seek>(
+    file: &mut R,
+) -> Result<Vec<(String, String)>, Box<dyn Error>> {
+    file.seek(SeekFrom::Start(2))?;
+    loop {
+        let mut marker = [0; 4];
+        file.read_exact(&mut marker)?;
+        if marker[0] != 0xff {
+            return Err(Box::<dyn Error>::from("malformed JPEG segment"));
+        }
+        let segment_len = u16::from_be_bytes([marker[2], marker[3]]) as i64;
+
+        // start of frame markers, except the ones for huffman tables,
+        // arithmetic coding, and the rest of JPEG
+        if (0xc0..=0xcf).contains(&marker[1]) && ![0xc4, 0xc8, 0xcc].contains(&marker[1]) {
+            let mut frame = [0; 6];
+            file.read_exact(&mut frame)?;
+            let height = u16::from_be_bytes([frame[1], frame[2]]);
+            let width = u16::from_be_bytes([frame[3], frame[4]]);
+            let color = match frame[5] {
+                1 => "grayscale",
+                3 => "YCbCr",
+                4 => "CMYK",
+                _ => "unknown",
+            };
+            let encoding = if marker[1] == 0xc2 {
+                "progressive"
+            } else {
+                "baseline"
+            };
+            return Ok(vec![
+                ("Dimensions".to_string(), format!("{width}x{height}")),
+                ("Color".to_string(), format!("{color}, {} bits", frame[0])),
+                ("Encoding".to_string(), encoding.to_string()),
+            ]);
+        }
+        if marker[1] == 0xda {
+            return Err(Box::<dyn Error>::from("missing JPEG frame header"));
+        }
+        file.seek(SeekFrom::Current(segment_len - 2))?;
+    }
+}
+
+// a gzip file doesn't list its members, only the name of the compressed file
+fn read_gzip_summary<R: Read + Seek>(
+    file: &mut R,
+    header: &[u8],
+    len: u64,
+) -> Result<Vec<(String, String)>, Box<dyn Error>> {
+    if header.len() < 10 || len < 18 {
+        return Err(Box::<dyn Error>::from("truncated gzip header"));
+    }
+    let mut summary = vec![];
+
+    // the name comes after the optional extra field
+    let flags = header[3];
+    if flags & 0x08 != 0 {
+        let mut start = 10;
+        if flags & 0x04 != 0 {
+            let extra_len = header
+                .get(10..12)
+                .map(|v| u16::from_le_bytes([v[0], v[1]]) as usize)
+                .unwrap_or_default();
+            start += 2 + extra_len;
+        }
+        if let Some(name) = header.get(start..) {
+            let end = name.iter().position(|&v| v == 0).unwrap_or(name.len());
+            summary.push((
+                "Name".to_string(),
+                String::from_utf8_lossy(&name[..end]).to_string(),
+            ));
+        }
+    }
+
+    let mtime = u32::from_le_bytes([header[4], header[5], header[6], header[7]]);
+    if let Some(v) = DateTime::from_timestamp(mtime as i64, 0).filter(|_| mtime != 0) {
+        summary.push((
+            "Modified".to_string(),
+            v.with_timezone(&Local)
+                .format("%Y-%m-%dT%H:%M:%S")
+                .to_string(),
+        ));
+    }
+
+    // the size is stored modulo 4GB, for the last member only
+    let mut size = [0; 4];
+    file.seek(SeekFrom::End(-4))?;
+    file.read_exact(&mut size)?;
+    summary.push((
+        "Uncompressed size".to_string(),
+        format_size(u32::from_le_bytes(size) as u64),
+    ));
+
+    Ok(summary)
+}
+
+// members of a tar archive, up to PREVIEW_MAX_MEMBERS, with how many there are
+fn read_tar_members<R: Read + Seek>(file: &mut R) -> Result<ArchiveListing, Box<dyn Error>> {
+    let mut members = vec![];
+    let mut long_name: Option<String> = None;
+    let mut offset = 0;
+    loop {
+        let mut header = [0; 512];
+        file.seek(SeekFrom::Start(offset))?;
+        if file.read_exact(&mut header).is_err() || header.iter().all(|&v| v == 0) {
+            break;
+        }
+        if members.len() == PREVIEW_MAX_MEMBERS {
+            return Ok((members, format!("{PREVIEW_MAX_MEMBERS}+")));
+        }
+
+        let field = |start: usize, len: usize| {
+            let bytes = &header[start..start + len];
+            let end = bytes.iter().position(|&v| v == 0).unwrap_or(len);
+            String::from_utf8_lossy(&bytes[..end]).to_string()
+        };
+        let size = u64::from_str_radix(field(124, 12).trim(), 8).unwrap_or_default();
+        let type_flag = header[156];
+        let data_len = size.div_ceil(512) * 512;
+
+        // a gnu long name is the data of its own entry, for the next one
+        if type_flag == b'L' {
+            // the size is only what the header claims, don't allocate for it
+            if size > TAR_MAX_LONG_NAME {
+                return Err(format!("tar long name of {size} bytes is too long").into());
+            }
+            let mut name = vec![0; size as usize];
+            file.read_exact(&mut name)?;
+            let end = name.iter().position(|&v| v == 0).unwrap_or(name.len());
+            long_name = Some(String::from_utf8_lossy(&name[..end]).to_string());
+            offset += 512 + data_len;
+            continue;
+        }
+
+        let mut name = match long_name.take() {
+            Some(v) => v,
+            None => {
+                let prefix = field(345, 155);
+                if &header[257..262] == b"ustar" && !prefix.is_empty() {
+                    format!("{prefix}/{}", field(0, 100))
+                } else {
+                    field(0, 100)
+                }
+            }
+        };
+        if type_flag == b'5' && !name.ends_with('/') {
+            name.push('/');
+        }
+        // pax headers only describe the next entry
+        if type_flag != b'x' && type_flag != b'g' {
+            members.push((size, name));
+        }
+        offset += 512 + data_len;
+    }
+
+    let count = members.len().to_string();
+    Ok((members, count))
+}
+
+// members of a zip archive from its central directory at the end
+fn read_zip_members<R: Read + Seek>(
+    file: &mut R,
+    len: u64,
+) -> Result<ArchiveListing, Box<dyn Error>> {
+    // the end of central directory record is followed by a comment of up to
+    // 64KB
+    let tail_len = len.min(22 + 65535);
+    let mut tail = vec![0; tail_len as usize];
+    file.seek(SeekFrom::Start(len - tail_len))?;
+    file.read_exact(&mut tail)?;
+    let end = (0..tail.len().saturating_sub(21))
+        .rev()
+        .find(|&i| tail[i..].starts_with(b"PK\x05\x06"))
+        .ok_or_else(|| Box::<dyn Error>::from("missing zip central directory"))?;
+    let record = &tail[end..];
+    let count = u16::from_le_bytes([record[10], record[11]]) as usize;
+    let directory_start =
+        u32::from_le_bytes([record[16], record[17], record[18], record[19]]) as u64;
+
+    let mut members = vec![];
+    file.seek(SeekFrom::Start(directory_start))?;
+    for _ in 0..count.min(PREVIEW_MAX_MEMBERS) {
+        let mut header = [0; 46];
+        file.read_exact(&mut header)?;
+        if !header.starts_with(b"PK\x01\x02") {
+            return Err(Box::<dyn Error>::from("malformed zip central directory"));
+        }
+        let size = u32::from_le_bytes([header[24], header[25], header[26], header[27]]) as u64;
+        let name_len = u16::from_le_bytes([header[28], header[29]]) as usize;
+        let skip_len = u16::from_le_bytes([header[30], header[31]]) as i64
+            + u16::from_le_bytes([header[32], header[33]]) as i64;
+        let mut name = vec![0; name_len];
+        file.read_exact(&mut name)?;
+        file.seek(SeekFrom::Current(skip_len))?;
+        members.push((size, String::from_utf8_lossy(&name).to_string()));
+    }
+
+    let count = if count > PREVIEW_MAX_MEMBERS {
+        format!("{count}")
+    } else {
+        members.len().to_string()
+    };
+    Ok((members, count))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io;
+
+    #[test]
+    fn test_highlight_line() {
+        let rust = Language::for_path(Path::new("main.rs"));
+        let mut in_block_comment = false;
+        assert_eq!(
+            highlight_line(r#"let x = "a // \"b"; // c"#, rust, &mut in_block_comment),
+            vec![
+                (SyntaxToken::Keyword, "let".to_string()),
+                (SyntaxToken::Plain, " x = ".to_string()),
+                (SyntaxToken::String, r#""a // \"b""#.to_string()),
+                (SyntaxToken::Plain, "; ".to_string()),
+                (SyntaxToken::Comment, "// c".to_string()),
+            ]
+        );
+
+        // block comments carry on to the next line
+        assert_eq!(
+            highlight_line("x1 = 2; /* a", rust, &mut in_block_comment),
+            vec![
+                (SyntaxToken::Plain, "x1 = ".to_string()),
+                (SyntaxToken::Number, "2".to_string()),
+                (SyntaxToken::Plain, "; ".to_string()),
+                (SyntaxToken::Comment, "/* a".to_string()),
+            ]
+        );
+        assert!(in_block_comment);
+        assert_eq!(
+            highlight_line("b */ fn", rust, &mut in_block_comment),
+            vec![
+                (SyntaxToken::Comment, "b */".to_string()),
+                (SyntaxToken::Plain, " ".to_string()),
+                (SyntaxToken::Keyword, "fn".to_string()),
+            ]
+        );
+        assert!(!in_block_comment);
+
+        assert_eq!(
+            highlight_line("fn x", None, &mut in_block_comment),
+            vec![(SyntaxToken::Plain, "fn x".to_string())]
+        );
+    }
+
+    #[test]
+    fn test_language_for_shebang() {
+        let python = Language::for_path(Path::new("a.py")).map(|v| v.extensions);
+        assert!(Language::for_shebang("#!/usr/bin/env python3").map(|v| v.extensions) == python);
+        assert!(Language::for_shebang("#!/usr/bin/python3.11 -u").map(|v| v.extensions) == python);
+        assert!(Language::for_shebang("#!/bin/sh").is_some());
+        assert!(Language::for_shebang("#!/usr/bin/awk -f").is_none());
+        assert!(Language::for_shebang("# not a shebang").is_none());
+    }
+
+    #[test]
+    fn test_file_magic() {
+        let mut elf = b"\x7fELF\x02\x01\x01".to_vec();
+        elf.resize(64, 0);
+        elf[16] = 3; // shared object
+        elf[18] = 0x3e; // x86-64
+        elf[24] = 0x40;
+        assert_eq!(FileMagic::detect(&elf), Some(FileMagic::Elf));
+        assert_eq!(
+            read_elf_summary(&elf).unwrap(),
+            vec![
+                ("Class".to_string(), "64-bit little endian".to_string()),
+                ("Type".to_string(), "shared object".to_string()),
+                ("Machine".to_string(), "x86-64".to_string()),
+                ("Entry point".to_string(), "0x40".to_string()),
+            ]
+        );
+
+        let mut png = b"\x89PNG\r\n\x1a\n\0\0\0\x0dIHDR".to_vec();
+        png.extend([0, 0, 1, 0, 0, 0, 0, 0x80, 8, 6]);
+        assert_eq!(FileMagic::detect(&png), Some(FileMagic::Png));
+        assert_eq!(
+            read_png_summary(&png).unwrap()[0],
+            ("Dimensions".to_string(), "256x128".to_string())
+        );
+
+        let mut tar = vec![0; 1024 + 3 * 512];
+        tar[..5].copy_from_slice(b"a.txt");
+        tar[124..135].copy_from_slice(b"00000000003");
+        tar[156] = b'0';
+        tar[257..262].copy_from_slice(b"ustar");
+        assert_eq!(FileMagic::detect(&tar), Some(FileMagic::Tar));
+        assert_eq!(
+            read_tar_members(&mut io::Cursor::new(tar)).unwrap(),
+            (vec![(3, "a.txt".to_string())], "1".to_string())
+        );
+
+        // a long name claiming gigabytes is refused before it's read
+        let mut tar = vec![0; 1024 + 512];
+        tar[..13].copy_from_slice(b"././@LongLink");
+        tar[124..135].copy_from_slice(b"77777777777");
+        tar[156] = b'L';
+        assert!(read_tar_members(&mut io::Cursor::new(tar)).is_err());
+
+        assert_eq!(FileMagic::detect(b"#!/bin/sh"), None);
+    }
+
+    #[test]
+    fn test_format_hex_line() {
+        assert_eq!(
+            format_hex_line(b"AB\n", 8),
+            ("41 42 0a                  ".to_string(), "AB.".to_string())
+        );
+    }
+}
@@ -0,0 +1,68 @@
+use std::error::Error;
+
+use libtrash::*;
+use std::path::{Path, PathBuf};
+use std::sync::{mpsc, Arc, Mutex};
+use std::thread;
+
+// at most this many directories are walked for their size at once
+const SIZE_WORKER_THREADS: usize = 4;
+
+// threads walking directories for their size, so that a large one doesn't
+// hold up the TUI. the results come back in the order they're done
+pub struct SizeWorker {
+    pub requests: mpsc::Sender<PathBuf>,
+    pub results: mpsc::Receiver<(PathBuf, Option<u64>)>,
+}
+
+impl SizeWorker {
+    pub fn new() -> Self {
+        let (requests, request_receiver) = mpsc::channel::<PathBuf>();
+        let (result_sender, results) = mpsc::channel();
+        let request_receiver = Arc::new(Mutex::new(request_receiver));
+        let threads = thread::available_parallelism()
+            .map(|v| v.get())
+            .unwrap_or(1)
+            .min(SIZE_WORKER_THREADS);
+        for _ in 0..threads {
+            let request_receiver = Arc::clone(&request_receiver);
+            let result_sender = result_sender.clone();
+            // the threads stop once the TUI is gone and the channels close
+            thread::spawn(move || loop {
+                let path = match request_receiver.lock().map(|v| v.recv()) {
+                    Ok(Ok(v)) => v,
+                    _ => break,
+                };
+                let size = get_entry_size(&path).ok();
+                if result_sender.send((path, size)).is_err() {
+                    break;
+                }
+            });
+        }
+
+        SizeWorker { requests, results }
+    }
+}
+
+// the size of a files entry or a path in it, the same as TrashFile::get_size
+fn get_entry_size(path: &Path) -> Result<u64, Box<dyn Error>> {
+    let metadata = path.symlink_metadata()?;
+    if metadata.is_dir() {
+        get_dir_size(&path.to_path_buf())
+    } else {
+        Ok(metadata.len())
+    }
+}
+
+// human readable size, in SI units
+pub fn format_size(size: u64) -> String {
+    if size < 1000 {
+        format!("{size}B")
+    } else if size < 1000000 {
+        format!("{}KB", size / 1000)
+    } else if size < 1000000000 {
+        format!("{}MB", size / 1000000)
+    } else {
+        format!("{}GB", size / 1000000000)
+    }
+}
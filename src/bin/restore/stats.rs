@@ -0,0 +1,192 @@
+use chrono::{DateTime, Days, Local};
+use libtrash::*;
+use std::collections::HashMap;
+use std::path::PathBuf;
+
+// the stats screen: entries listed in each top list, the bars of the
+// deletions histogram, and the purge presets on 1 to 4
+const STATS_TOP_ENTRIES: usize = 5;
+
+pub const STATS_HISTOGRAM_DAYS: usize = 14;
+
+pub const STATS_HISTOGRAM_WEEKS: usize = 12;
+
+pub const STATS_HISTOGRAM_WIDTH: usize = 30;
+
+pub const PURGE_OLDER_THAN_DAYS: &[i64] = &[7, 30, 90, 365];
+
+// what the stats screen shows, counted when it's opened
+pub struct TrashStats {
+    pub entries: Vec<(DateTime<Local>, Option<u64>)>, // deletion date and size, if known yet
+    pub calculating: usize,                           // entries without a size yet
+    pub files: usize,
+    pub dirs: usize,
+    pub links: usize,
+    pub largest: Vec<(u64, PathBuf)>,
+    pub oldest: Vec<(DateTime<Local>, PathBuf)>,
+}
+
+impl TrashStats {
+    pub fn from(trash_files: &[TrashFile], sizes: &HashMap<PathBuf, Option<u64>>) -> Self {
+        let mut stats = TrashStats {
+            entries: vec![],
+            calculating: 0,
+            files: 0,
+            dirs: 0,
+            links: 0,
+            largest: vec![],
+            oldest: vec![],
+        };
+
+        for trash_file in trash_files {
+            let Some(trashinfo) = &trash_file.trashinfo else {
+                continue;
+            };
+            let metadata = trash_file
+                .files_entry
+                .as_ref()
+                .and_then(|v| v.symlink_metadata().ok());
+            match metadata {
+                Some(v) if v.is_symlink() => stats.links += 1,
+                Some(v) if v.is_dir() => stats.dirs += 1,
+                _ => stats.files += 1,
+            }
+
+            let deletion_date = trashinfo.get_deletion_date();
+            let size = match trash_file.files_entry.as_ref().and_then(|v| sizes.get(v)) {
+                Some(v) => Some(v.unwrap_or(0)),
+                None => {
+                    stats.calculating += 1;
+                    None
+                }
+            };
+            stats.entries.push((deletion_date, size));
+            // entries still being calculated can't be ranked yet
+            if let Some(size) = size {
+                stats.largest.push((size, trash_file.original_file.clone()));
+            }
+            stats
+                .oldest
+                .push((deletion_date, trash_file.original_file.clone()));
+        }
+
+        stats.largest.sort_by_key(|v| std::cmp::Reverse(v.0));
+        stats.largest.truncate(STATS_TOP_ENTRIES);
+        stats.oldest.sort_by_key(|v| v.0);
+        stats.oldest.truncate(STATS_TOP_ENTRIES);
+        stats
+    }
+
+    // entries and bytes a purge of what's older than the days would delete,
+    // no bytes while any of those entries is still being calculated
+    pub fn get_purge_projection(&self, days: i64, now: DateTime<Local>) -> (usize, Option<u64>) {
+        self.entries
+            .iter()
+            .filter(|(date, _)| get_age_days(*date, now) > days)
+            .fold((0, Some(0)), |(count, size), (_, v)| {
+                (count + 1, size.zip(*v).map(|(a, b)| a + b))
+            })
+    }
+}
+
+// calendar days since the date
+pub fn get_age_days(date: DateTime<Local>, now: DateTime<Local>) -> i64 {
+    (now.date_naive() - date.date_naive()).num_days()
+}
+
+// deletions counted per day or week, the most recent first, labelled with
+// the day or the first day of the week
+pub fn get_deletion_histogram(
+    dates: &[DateTime<Local>],
+    now: DateTime<Local>,
+    by_week: bool,
+    buckets: usize,
+) -> Vec<(String, usize)> {
+    let days_per_bucket = if by_week { 7 } else { 1 };
+    let mut counts = vec![0; buckets];
+    for date in dates {
+        let age = get_age_days(*date, now);
+        if age < 0 {
+            continue;
+        }
+        if let Some(v) = counts.get_mut((age / days_per_bucket) as usize) {
+            *v += 1;
+        }
+    }
+
+    counts
+        .into_iter()
+        .enumerate()
+        .map(|(i, count)| {
+            let first_day = now
+                .date_naive()
+                .checked_sub_days(Days::new((i as u64 + 1) * days_per_bucket as u64 - 1))
+                .unwrap_or_default();
+            (first_day.format("%m-%d").to_string(), count)
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    #[test]
+    fn test_get_deletion_histogram() {
+        let now = Local.with_ymd_and_hms(2024, 3, 15, 12, 0, 0).unwrap();
+        let dates = [
+            Local.with_ymd_and_hms(2024, 3, 15, 1, 0, 0).unwrap(),
+            Local.with_ymd_and_hms(2024, 3, 14, 23, 0, 0).unwrap(),
+            Local.with_ymd_and_hms(2024, 3, 14, 9, 0, 0).unwrap(),
+            Local.with_ymd_and_hms(2024, 3, 8, 9, 0, 0).unwrap(),
+            Local.with_ymd_and_hms(2023, 1, 1, 9, 0, 0).unwrap(),
+        ];
+
+        let by_day = get_deletion_histogram(&dates, now, false, 3);
+        assert_eq!(
+            by_day,
+            vec![
+                ("03-15".to_string(), 1),
+                ("03-14".to_string(), 2),
+                ("03-13".to_string(), 0)
+            ]
+        );
+
+        let by_week = get_deletion_histogram(&dates, now, true, 2);
+        assert_eq!(
+            by_week,
+            vec![("03-09".to_string(), 3), ("03-02".to_string(), 1)]
+        );
+    }
+
+    #[test]
+    fn test_get_purge_projection() {
+        let now = Local.with_ymd_and_hms(2024, 3, 15, 12, 0, 0).unwrap();
+        let stats = TrashStats {
+            entries: vec![
+                (
+                    Local.with_ymd_and_hms(2024, 3, 1, 9, 0, 0).unwrap(),
+                    Some(10),
+                ),
+                (
+                    Local.with_ymd_and_hms(2024, 2, 1, 9, 0, 0).unwrap(),
+                    Some(5),
+                ),
+                (Local.with_ymd_and_hms(2024, 3, 14, 9, 0, 0).unwrap(), None),
+            ],
+            calculating: 1,
+            files: 3,
+            dirs: 0,
+            links: 0,
+            largest: vec![],
+            oldest: vec![],
+        };
+
+        // the entry being calculated is only in the shortest purge
+        assert_eq!(stats.get_purge_projection(0, now), (3, None));
+        assert_eq!(stats.get_purge_projection(7, now), (2, Some(15)));
+        assert_eq!(stats.get_purge_projection(30, now), (1, Some(5)));
+        assert_eq!(stats.get_purge_projection(365, now), (0, Some(0)));
+    }
+}
@@ -0,0 +1,368 @@
+use std::error::Error;
+
+use libtrash::*;
+use ratatui::style::{Color, Modifier, Style};
+use std::env;
+use std::fs;
+use std::io::{self, Read, Write};
+use std::os::fd::AsRawFd;
+use std::str::FromStr;
+use std::time::{Duration, Instant};
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum ThemeColor {
+    Highlight,
+    TitleText,
+    Text,
+    BoldText,
+    ErrorText,
+    SelectedFGDir,
+    SelectedFGLink,
+    SelectedFGFile,
+    SelectedBG,
+    UnselectedFGDir,
+    UnselectedFGLink,
+    UnselectedFGFile,
+    DialogBG,
+    DialogText,
+    DialogButtonBG,
+    DialogButtonText,
+    SyntaxKeyword,
+    SyntaxString,
+    SyntaxComment,
+    SyntaxNumber,
+}
+
+// names of the colours in theme files
+const THEME_COLORS: &[(ThemeColor, &str)] = &[
+    (ThemeColor::Highlight, "highlight"),
+    (ThemeColor::TitleText, "title-text"),
+    (ThemeColor::Text, "text"),
+    (ThemeColor::BoldText, "bold-text"),
+    (ThemeColor::ErrorText, "error-text"),
+    (ThemeColor::SelectedFGDir, "selected-fg-dir"),
+    (ThemeColor::SelectedFGLink, "selected-fg-link"),
+    (ThemeColor::SelectedFGFile, "selected-fg-file"),
+    (ThemeColor::SelectedBG, "selected-bg"),
+    (ThemeColor::UnselectedFGDir, "unselected-fg-dir"),
+    (ThemeColor::UnselectedFGLink, "unselected-fg-link"),
+    (ThemeColor::UnselectedFGFile, "unselected-fg-file"),
+    (ThemeColor::DialogBG, "dialog-bg"),
+    (ThemeColor::DialogText, "dialog-text"),
+    (ThemeColor::DialogButtonBG, "dialog-button-bg"),
+    (ThemeColor::DialogButtonText, "dialog-button-text"),
+    (ThemeColor::SyntaxKeyword, "syntax-keyword"),
+    (ThemeColor::SyntaxString, "syntax-string"),
+    (ThemeColor::SyntaxComment, "syntax-comment"),
+    (ThemeColor::SyntaxNumber, "syntax-number"),
+];
+
+pub const DARK_THEME: &[(ThemeColor, Color)] = &[
+    (ThemeColor::Highlight, Color::White),
+    (ThemeColor::TitleText, Color::Black),
+    (ThemeColor::Text, Color::Gray),
+    (ThemeColor::BoldText, Color::White),
+    (ThemeColor::ErrorText, Color::LightRed),
+    (ThemeColor::SelectedFGDir, Color::Blue),
+    (ThemeColor::SelectedFGLink, Color::Magenta),
+    (ThemeColor::SelectedFGFile, Color::White),
+    (ThemeColor::SelectedBG, Color::DarkGray),
+    (ThemeColor::UnselectedFGDir, Color::Blue),
+    (ThemeColor::UnselectedFGLink, Color::Magenta),
+    (ThemeColor::UnselectedFGFile, Color::White),
+    (ThemeColor::DialogBG, Color::Gray),
+    (ThemeColor::DialogText, Color::Black),
+    (ThemeColor::DialogButtonBG, Color::Black),
+    (ThemeColor::DialogButtonText, Color::White),
+    (ThemeColor::SyntaxKeyword, Color::LightBlue),
+    (ThemeColor::SyntaxString, Color::LightGreen),
+    (ThemeColor::SyntaxComment, Color::DarkGray),
+    (ThemeColor::SyntaxNumber, Color::LightYellow),
+];
+
+pub const LIGHT_THEME: &[(ThemeColor, Color)] = &[
+    (ThemeColor::Highlight, Color::DarkGray),
+    (ThemeColor::TitleText, Color::White),
+    (ThemeColor::Text, Color::DarkGray),
+    (ThemeColor::BoldText, Color::Black),
+    (ThemeColor::ErrorText, Color::LightRed),
+    (ThemeColor::SelectedFGDir, Color::LightBlue),
+    (ThemeColor::SelectedFGLink, Color::LightMagenta),
+    (ThemeColor::SelectedFGFile, Color::Black),
+    (ThemeColor::SelectedBG, Color::Gray),
+    (ThemeColor::UnselectedFGDir, Color::Blue),
+    (ThemeColor::UnselectedFGLink, Color::Magenta),
+    (ThemeColor::UnselectedFGFile, Color::Black),
+    (ThemeColor::DialogBG, Color::DarkGray),
+    (ThemeColor::DialogText, Color::White),
+    (ThemeColor::DialogButtonBG, Color::White),
+    (ThemeColor::DialogButtonText, Color::Black),
+    (ThemeColor::SyntaxKeyword, Color::Blue),
+    (ThemeColor::SyntaxString, Color::Green),
+    (ThemeColor::SyntaxComment, Color::Gray),
+    (ThemeColor::SyntaxNumber, Color::Red),
+];
+
+// the colours of the TUI, a built in theme or a theme file. without colours,
+// NO_COLOR, highlights are shown in reverse instead
+#[derive(Debug)]
+pub struct Theme {
+    colors: Vec<(ThemeColor, Color)>,
+    no_color: bool,
+}
+
+impl Theme {
+    pub fn built_in(colors: &[(ThemeColor, Color)]) -> Self {
+        Theme {
+            colors: colors.to_vec(),
+            no_color: false,
+        }
+    }
+
+    pub fn no_color() -> Self {
+        Theme {
+            colors: vec![],
+            no_color: true,
+        }
+    }
+
+    // dark or light, or $XDG_CONFIG_HOME/trash-rs/themes/NAME.conf. errors in
+    // the lines of the file are returned with it
+    pub fn load(name: &str) -> Result<(Self, Vec<String>), Box<dyn Error>> {
+        match name.to_lowercase().as_str() {
+            "dark" => return Ok((Theme::built_in(DARK_THEME), vec![])),
+            "light" => return Ok((Theme::built_in(LIGHT_THEME), vec![])),
+            _ => {}
+        }
+
+        let binary_name = env!("CARGO_PKG_NAME");
+        let path = get_xdg_config_home()?
+            .join(binary_name)
+            .join("themes")
+            .join(format!("{name}.conf"));
+        match fs::read_to_string(&path) {
+            Ok(v) => Ok(Theme::parse(&v, name)),
+            Err(e) if e.kind() == io::ErrorKind::NotFound => Err(Box::<dyn Error>::from(format!(
+                "no built in theme or {} by that name",
+                path.display()
+            ))),
+            Err(e) => Err(Box::<dyn Error>::from(format!(
+                "cannot read {}: {e}",
+                path.display()
+            ))),
+        }
+    }
+
+    // lines of "name = colour", applied on top of the dark theme, or the one
+    // set as the base. lines with errors are skipped, and returned as
+    // messages
+    fn parse(contents: &str, name: &str) -> (Self, Vec<String>) {
+        let mut errors = vec![];
+        let lines: Vec<(usize, &str, &str)> = contents
+            .lines()
+            .enumerate()
+            .filter(|(_, line)| !line.trim().is_empty() && !line.trim().starts_with('#'))
+            .filter_map(|(i, line)| match line.split_once('=') {
+                Some((slot, value)) => Some((i + 1, slot.trim(), value.trim())),
+                None => {
+                    errors.push(format!("{name}.conf:{}: expected 'name = colour'", i + 1));
+                    None
+                }
+            })
+            .collect();
+
+        // the base goes first, wherever it is
+        let mut theme = Theme::built_in(DARK_THEME);
+        for (line_number, slot, value) in &lines {
+            if *slot == "base" {
+                theme = match value.to_lowercase().as_str() {
+                    "dark" => Theme::built_in(DARK_THEME),
+                    "light" => Theme::built_in(LIGHT_THEME),
+                    _ => {
+                        errors.push(format!(
+                            "{name}.conf:{line_number}: unknown base '{value}', expected dark or light"
+                        ));
+                        continue;
+                    }
+                };
+            }
+        }
+
+        for (line_number, slot, value) in lines {
+            if slot == "base" {
+                continue;
+            }
+            let Some((theme_color, _)) = THEME_COLORS.iter().find(|(_, v)| *v == slot) else {
+                errors.push(format!(
+                    "{name}.conf:{line_number}: unknown colour '{slot}'"
+                ));
+                continue;
+            };
+            // a name, a 256 colour index, or #rrggbb
+            let Ok(color) = Color::from_str(value) else {
+                errors.push(format!(
+                    "{name}.conf:{line_number}: invalid colour value '{value}'"
+                ));
+                continue;
+            };
+            for (v, c) in theme.colors.iter_mut() {
+                if v == theme_color {
+                    *c = color;
+                }
+            }
+        }
+
+        (theme, errors)
+    }
+
+    // highlights are only told apart by their colours, so without colours
+    // they are reversed instead
+    pub fn get_highlight_modifier(&self) -> Modifier {
+        if self.no_color {
+            Modifier::REVERSED
+        } else {
+            Modifier::empty()
+        }
+    }
+
+    pub fn get_title_style(&self) -> Style {
+        Style::default()
+            .add_modifier(Modifier::BOLD | self.get_highlight_modifier())
+            .bg(self.get(ThemeColor::Highlight))
+            .fg(self.get(ThemeColor::TitleText))
+    }
+
+    pub fn get_dialog_style(&self) -> Style {
+        Style::default()
+            .bg(self.get(ThemeColor::DialogBG))
+            .fg(self.get(ThemeColor::DialogText))
+    }
+
+    pub fn get_dialog_text_style(&self) -> Style {
+        Style::default().fg(self.get(ThemeColor::DialogText))
+    }
+
+    // the selected button, choice, or row of a dialog
+    pub fn get_button_style(&self) -> Style {
+        Style::default()
+            .add_modifier(Modifier::BOLD | self.get_highlight_modifier())
+            .bg(self.get(ThemeColor::DialogButtonBG))
+            .fg(self.get(ThemeColor::DialogButtonText))
+    }
+
+    pub fn get(&self, color: ThemeColor) -> Color {
+        self.colors
+            .iter()
+            .find(|(v, _)| *v == color)
+            .map(|(_, c)| *c)
+            .unwrap_or(Color::Reset)
+    }
+}
+
+// ask the terminal for its background colour with OSC 11. every terminal
+// answers the device attributes query sent after it, so there's no waiting
+// for the ones that don't know OSC 11
+pub fn query_background_is_light() -> Option<bool> {
+    let mut tty = fs::OpenOptions::new()
+        .read(true)
+        .write(true)
+        .open("/dev/tty")
+        .ok()?;
+    tty.write_all(b"\x1b]11;?\x1b\\\x1b[c").ok()?;
+    tty.flush().ok()?;
+
+    let deadline = Instant::now() + Duration::from_millis(200);
+    let mut response = vec![];
+    loop {
+        let timeout = deadline.saturating_duration_since(Instant::now());
+        let mut pollfd = libc::pollfd {
+            fd: tty.as_raw_fd(),
+            events: libc::POLLIN,
+            revents: 0,
+        };
+        let ready = unsafe { libc::poll(&mut pollfd, 1, timeout.as_millis() as i32) };
+        if ready <= 0 {
+            break;
+        }
+        let mut buff = [0; 256];
+        match tty.read(&mut buff) {
+            Ok(0) | Err(_) => break,
+            Ok(n) => response.extend_from_slice(&buff[..n]),
+        }
+
+        // the device attributes answer, ESC [ ? ... c, comes last
+        if let Some(i) = response.windows(3).position(|v| v == b"\x1b[?") {
+            if response[i..].contains(&b'c') {
+                break;
+            }
+        }
+    }
+
+    parse_background_response(&response)
+}
+
+// ESC ] 11 ; rgb:RRRR/GGGG/BBBB, with 1 to 4 hex digits a component. light
+// if the luminance is over half
+fn parse_background_response(response: &[u8]) -> Option<bool> {
+    let response = String::from_utf8_lossy(response);
+    let start = response.find("]11;rgb:")? + "]11;rgb:".len();
+    let mut components = response[start..].splitn(3, '/').map(|v| {
+        let digits: String = v.chars().take_while(|c| c.is_ascii_hexdigit()).collect();
+        let max = 16_f32.powi(digits.len() as i32) - 1.0;
+        u32::from_str_radix(&digits, 16)
+            .ok()
+            .filter(|_| digits.len() <= 4)
+            .map(|v| v as f32 / max)
+    });
+    let (r, g, b) = (
+        components.next()??,
+        components.next()??,
+        components.next()??,
+    );
+    Some(0.2126 * r + 0.7152 * g + 0.0722 * b > 0.5)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_theme() {
+        // every colour is named, and set by the built in themes
+        for theme in [DARK_THEME, LIGHT_THEME] {
+            for (color, _) in THEME_COLORS {
+                assert!(theme.iter().any(|(v, _)| v == color));
+            }
+        }
+
+        let (theme, errors) = Theme::parse(
+            "# comment\ntext = #102030\nhighlight = 208\nbold = red\nbase = light\nerror-text = reddish\n",
+            "mine",
+        );
+        assert_eq!(
+            errors,
+            vec![
+                "mine.conf:4: unknown colour 'bold'".to_string(),
+                "mine.conf:6: invalid colour value 'reddish'".to_string(),
+            ]
+        );
+        assert_eq!(theme.get(ThemeColor::Text), Color::Rgb(0x10, 0x20, 0x30));
+        assert_eq!(theme.get(ThemeColor::Highlight), Color::Indexed(208));
+        // the rest from the base, wherever it is
+        assert_eq!(theme.get(ThemeColor::BoldText), Color::Black);
+    }
+
+    #[test]
+    fn test_parse_background_response() {
+        assert_eq!(
+            parse_background_response(b"\x1b]11;rgb:ffff/ffff/dddd\x1b\\\x1b[?62;22c"),
+            Some(true)
+        );
+        assert_eq!(
+            parse_background_response(b"\x1b]11;rgb:1c/1c/1c\x07\x1b[?1;2c"),
+            Some(false)
+        );
+        // no OSC 11, only the device attributes
+        assert_eq!(parse_background_response(b"\x1b[?1;2c"), None);
+    }
+}
@@ -0,0 +1,647 @@
+use crate::dialogs::{
+    render_confirmation, render_error_log, render_filter, render_help, render_report,
+    render_restore_to, render_sort, render_stats, ConfirmationView, ErrorLogView, FilterView,
+    HelpView, ReportView, RestoreToView, SortView, StatsView,
+};
+use crate::sizes::format_size;
+use crate::theme::{Theme, ThemeColor};
+use crate::BINARY_NAME;
+use ratatui::layout::{Constraint, Flex, Layout, Rect};
+use ratatui::style::{Modifier, Style};
+use ratatui::text::{Line, Span, Text};
+use ratatui::widgets::{
+    Block, Borders, Clear, List, ListItem, Padding, Paragraph, Scrollbar, ScrollbarOrientation,
+    ScrollbarState, Wrap,
+};
+use ratatui::Frame;
+use std::path::Path;
+
+// layout values
+const LAYOUT_FILE_LIST_WIDTH_PERCENTAGE: u16 = 70;
+
+const LAYOUT_PREVIEW_HEIGHT_PERCENTAGE: u16 = 70;
+
+const LAYOUT_TITLE_HEIGHT: u16 = 3;
+
+const LAYOUT_FOOTER_HEIGHT: u16 = 3;
+
+const LAYOUT_ROOTS_WIDTH: u16 = 34;
+
+pub const LAYOUT_ROOT_HEIGHT: u16 = 3; // lines of a root in the sidebar
+
+pub const LAYOUT_SUBTITLE_WIDTH: usize = 16; // of the selected file in the list
+
+// what a trashed file or an entry of a trashed directory is, for its icon and
+// colour in the lists
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum EntryKind {
+    File,
+    Dir,
+    Link,
+}
+
+impl EntryKind {
+    pub fn for_path(path: &Path) -> Self {
+        if path.is_symlink() {
+            EntryKind::Link
+        } else if path.is_dir() {
+            EntryKind::Dir
+        } else {
+            EntryKind::File
+        }
+    }
+
+    fn get_icon(&self) -> &'static str {
+        match self {
+            EntryKind::File => "📄",
+            EntryKind::Dir => "📁",
+            EntryKind::Link => "🔗",
+        }
+    }
+
+    fn get_color(&self, is_selected: bool) -> ThemeColor {
+        match (self, is_selected) {
+            (EntryKind::File, true) => ThemeColor::SelectedFGFile,
+            (EntryKind::File, false) => ThemeColor::UnselectedFGFile,
+            (EntryKind::Dir, true) => ThemeColor::SelectedFGDir,
+            (EntryKind::Dir, false) => ThemeColor::UnselectedFGDir,
+            (EntryKind::Link, true) => ThemeColor::SelectedFGLink,
+            (EntryKind::Link, false) => ThemeColor::UnselectedFGLink,
+        }
+    }
+}
+
+// a row of a file list
+pub struct ListRow {
+    pub name: String,
+    pub kind: EntryKind,
+    pub is_external: bool, // trashed on another mount
+    pub is_marked: bool,
+    pub matches: Vec<usize>, // chars of the name matching the search
+}
+
+// the description and the preview of the selected file
+pub struct DetailsView {
+    pub description: Vec<(&'static str, String)>, // label, value, empty for a gap
+    pub path_matches: Vec<usize>,                 // chars of the original path matching the search
+    pub preview: Text<'static>,
+    pub preview_position: Option<String>,
+    pub preview_focused: bool,
+}
+
+// what the main screen shows, worked out from the trashed files beforehand so
+// that drawing it doesn't touch the filesystem
+pub struct MainView {
+    pub title: String,
+    pub sort_label: &'static str,
+    pub empty_note: Option<String>, // instead of the rows, if there are none
+    pub rows: Vec<ListRow>,         // the ones scrolled into view
+    pub selected: usize,            // of the rows
+    pub subtitle: Option<String>,   // of the selected row, by the sort
+    pub count: usize,
+    pub position: usize,
+    pub is_scrollable: bool,
+    pub details: DetailsView,
+}
+
+// what the browse screen shows, the same way
+pub struct BrowseView {
+    pub title: String,
+    pub rows: Vec<ListRow>,
+    pub selected: usize,
+    pub details: DetailsView,
+}
+
+// a trash root in the sidebar, or all of them
+pub struct RootRow {
+    pub name: String,
+    pub location: String,
+    pub count: usize,
+    pub size: Option<u64>, // None while calculating
+    pub is_scoped: bool,   // the files shown are of this root
+}
+
+// what the roots sidebar shows
+pub struct RootsView {
+    pub rows: Vec<RootRow>, // the ones scrolled into view
+    pub selected: usize,    // of the rows
+    pub focused: bool,
+}
+
+// where the sidebar, the list, and the details go in the middle of the screen
+pub struct ScreenAreas {
+    pub roots: Rect, // empty without the sidebar
+    pub list: Rect,
+    pub details: [Rect; 2],
+}
+
+// the middle of the screen, or the dialog over it
+pub enum Screen {
+    Main(ScreenAreas, Option<RootsView>, MainView),
+    Browse(ScreenAreas, BrowseView),
+    Confirmation(ConfirmationView),
+    RestoreTo(RestoreToView),
+    Sort(SortView),
+    Filter(FilterView),
+    Report(ReportView),
+    Stats(StatsView, Option<ConfirmationView>), // and the purge being confirmed
+    ErrorLog(ErrorLogView),
+    Help(HelpView),
+    Blank,
+}
+
+// everything the screen shows, built before drawing it
+pub struct View {
+    pub filters: String, // the active ones, on the right of the title
+    pub screen: Screen,
+    pub search_bar: Option<String>, // while typing a search
+    pub directions: Vec<(String, &'static str)>,
+    pub notification: Option<(String, bool)>, // message, is an error
+}
+
+// the title, the middle, and the footer of the screen
+pub fn get_frame_areas(area: Rect) -> [Rect; 3] {
+    Layout::vertical([
+        Constraint::Length(LAYOUT_TITLE_HEIGHT),
+        Constraint::Min(3),
+        Constraint::Length(LAYOUT_FOOTER_HEIGHT),
+    ])
+    .areas(area)
+}
+
+// the sidebar takes its width from the list
+pub fn get_screen_areas(area: Rect, roots_shown: bool) -> ScreenAreas {
+    let (roots, area) = if roots_shown {
+        let [roots, rest] =
+            Layout::horizontal([Constraint::Length(LAYOUT_ROOTS_WIDTH), Constraint::Min(0)])
+                .areas(area);
+        (roots, rest)
+    } else {
+        (Rect::default(), area)
+    };
+    let [list, right_column] = Layout::horizontal([
+        Constraint::Percentage(LAYOUT_FILE_LIST_WIDTH_PERCENTAGE),
+        Constraint::Percentage(100 - LAYOUT_FILE_LIST_WIDTH_PERCENTAGE),
+    ])
+    .areas(area);
+    let details = Layout::vertical([
+        Constraint::Percentage(100 - LAYOUT_PREVIEW_HEIGHT_PERCENTAGE),
+        Constraint::Percentage(LAYOUT_PREVIEW_HEIGHT_PERCENTAGE),
+    ])
+    .areas(right_column);
+    ScreenAreas {
+        roots,
+        list,
+        details,
+    }
+}
+
+// draw a view, without looking at anything else
+pub fn render(f: &mut Frame, view: &View, theme: &Theme) {
+    let title_style = theme.get_title_style();
+    let frame_area = f.area();
+    let [title_area, _, footer_area] = get_frame_areas(frame_area);
+
+    // ============================== title
+    let title_block = Block::default().borders(Borders::ALL);
+    let title = format!(" {BINARY_NAME}");
+    // active filters on the right
+    let title_width = (frame_area.width as usize).saturating_sub(2 + view.filters.chars().count()); // -2 for the border
+    let padded_title = format!(
+        "{:<width$}",
+        format!("{title:<title_width$}{}", view.filters),
+        width = frame_area.width as usize
+    );
+    let title = Paragraph::new(Text::styled(padded_title, title_style)).block(title_block);
+    f.render_widget(title, title_area);
+
+    // ================== mid section
+    match &view.screen {
+        Screen::Main(areas, roots, main_view) => {
+            if let Some(roots) = roots {
+                render_roots(f, roots, areas.roots, theme);
+            }
+            render_main_view(f, main_view, areas.list, areas.details, theme);
+        }
+        Screen::Browse(areas, browse_view) => {
+            render_browse_view(f, browse_view, areas.list, areas.details, theme);
+        }
+        Screen::Confirmation(v) => render_confirmation(f, v, theme),
+        Screen::RestoreTo(v) => render_restore_to(f, v, theme),
+        Screen::Sort(v) => render_sort(f, v, theme),
+        Screen::Filter(v) => render_filter(f, v, theme),
+        Screen::Report(v) => render_report(f, v, theme),
+        Screen::Stats(v, confirmation) => {
+            render_stats(f, v, theme);
+            if let Some(confirmation) = confirmation {
+                render_confirmation(f, confirmation, theme);
+            }
+        }
+        Screen::ErrorLog(v) => render_error_log(f, v, theme),
+        Screen::Help(v) => render_help(f, v, theme),
+        Screen::Blank => {}
+    }
+
+    // ================== footer
+    let footer_block = Block::default().borders(Borders::ALL);
+    let mut directions_line_contents = vec![];
+    // the search bar takes the start of the footer while typing
+    if let Some(search_bar) = &view.search_bar {
+        f.set_cursor_position((
+            footer_area.x + 1 + search_bar.chars().count() as u16,
+            footer_area.y + 1,
+        ));
+        directions_line_contents.push(Span::styled(
+            search_bar.clone(),
+            Style::default().add_modifier(Modifier::BOLD),
+        ));
+        directions_line_contents.push(Span::from("   "));
+    }
+    let dash = Span::from(" - ");
+    let desc_style = Style::default().add_modifier(Modifier::ITALIC);
+    for (shortcut, desc) in view.directions.iter().filter(|(v, _)| !v.is_empty()) {
+        directions_line_contents.push(Span::styled(shortcut.clone(), title_style));
+        directions_line_contents.push(dash.clone());
+        directions_line_contents.push(Span::styled(format!(" {desc} "), desc_style));
+    }
+
+    let directions_line = Line::from(directions_line_contents);
+    let directions_block = Paragraph::new(directions_line).block(footer_block);
+    f.render_widget(directions_block, footer_area);
+
+    // ================== notification, over the bottom right corner
+    if let Some((message, is_error)) = &view.notification {
+        let (title, border_color) = if *is_error {
+            (" Error, L for the log ", theme.get(ThemeColor::ErrorText))
+        } else {
+            (" Done ", theme.get(ThemeColor::DialogText))
+        };
+        let block = Block::bordered()
+            .title(Span::styled(
+                title,
+                theme.get_dialog_text_style().add_modifier(Modifier::BOLD),
+            ))
+            .border_style(Style::default().fg(border_color))
+            .padding(Padding::horizontal(1))
+            .style(theme.get_dialog_style());
+
+        // wide enough for the message if there's space, -4 for the
+        // border and the padding
+        let message_width = message.chars().count() + 4;
+        let max_width = (frame_area.width as usize).saturating_sub(2).max(1);
+        let width = message_width.max(title.len() + 2).min(max_width);
+        let height = message_width.div_ceil(width) + 2;
+        let area = Rect {
+            x: frame_area.width.saturating_sub(width as u16 + 1),
+            y: frame_area
+                .height
+                .saturating_sub(LAYOUT_FOOTER_HEIGHT + height as u16),
+            width: width as u16,
+            height: (height as u16).min(frame_area.height),
+        };
+        let toast = Paragraph::new(message.clone())
+            .wrap(Wrap { trim: false })
+            .block(block);
+        f.render_widget(Clear, area);
+        f.render_widget(toast, area);
+    }
+}
+
+// the roots sidebar, the scoped root marked
+fn render_roots(f: &mut Frame, view: &RootsView, area: Rect, theme: &Theme) {
+    let inner_width = area.width.saturating_sub(2) as usize;
+    let items: Vec<ListItem> = view
+        .rows
+        .iter()
+        .enumerate()
+        .map(|(i, row)| {
+            let mark = if row.is_scoped { "● " } else { "  " };
+            let style = if i == view.selected && view.focused {
+                Style::default()
+                    .bg(theme.get(ThemeColor::SelectedBG))
+                    .fg(theme.get(ThemeColor::SelectedFGFile))
+                    .add_modifier(theme.get_highlight_modifier())
+            } else if i == view.selected {
+                Style::default().add_modifier(Modifier::BOLD)
+            } else {
+                Style::default()
+            };
+            let files = if row.count == 1 { "file" } else { "files" };
+            ListItem::new(vec![
+                Line::from(vec![
+                    Span::styled(mark, Style::default().add_modifier(Modifier::BOLD)),
+                    Span::styled(
+                        format!(
+                            "{:<width$}",
+                            row.name,
+                            width = inner_width.saturating_sub(2)
+                        ),
+                        Style::default().add_modifier(Modifier::BOLD),
+                    ),
+                ]),
+                Line::from(format!(
+                    "  {:<width$}",
+                    row.location,
+                    width = inner_width.saturating_sub(2)
+                )),
+                Line::from(format!(
+                    "  {:<width$}",
+                    format!(
+                        "{} {files} · {}",
+                        row.count,
+                        row.size
+                            .map(format_size)
+                            .unwrap_or_else(|| "calculating…".to_string())
+                    ),
+                    width = inner_width.saturating_sub(2)
+                )),
+            ])
+            .style(style)
+        })
+        .collect();
+
+    let title_style = Style::default().add_modifier(Modifier::BOLD);
+    let block = Block::default()
+        .title(Span::styled(" Trash Roots ", title_style))
+        .borders(Borders::ALL)
+        .border_style(if view.focused {
+            Style::default().fg(theme.get(ThemeColor::BoldText))
+        } else {
+            Style::default()
+        });
+    f.render_widget(List::new(items).block(block), area);
+}
+
+// the file list of the main screen, with the details of the selected file
+fn render_main_view(
+    f: &mut Frame,
+    view: &MainView,
+    list_area: Rect,
+    details_areas: [Rect; 2],
+    theme: &Theme,
+) {
+    let title_style = theme.get_title_style();
+    let list_block = Block::default()
+        .borders(Borders::ALL)
+        .title(Span::styled(view.title.clone(), title_style));
+    if let Some(note) = &view.empty_note {
+        f.render_widget(Paragraph::new(note.clone()).block(list_block), list_area);
+    } else {
+        let file_list_width = list_area.width as usize;
+        let mark_style = Style::default()
+            .fg(theme.get(ThemeColor::BoldText))
+            .add_modifier(Modifier::BOLD);
+        let list_items: Vec<ListItem> = view
+            .rows
+            .iter()
+            .enumerate()
+            .map(|(i, row)| {
+                let is_selected = i == view.selected;
+                let entry_mark = if row.is_marked {
+                    Span::styled("● ", mark_style)
+                } else {
+                    Span::from("  ")
+                };
+                let entry_symbol = if row.is_external {
+                    Span::from("🢅 ")
+                } else {
+                    Span::from("  ")
+                };
+                let mut spans = vec![entry_mark, entry_symbol, Span::from(row.kind.get_icon())];
+                let fg_color = theme.get(row.kind.get_color(is_selected));
+
+                if !is_selected {
+                    let max_filename_length = file_list_width.saturating_sub(2 + 6); // border - mark and icon columns
+                    let name_display = if row.name.len() >= max_filename_length {
+                        format!("{}..", &row.name[..max_filename_length.saturating_sub(2)])
+                    } else {
+                        format!("{:<max_filename_length$}", row.name)
+                    };
+                    spans.extend(highlight_matches(
+                        name_display,
+                        &row.matches,
+                        Style::default().fg(fg_color),
+                    ));
+                    return ListItem::new(Line::from(spans));
+                }
+
+                // icons + filename                     + subtitle
+                // 1+1+1   width - (icons + substitle)     2+18
+                // without a subtitle
+                //  icons + width - icons
+                let max_filename_length = match view.subtitle {
+                    Some(_) => file_list_width.saturating_sub(2 + 6 + LAYOUT_SUBTITLE_WIDTH), // border - mark and icon columns - spacer between subtitle
+                    None => file_list_width.saturating_sub(2 + 6), // border - mark and icon columns (unicode is two columns)
+                };
+                let name_display = if row.name.len() >= max_filename_length {
+                    format!("{}..", &row.name[..max_filename_length.saturating_sub(2)])
+                } else {
+                    format!(
+                        "{:<width$}",
+                        row.name,
+                        width = max_filename_length.saturating_sub(1)
+                    )
+                };
+                spans.extend(highlight_matches(
+                    name_display,
+                    &row.matches,
+                    Style::default()
+                        .bg(theme.get(ThemeColor::SelectedBG))
+                        .fg(fg_color)
+                        .add_modifier(Modifier::BOLD),
+                ));
+                if let Some(subtitle) = &view.subtitle {
+                    spans.push(Span::styled(
+                        format!("{subtitle:>width$} ", width = LAYOUT_SUBTITLE_WIDTH - 1),
+                        Style::default()
+                            .fg(theme.get(ThemeColor::SelectedFGFile))
+                            .add_modifier(Modifier::ITALIC),
+                    ));
+                }
+                ListItem::new(
+                    Line::from(spans).style(
+                        Style::default()
+                            .bg(theme.get(ThemeColor::SelectedBG))
+                            .add_modifier(theme.get_highlight_modifier()),
+                    ),
+                )
+            })
+            .collect();
+
+        let list = List::new(list_items).block(
+            list_block.title_top(
+                Line::from(vec![
+                    Span::styled(" Sorted By ", title_style.remove_modifier(Modifier::BOLD)),
+                    Span::styled(
+                        format!("{} ", view.sort_label),
+                        title_style.add_modifier(Modifier::ITALIC),
+                    ),
+                ])
+                .right_aligned(),
+            ),
+        );
+        f.render_widget(list, list_area);
+    }
+
+    render_details(f, &view.details, details_areas, theme);
+
+    // scroll bar for the list
+    let thumb_symbol = if view.is_scrollable { "█" } else { "░" };
+    let scrollbar = Scrollbar::new(ScrollbarOrientation::VerticalRight)
+        .thumb_symbol(thumb_symbol)
+        .track_symbol(Some("░"));
+    let mut scrollbar_state = ScrollbarState::new(view.count).position(view.position);
+    f.render_stateful_widget(scrollbar, list_area, &mut scrollbar_state);
+}
+
+// the entries of a trashed directory, with the details of the selected one
+fn render_browse_view(
+    f: &mut Frame,
+    view: &BrowseView,
+    list_area: Rect,
+    details_areas: [Rect; 2],
+    theme: &Theme,
+) {
+    let title_style = theme.get_title_style();
+    let file_list_width = list_area.width.saturating_sub(2) as usize;
+    let list_items: Vec<ListItem> = view
+        .rows
+        .iter()
+        .enumerate()
+        .map(|(i, row)| {
+            let is_selected = i == view.selected;
+            let line = Line::from(vec![
+                Span::from(format!("  {}", row.kind.get_icon())),
+                Span::styled(
+                    format!(
+                        "{:<width$}",
+                        row.name,
+                        width = file_list_width.saturating_sub(4)
+                    ),
+                    Style::default().fg(theme.get(row.kind.get_color(is_selected))),
+                ),
+            ]);
+            if is_selected {
+                ListItem::new(
+                    line.style(
+                        Style::default()
+                            .bg(theme.get(ThemeColor::SelectedBG))
+                            .add_modifier(Modifier::BOLD | theme.get_highlight_modifier()),
+                    ),
+                )
+            } else {
+                ListItem::new(line)
+            }
+        })
+        .collect();
+
+    let list = List::new(list_items).block(
+        Block::default()
+            .borders(Borders::ALL)
+            .title(Span::styled(view.title.clone(), title_style))
+            .title_top(
+                Line::from(Span::styled(
+                    " Browsing a trashed directory ",
+                    title_style
+                        .remove_modifier(Modifier::BOLD)
+                        .add_modifier(Modifier::ITALIC),
+                ))
+                .right_aligned(),
+            ),
+    );
+    f.render_widget(list, list_area);
+
+    render_details(f, &view.details, details_areas, theme);
+}
+
+// the description of the selected file over its preview, highlighted when
+// focused, with the lines shown of a text preview
+fn render_details(f: &mut Frame, details: &DetailsView, areas: [Rect; 2], theme: &Theme) {
+    let title_style = theme.get_title_style();
+    let label_style = Style::default()
+        .fg(theme.get(ThemeColor::BoldText))
+        .add_modifier(Modifier::BOLD);
+    let value_style = Style::default().fg(theme.get(ThemeColor::Text));
+
+    let description: Vec<Line> = details
+        .description
+        .iter()
+        .map(|(label, value)| {
+            if label.is_empty() {
+                return Line::default();
+            }
+
+            let mut spans = vec![Span::styled(format!("{label}: "), label_style)];
+            if *label == "Original path" {
+                spans.extend(highlight_matches(
+                    value.clone(),
+                    &details.path_matches,
+                    value_style,
+                ));
+            } else {
+                spans.push(Span::styled(value.clone(), value_style));
+            }
+            Line::from(spans)
+        })
+        .collect();
+    let desc_block = Block::default()
+        .title(Span::styled(" Description ", title_style))
+        .borders(Borders::ALL)
+        .padding(Padding::new(1, 1, 1, 1));
+    let desc_text = Paragraph::new(description)
+        .wrap(Wrap { trim: false })
+        .block(desc_block);
+    f.render_widget(desc_text, areas[0]);
+
+    let title = match &details.preview_position {
+        Some(v) => format!(" Preview [{v}] "),
+        None => " Preview ".to_string(),
+    };
+    let mut preview_block = Block::default()
+        .title(Span::styled(title, title_style))
+        .borders(Borders::ALL)
+        .padding(Padding::new(1, 1, 1, 1));
+    if details.preview_focused {
+        preview_block = preview_block.border_style(label_style);
+    }
+    f.render_widget(
+        Paragraph::new(details.preview.clone()).block(preview_block),
+        areas[1],
+    );
+}
+
+// split the text into spans, with the chars at the matched positions
+// underlined
+fn highlight_matches(text: String, matches: &[usize], style: Style) -> Vec<Span<'static>> {
+    if matches.is_empty() {
+        return vec![Span::styled(text, style)];
+    }
+
+    let match_style = style.add_modifier(Modifier::UNDERLINED);
+    let mut spans: Vec<Span> = vec![];
+    let mut run = String::new();
+    let mut run_matched = false;
+    for (i, c) in text.chars().enumerate() {
+        let matched = matches.contains(&i);
+        if matched != run_matched && !run.is_empty() {
+            let run_style = if run_matched { match_style } else { style };
+            spans.push(Span::styled(std::mem::take(&mut run), run_style));
+        }
+        run_matched = matched;
+        run.push(c);
+    }
+    let run_style = if run_matched { match_style } else { style };
+    spans.push(Span::styled(run, run_style));
+
+    spans
+}
+
+/// helper function to create a centered rect using up certain percentage of the available rect `r`
+/// copied from ratatui docs
+pub fn popup_area(area: Rect, percent_x: u16, percent_y: u16) -> Rect {
+    let vertical = Layout::vertical([Constraint::Percentage(percent_y)]).flex(Flex::Center);
+    let horizontal = Layout::horizontal([Constraint::Percentage(percent_x)]).flex(Flex::Center);
+    let [area] = vertical.areas(area);
+    let [area] = horizontal.areas(area);
+    area
+}
@@ -0,0 +1,148 @@
+use std::error::Error;
+
+use std::collections::HashMap;
+use std::ffi::{CString, OsStr, OsString};
+use std::io;
+use std::os::fd::{AsRawFd, FromRawFd, OwnedFd};
+use std::os::unix::ffi::OsStrExt;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+// how often changes to the trash are picked up while waiting for input
+pub const WATCH_INTERVAL: Duration = Duration::from_millis(250);
+
+// entries added, removed, or written to in the files and info directories
+const WATCH_MASK: u32 = libc::IN_CREATE
+    | libc::IN_DELETE
+    | libc::IN_MOVED_FROM
+    | libc::IN_MOVED_TO
+    | libc::IN_CLOSE_WRITE;
+
+// inotify watches on the files and info directories of the trash roots, to
+// pick up what other programs do to the trash
+pub struct TrashWatcher {
+    fd: OwnedFd,
+    watches: HashMap<i32, PathBuf>, // watch descriptor, directory
+}
+
+impl TrashWatcher {
+    pub fn new() -> Result<Self, Box<dyn Error>> {
+        let fd = unsafe { libc::inotify_init1(libc::IN_NONBLOCK | libc::IN_CLOEXEC) };
+        if fd < 0 {
+            return Err(Box::new(io::Error::last_os_error()));
+        }
+
+        Ok(TrashWatcher {
+            fd: unsafe { OwnedFd::from_raw_fd(fd) },
+            watches: HashMap::new(),
+        })
+    }
+
+    // watching a directory again keeps the same watch
+    pub fn watch(&mut self, dir: &Path) -> Result<(), Box<dyn Error>> {
+        let path = CString::new(dir.as_os_str().as_bytes())?;
+        let wd = unsafe { libc::inotify_add_watch(self.fd.as_raw_fd(), path.as_ptr(), WATCH_MASK) };
+        if wd < 0 {
+            return Err(Box::new(io::Error::last_os_error()));
+        }
+
+        self.watches.insert(wd, dir.to_path_buf());
+        Ok(())
+    }
+
+    // the paths changed since the last call, without waiting. true if events
+    // were dropped, then anything could have changed
+    pub fn read_changes(&mut self) -> (Vec<PathBuf>, bool) {
+        let mut changes = vec![];
+        let mut overflowed = false;
+        let mut buffer = [0u8; 4096];
+        loop {
+            let len = unsafe {
+                libc::read(
+                    self.fd.as_raw_fd(),
+                    buffer.as_mut_ptr() as *mut libc::c_void,
+                    buffer.len(),
+                )
+            };
+            if len <= 0 {
+                break;
+            }
+
+            for (wd, mask, name) in parse_inotify_events(&buffer[..len as usize]) {
+                if mask & libc::IN_Q_OVERFLOW != 0 {
+                    overflowed = true;
+                } else if mask & libc::IN_IGNORED != 0 {
+                    // the directory is gone, or its mount
+                    self.watches.remove(&wd);
+                } else if let Some(dir) = self.watches.get(&wd) {
+                    changes.push(dir.join(name));
+                }
+            }
+        }
+
+        (changes, overflowed)
+    }
+}
+
+// watch descriptor, mask and name of each inotify_event in a read
+fn parse_inotify_events(buffer: &[u8]) -> Vec<(i32, u32, OsString)> {
+    // wd, mask, cookie and len, then len bytes of the nul padded name
+    const HEADER_LEN: usize = 16;
+    let mut events = vec![];
+    let mut offset = 0;
+    while offset + HEADER_LEN <= buffer.len() {
+        let field = |i: usize| {
+            let start = offset + i * 4;
+            [
+                buffer[start],
+                buffer[start + 1],
+                buffer[start + 2],
+                buffer[start + 3],
+            ]
+        };
+        let wd = i32::from_ne_bytes(field(0));
+        let mask = u32::from_ne_bytes(field(1));
+        let len = u32::from_ne_bytes(field(3)) as usize;
+        let name_end = (offset + HEADER_LEN + len).min(buffer.len());
+        let name = &buffer[offset + HEADER_LEN..name_end];
+        let name = match name.iter().position(|v| *v == 0) {
+            Some(i) => &name[..i],
+            None => name,
+        };
+
+        events.push((wd, mask, OsStr::from_bytes(name).to_os_string()));
+        offset = name_end;
+    }
+
+    events
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_inotify_events() {
+        let mut buffer = vec![];
+        for (wd, mask, name, len) in [
+            (1, libc::IN_CREATE, &b"a.trashinfo"[..], 16),
+            (2, libc::IN_IGNORED, &b""[..], 0),
+        ] {
+            buffer.extend(i32::to_ne_bytes(wd));
+            buffer.extend(u32::to_ne_bytes(mask));
+            buffer.extend(u32::to_ne_bytes(0));
+            buffer.extend(u32::to_ne_bytes(len));
+            let mut name = name.to_vec();
+            name.resize(len as usize, 0);
+            buffer.extend(name);
+        }
+
+        assert_eq!(
+            parse_inotify_events(&buffer),
+            vec![
+                (1, libc::IN_CREATE, OsString::from("a.trashinfo")),
+                (2, libc::IN_IGNORED, OsString::new())
+            ]
+        );
+    }
+}
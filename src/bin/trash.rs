@@ -1,7 +1,15 @@
 use std::env;
 use std::error::Error;
-use std::io::{stdin, stdout, Write};
+use std::ffi::OsStr;
+use std::fs::File;
+use std::io::{stdin, stdout, Read, Write};
+use std::os::unix::ffi::OsStrExt;
+use std::path::PathBuf;
 
+use libtrash::cli::{
+    find_option, format_options_help, generate_completions, write_man_pages, CliOption, CliValue,
+    ManPage, ManSection, Shell, ValueHint, TRASH_RS_MAN_PAGE,
+};
 use libtrash::*;
 
 const BINARY_NAME: &str = "trash";
@@ -13,6 +21,130 @@ const EXITCODE_INVALID_ARGS: i32 = 1;
 const EXITCODE_UNSUPPORTED: i32 = 2;
 const EXITCODE_EXTERNAL: i32 = 255;
 
+const OPTIONS: &[CliOption] = &[
+    CliOption {
+        short: Some('h'),
+        long: "help",
+        value: None,
+        desc: "display this help and exit",
+    },
+    CliOption {
+        short: Some('i'),
+        long: "interactive",
+        value: None,
+        desc: "prompt before every move",
+    },
+    CliOption {
+        short: Some('v'),
+        long: "verbose",
+        value: None,
+        desc: "explain what is being done",
+    },
+    CliOption {
+        short: Some('V'),
+        long: "version",
+        value: None,
+        desc: "output version information and exit",
+    },
+    CliOption {
+        short: None,
+        long: "files0-from",
+        value: Some(CliValue {
+            name: "F",
+            hint: ValueHint::Path,
+            optional: false,
+        }),
+        desc: "read NUL-terminated file names from file F, - for standard input",
+    },
+    CliOption {
+        short: None,
+        long: "files-from",
+        value: Some(CliValue {
+            name: "F",
+            hint: ValueHint::Path,
+            optional: false,
+        }),
+        desc: "read newline-terminated file names from file F, - for standard input",
+    },
+    CliOption {
+        short: None,
+        long: "undo",
+        value: Some(CliValue {
+            name: "ID",
+            hint: ValueHint::Any,
+            optional: true,
+        }),
+        desc: "restore the files trashed by the last invocation, or by operation ID",
+    },
+    CliOption {
+        short: None,
+        long: "dry-run",
+        value: None,
+        desc: "show where the files would be trashed to, without changing anything",
+    },
+    CliOption {
+        short: None,
+        long: "which",
+        value: None,
+        desc: "explain how the trash directory for the files is chosen",
+    },
+    CliOption {
+        short: None,
+        long: "completions",
+        value: Some(CliValue {
+            name: "SHELL",
+            hint: ValueHint::Choices(Shell::NAMES),
+            optional: false,
+        }),
+        desc: "print a completion script for bash, zsh or fish and exit",
+    },
+    CliOption {
+        short: None,
+        long: "generate-man",
+        value: Some(CliValue {
+            name: "DIR",
+            hint: ValueHint::Path,
+            optional: false,
+        }),
+        desc: "write the trash(1) and trash-rs(5) man pages to DIR and exit",
+    },
+];
+
+const USAGE: &str = "[OPTION]... [FILE]...";
+const SUMMARY: &str = "Move the FILE(s) to the trash bin without unlinking";
+
+// shown after the options in --help, and as the man page description
+const DESCRIPTION: &[&str] = &[
+    "Every invocation is journaled under $XDG_STATE_HOME/trash-rs, so that it can be
+undone with --undo as long as the trashed files are still in the trash bin. Use
+-v to see the operation ID of an invocation.",
+    "trash does not traverse symbolic links. It will only move the link to
+trash bin, not the target.",
+    "To trash a file whose name starts with a '-', for example '-foo',
+use one of these commands:",
+    "  trash -- -foo
+
+  trash ./-foo",
+    "To restore a trashed file, any freedesktop.org trash specificaiton compatible
+tool can be used, including File Explorer in desktop environments like GNOME or
+the TUI released with this project, \"Trash Bin\".",
+];
+
+const MAN_PAGE: ManPage = ManPage {
+    name: BINARY_NAME,
+    section: 1,
+    summary: "move files to the trash bin without unlinking",
+    synopsis: Some(USAGE),
+    description: DESCRIPTION,
+    options: OPTIONS,
+    sections: &[ManSection {
+        title: "REPORTING BUGS",
+        paragraphs: &["Report issues at <https://github.com/chamilad/trash-rs>."],
+        entries: &[],
+    }],
+    see_also: &["restore(1)", "trash-rs(5)", "rm(1)"],
+};
+
 // Does NOT support trashing files from external mounts to user's trash dir
 // Does NOT trash a file from external mounts to home if topdirs cannot be used
 fn main() {
@@ -27,6 +159,14 @@ fn main() {
         }
     };
 
+    if let Some(shell) = args_conf.completions {
+        print!(
+            "{}",
+            generate_completions(shell, BINARY_NAME, OPTIONS, Some(ValueHint::Path))
+        );
+        std::process::exit(EXITCODE_OK);
+    }
+
     if args_conf.version {
         let version = env!("CARGO_PKG_VERSION");
         let binary_name = env!("CARGO_PKG_NAME");
@@ -34,43 +174,91 @@ fn main() {
         std::process::exit(EXITCODE_OK);
     }
 
-    if args_conf.help {
-        println!(
-            r#"
-{BINARY_NAME} version {BINARY_VERSION}
-a freedesktop.org trash spec implementation for the CLI
-
-Usage: {BINARY_NAME} [OPTION]... [FILE]...
-Move the FILE(s) to the trash bin without unlinking
+    if let Some(dir) = &args_conf.generate_man {
+        match write_man_pages(dir, &[&MAN_PAGE, &TRASH_RS_MAN_PAGE]) {
+            Ok(v) => {
+                for path in v {
+                    println!("{}", path.display());
+                }
+            }
+            Err(e) => {
+                msg_err(format!("cannot write man pages: {e}"));
+                std::process::exit(EXITCODE_EXTERNAL);
+            }
+        }
+        std::process::exit(EXITCODE_OK);
+    }
 
-    -h, --help          display this help and exit
-    -i, --interactive   prompt before every move
-    -v, --verbose       explain what is being done
-    -V, --version       output version information and exit
+    if args_conf.help {
+        println!();
+        println!("{BINARY_NAME} version {BINARY_VERSION}");
+        println!("a freedesktop.org trash spec implementation for the CLI");
+        println!();
+        println!("Usage: {BINARY_NAME} {USAGE}");
+        println!("{SUMMARY}");
+        println!();
+        print!("{}", format_options_help(OPTIONS));
+        for paragraph in DESCRIPTION {
+            println!();
+            println!("{paragraph}");
+        }
+        println!();
+        println!("{BINARY_NAME} source code, documentation, and issue tracker is in Github:");
+        println!("<https://github.com/chamilad/trash-rs>");
+        std::process::exit(EXITCODE_OK);
+    }
 
-{BINARY_NAME} does not traverse symbolic links. It will only move the link to 
-trash bin, not the target.
+    if args_conf.undo {
+        undo_operation(args_conf.undo_id.as_deref(), args_conf.verbose);
+    }
 
-To trash a file whose name starts with a '-', for example '-foo',
-use one of these commands:
-  {BINARY_NAME} -- -foo
+    if args_conf.which {
+        let mut exit_code = EXITCODE_OK;
+        for file_name in &args_conf.file_names {
+            if let Err(e) = explain_trash_root(file_name) {
+                msg_err(format!(
+                    "cannot explain trash directory for '{file_name}': {e}"
+                ));
+                exit_code = EXITCODE_UNSUPPORTED;
+            }
+        }
 
-  {BINARY_NAME} ./-foo
+        std::process::exit(exit_code);
+    }
 
-To restore a trashed file, any freedesktop.org trash specificaiton compatible
-tool can be used, including File Explorer in desktop environments like GNOME or
-the TUI released with this project, \"Trash Bin\".
+    // journal the trashed files so that the invocation can be undone. a dry
+    // run trashes nothing, so there's nothing to journal
+    let mut operation = if args_conf.dry_run {
+        None
+    } else {
+        match TrashOperation::new() {
+            Ok(v) => Some(v),
+            Err(e) => {
+                msg_err(format!("cannot journal trash operation: {e}"));
+                None
+            }
+        }
+    };
 
-{BINARY_NAME} source code, documentation, and issue tracker is in Github:
-<https://github.com/chamilad/trash-rs>
-"#
-        );
-        std::process::exit(EXITCODE_OK);
+    // names read from a file can be any bytes, not only utf-8
+    let mut file_names: Vec<PathBuf> = args_conf.file_names.iter().map(PathBuf::from).collect();
+    if let Some(files_from) = &args_conf.files_from {
+        match files_from.read_operands() {
+            Ok(mut v) => file_names.append(&mut v),
+            Err(e) => {
+                msg_err(format!(
+                    "cannot read file names from '{}': {e}",
+                    files_from.source
+                ));
+                std::process::exit(EXITCODE_EXTERNAL);
+            }
+        }
     }
 
-    for file_name in args_conf.file_names {
+    for file_path in file_names {
+        let file_name = file_path.display();
         // get absolute path and check file exists
-        let abs_file = match to_abs_path(&file_name) {
+        let abs_file = match to_abs_path(&file_path) {
             Ok(v) => v,
             Err(_) => {
                 msg_err(format!(
@@ -103,7 +291,13 @@ the TUI released with this project, \"Trash Bin\".
             std::process::exit(EXITCODE_UNSUPPORTED);
         }
 
-        let trash_dir = match TrashDirectory::resolve_for_file(&abs_file, args_conf.verbose) {
+        // a dry run must not create the trash directories while resolving
+        let trash_dir = if args_conf.dry_run {
+            TrashDirectory::probe_for_file(&abs_file, args_conf.verbose)
+        } else {
+            TrashDirectory::resolve_for_file(&abs_file, args_conf.verbose)
+        };
+        let trash_dir = match trash_dir {
             Ok(v) => v,
             Err(e) => {
                 msg_err(format!(
@@ -134,6 +328,24 @@ the TUI released with this project, \"Trash Bin\".
             }
         }
 
+        if args_conf.dry_run {
+            msg(format!("would trash '{file_name}'"));
+            println!(
+                "  trash root:  {} ({:?})",
+                trash_dir.home.display(),
+                trash_dir.root_type
+            );
+            println!(
+                "  files entry: {}",
+                trash_file.files_entry.as_ref().unwrap().display()
+            );
+            println!(
+                "  trashinfo:   {}",
+                trash_file.trashinfo.as_ref().unwrap().path.display()
+            );
+            continue;
+        }
+
         if args_conf.interactive {
             print!("trash file '{file_name}'? (y/n): ");
             match stdout().flush() {
@@ -176,18 +388,241 @@ the TUI released with this project, \"Trash Bin\".
                 std::process::exit(EXITCODE_UNSUPPORTED);
             }
         }
+
+        if let Some(op) = operation.as_mut() {
+            match op.record(&trash_file) {
+                Ok(_) => {
+                    if args_conf.verbose && op.trashinfo_paths.len() == 1 {
+                        msg(format!("operation id: {}", op.id));
+                    }
+                }
+                Err(e) => {
+                    msg_err(format!("cannot journal trashing '{file_name}': {e}"));
+                }
+            }
+        }
     }
 }
 
+// print how the trash directory for a file is resolved, following the
+// same steps as trashing it would
+fn explain_trash_root(file_name: &str) -> Result<(), Box<dyn Error>> {
+    let abs_file = to_abs_path(file_name)?;
+    if abs_file.symlink_metadata().is_err() {
+        return Err(Box::<dyn Error>::from("no such file or directory"));
+    }
+
+    let (resolution, trash_dir) =
+        TrashDirectory::explain_for_file(&abs_file, &get_xdg_data_home()?);
+    let device_display = |d: &Device| {
+        format!(
+            "{}:{} (dev id {})",
+            d.dev_num.get_major(),
+            d.dev_num.get_minor(),
+            d.dev_num.dev_id
+        )
+    };
+
+    println!("{}", abs_file.display());
+    if let Some(v) = &resolution.file_device {
+        println!("  file device:            {}", device_display(v));
+    }
+    if let Some(v) = &resolution.xdg_data_home {
+        println!("  $XDG_DATA_HOME:         {}", v.display());
+    }
+    if let Some(v) = &resolution.xdg_data_home_device {
+        println!("  $XDG_DATA_HOME device:  {}", device_display(v));
+    }
+    if let (Some(file_device), Some(xdg_data_home_device)) =
+        (&resolution.file_device, &resolution.xdg_data_home_device)
+    {
+        let same_device = file_device.dev_num.dev_id == xdg_data_home_device.dev_num.dev_id;
+        println!(
+            "  same device:            {}",
+            if same_device { "yes" } else { "no" }
+        );
+    }
+
+    if let Some(file_device) = &resolution.file_device {
+        if let Some(mount_point) = file_device.get_mount_point() {
+            println!("  mount point:            {}", mount_point.display());
+        }
+        if let Some(mount_root) = file_device.get_mount_root() {
+            println!("  mount root:             {}", mount_root.display());
+        }
+        if let Some(dev_name) = file_device.get_dev_name() {
+            println!("  device name:            {dev_name}");
+        }
+    }
+
+    // the checks of each directory, in the order they were made
+    let mut last_path = None;
+    for check in &resolution.checks {
+        if last_path != Some(&check.path) {
+            println!("  {}", check.path.display());
+            last_path = Some(&check.path);
+        }
+        let result = match &check.error {
+            Some(e) => format!("no, {e}"),
+            None => "yes".to_string(),
+        };
+        println!("    {:<26} {result}", format!("{}:", check.name));
+    }
+
+    match trash_dir {
+        Ok(trash_dir) => {
+            println!(
+                "  trash root:             {} ({:?})",
+                trash_dir.home.display(),
+                trash_dir.root_type
+            );
+            Ok(())
+        }
+        Err(e) => Err(Box::<dyn Error>::from(format!(
+            "no usable trash directory: {e}"
+        ))),
+    }
+}
+
+// restore the files trashed by a journaled operation, the last one if no id
+// is given. Entries that have already left the trash bin are skipped, and
+// the ones that couldn't be restored are kept in the journal to try again.
+fn undo_operation(id: Option<&str>, verbose: bool) -> ! {
+    let operation = match id {
+        Some(v) => TrashOperation::load(v).map(Some),
+        None => TrashOperation::last(),
+    };
+
+    let mut operation = match operation {
+        Ok(Some(v)) => v,
+        Ok(None) => {
+            msg_err("nothing to undo");
+            std::process::exit(EXITCODE_OK);
+        }
+        Err(e) => {
+            msg_err(format!("cannot undo: {e}"));
+            std::process::exit(EXITCODE_INVALID_ARGS);
+        }
+    };
+
+    if verbose {
+        msg(format!("undoing operation {}", operation.id));
+    }
+
+    let mut remaining: Vec<PathBuf> = vec![];
+    // restore in reverse, so that a directory is back before its children
+    for trashinfo_path in operation.trashinfo_paths.iter().rev() {
+        if !trashinfo_path.is_file() {
+            if verbose {
+                msg(format!(
+                    "skipping '{}': no longer in the trash bin",
+                    trashinfo_path.display()
+                ));
+            }
+            continue;
+        }
+
+        let trash_file = match TrashFile::from_trashinfo(trashinfo_path) {
+            Ok(v) => v,
+            Err(e) => {
+                msg_err(format!(
+                    "cannot restore '{}': {e}",
+                    trashinfo_path.display()
+                ));
+                remaining.insert(0, trashinfo_path.clone());
+                continue;
+            }
+        };
+
+        let original_file = trash_file.original_file.display().to_string();
+
+        // rename replaces an existing file silently
+        if trash_file.original_file.symlink_metadata().is_ok() {
+            msg_err(format!("cannot restore '{original_file}': file exists"));
+            remaining.insert(0, trashinfo_path.clone());
+            continue;
+        }
+
+        match trash_file.restore() {
+            Ok(_) => {
+                if verbose {
+                    msg(format!("restored '{original_file}'"));
+                }
+            }
+            Err(e) => {
+                msg_err(format!("cannot restore '{original_file}': {e}"));
+                remaining.insert(0, trashinfo_path.clone());
+            }
+        }
+    }
+
+    let failed = !remaining.is_empty();
+    if let Err(e) = operation.save(remaining) {
+        msg_err(format!("cannot update journal: {e}"));
+        std::process::exit(EXITCODE_EXTERNAL);
+    }
+
+    if failed {
+        std::process::exit(EXITCODE_UNSUPPORTED);
+    }
+
+    std::process::exit(EXITCODE_OK);
+}
+
 #[derive(Debug, Clone)]
 struct Args {
-    interactive: bool, // -i, --interactive
-    verbose: bool,     // -v, --verbose
-    help: bool,        // -h, --help
-    version: bool,     // -V, --version
+    interactive: bool,             // -i, --interactive
+    verbose: bool,                 // -v, --verbose
+    help: bool,                    // -h, --help
+    version: bool,                 // -V, --version
+    files_from: Option<FilesFrom>, // --files0-from, --files-from
+    undo: bool,                    // --undo
+    undo_id: Option<String>,       // --undo ID
+    dry_run: bool,                 // --dry-run
+    which: bool,                   // --which
+    completions: Option<Shell>,    // --completions SHELL
+    generate_man: Option<PathBuf>, // --generate-man DIR
     file_names: Vec<String>,
 }
 
+// a source of file operands other than the command line, so that large
+// batches from find -print0 or fd -0 do not hit argv limits
+#[derive(Debug, Clone, PartialEq)]
+struct FilesFrom {
+    source: String, // file path, or - for stdin
+    delimiter: u8,
+}
+
+impl FilesFrom {
+    const STDIN: &'static str = "-";
+
+    fn reads_stdin(&self) -> bool {
+        self.source == Self::STDIN
+    }
+
+    fn read_operands(&self) -> Result<Vec<PathBuf>, Box<dyn Error>> {
+        let mut content: Vec<u8> = vec![];
+        if self.reads_stdin() {
+            stdin().read_to_end(&mut content)?;
+        } else {
+            File::open(&self.source)?.read_to_end(&mut content)?;
+        }
+
+        Ok(Self::split_operands(&content, self.delimiter))
+    }
+
+    // split the raw list into file names, kept as bytes since a file name
+    // doesn't have to be utf-8. empty names are dropped, which also takes care
+    // of the trailing delimiter find and fd put after the last name
+    fn split_operands(content: &[u8], delimiter: u8) -> Vec<PathBuf> {
+        content
+            .split(|b| *b == delimiter)
+            .filter(|name| !name.is_empty())
+            .map(|name| PathBuf::from(OsStr::from_bytes(name)))
+            .collect()
+    }
+}
+
 impl Args {
     fn parse(args: Vec<String>) -> Result<Self, Box<dyn Error>> {
         // need at least one arg
@@ -199,44 +634,143 @@ impl Args {
         let mut verbose: bool = false;
         let mut help: bool = false;
         let mut version: bool = false;
+        let mut files_from: Option<FilesFrom> = None;
+        let mut undo: bool = false;
+        let mut undo_id: Option<String> = None;
+        let mut dry_run: bool = false;
+        let mut which: bool = false;
+        let mut completions: Option<Shell> = None;
+        let mut generate_man: Option<PathBuf> = None;
         let mut file_names: Vec<String> = vec![];
         let mut eoo = false; // -- is end of options
-        for arg in args {
+        let mut args_iter = args.into_iter().peekable();
+        while let Some(arg) = args_iter.next() {
             if eoo {
                 file_names.push(arg);
-            } else {
-                match arg.as_str() {
-                    "--" => eoo = true,
-                    "-i" | "--interactive" => interactive = true,
-                    "-v" | "--verbose" => verbose = true,
-                    "-h" | "--help" => help = true,
-                    "-V" | "--version" => version = true,
-                    "-iv" | "-vi" => {
-                        verbose = true;
-                        interactive = true;
+                continue;
+            }
+
+            match arg.as_str() {
+                "--" => {
+                    eoo = true;
+                    continue;
+                }
+                "-iv" | "-vi" => {
+                    verbose = true;
+                    interactive = true;
+                    continue;
+                }
+                _ => (),
+            }
+
+            // every option accepted is in OPTIONS, so that --help, the man
+            // page, and the completions can't go out of date
+            let (opt, value) = match find_option(OPTIONS, &arg) {
+                Some(v) => v,
+                None => {
+                    if arg.starts_with("-") {
+                        return Err(Box::<dyn Error>::from(format!("invalid option -- '{arg}'")));
                     }
-                    _ => {
-                        if arg.starts_with("-") {
-                            return Err(Box::<dyn Error>::from(format!(
-                                "invalid option -- '{arg}'"
-                            )));
-                        }
 
-                        file_names.push(arg);
+                    file_names.push(arg);
+                    continue;
+                }
+            };
+
+            // --files0-from=F and --files0-from F are both accepted
+            let value = match &opt.value {
+                None => {
+                    if value.is_some() {
+                        return Err(Box::<dyn Error>::from(format!("invalid option -- '{arg}'")));
                     }
+                    None
+                }
+                Some(v) if v.optional => {
+                    value.or_else(|| args_iter.next_if(|a| !a.starts_with("-")))
+                }
+                Some(_) => match value.or_else(|| args_iter.next()) {
+                    Some(v) if !v.is_empty() => Some(v),
+                    _ => {
+                        return Err(Box::<dyn Error>::from(format!(
+                            "option '--{}' requires an argument",
+                            opt.long
+                        )));
+                    }
+                },
+            };
+
+            match opt.long {
+                "help" => help = true,
+                "interactive" => interactive = true,
+                "verbose" => verbose = true,
+                "version" => version = true,
+                "files0-from" | "files-from" => {
+                    let delimiter = if opt.long == "files0-from" {
+                        b'\0'
+                    } else {
+                        b'\n'
+                    };
+
+                    files_from = Some(FilesFrom {
+                        source: value.unwrap(),
+                        delimiter,
+                    });
+                }
+                // the operation id is optional, defaults to the last one
+                "undo" => {
+                    undo = true;
+                    undo_id = value;
+                }
+                "dry-run" => dry_run = true,
+                "which" => which = true,
+                "completions" => completions = Some(Shell::from(&value.unwrap())?),
+                "generate-man" => generate_man = value.map(PathBuf::from),
+                _ => {
+                    return Err(Box::<dyn Error>::from(format!("invalid option -- '{arg}'")));
                 }
             }
         }
 
-        if file_names.is_empty() && !(help || version) {
+        if undo && (!file_names.is_empty() || files_from.is_some()) {
+            return Err(Box::<dyn Error>::from("--undo does not take file operands"));
+        }
+
+        if which && (undo || files_from.is_some() || interactive) {
+            return Err(Box::<dyn Error>::from("--which only takes file operands"));
+        }
+
+        if undo && dry_run {
+            return Err(Box::<dyn Error>::from(
+                "cannot combine --dry-run with --undo",
+            ));
+        }
+
+        if file_names.is_empty()
+            && files_from.is_none()
+            && !(help || version || undo || completions.is_some() || generate_man.is_some())
+        {
             return Err(Box::<dyn Error>::from("missing operand"));
         }
 
+        // confirmations are read from stdin, which is already taken
+        if interactive && files_from.as_ref().is_some_and(|f| f.reads_stdin()) {
+            return Err(Box::<dyn Error>::from(
+                "cannot combine --interactive with file names from standard input",
+            ));
+        }
+
         Ok(Args {
             interactive,
             verbose,
             help,
             version,
+            files_from,
+            undo,
+            undo_id,
+            dry_run,
+            which,
+            completions,
+            generate_man,
             file_names,
         })
     }
@@ -354,5 +888,186 @@ mod tests {
         let i: Vec<String> = vec![String::from("--")];
         let args = Args::parse(i);
         assert!(args.is_err());
+
+        // files-from needs a source
+        let i: Vec<String> = vec![String::from("--files0-from")];
+        let args = Args::parse(i);
+        assert!(args.is_err());
+        let i: Vec<String> = vec![String::from("--files-from=")];
+        let args = Args::parse(i);
+        assert!(args.is_err());
+
+        // stdin can't be used for both file names and confirmations
+        let i: Vec<String> = vec![String::from("-i"), String::from("--files0-from=-")];
+        let args = Args::parse(i);
+        assert!(args.is_err());
+
+        let i: Vec<String> = vec![String::from("--verbose=yes"), String::from("somefile")];
+        let args = Args::parse(i);
+        assert!(args.is_err());
+    }
+
+    #[test]
+    fn test_parse_args_files_from() {
+        let i: Vec<String> = vec![String::from("--files0-from=-")];
+        let args = Args::parse(i);
+        assert!(args.is_ok());
+        let a = args.unwrap();
+        assert!(a.file_names.is_empty());
+        assert!(
+            a.files_from
+                == Some(FilesFrom {
+                    source: String::from("-"),
+                    delimiter: b'\0'
+                })
+        );
+
+        let i: Vec<String> = vec![
+            String::from("-v"),
+            String::from("--files-from"),
+            String::from("list.txt"),
+            String::from("somefile"),
+        ];
+        let args = Args::parse(i);
+        assert!(args.is_ok());
+        let a = args.unwrap();
+        assert!(a.verbose);
+        assert!(a.file_names == vec![String::from("somefile")]);
+        assert!(
+            a.files_from
+                == Some(FilesFrom {
+                    source: String::from("list.txt"),
+                    delimiter: b'\n'
+                })
+        );
+
+        // after end of options, it's a file name
+        let i: Vec<String> = vec![String::from("--"), String::from("--files0-from=-")];
+        let args = Args::parse(i);
+        assert!(args.is_ok());
+        let a = args.unwrap();
+        assert!(a.files_from.is_none());
+        assert!(a.file_names[0] == "--files0-from=-");
+    }
+
+    #[test]
+    fn test_parse_args_dry_run() {
+        let i: Vec<String> = vec![String::from("--dry-run"), String::from("somefile")];
+        let args = Args::parse(i);
+        assert!(args.is_ok());
+        let a = args.unwrap();
+        assert!(a.dry_run && !a.interactive && !a.verbose);
+        assert!(a.file_names[0] == "somefile");
+
+        // still needs a file to trash
+        let i: Vec<String> = vec![String::from("--dry-run")];
+        let args = Args::parse(i);
+        assert!(args.is_err());
+
+        let i: Vec<String> = vec![String::from("--dry-run"), String::from("--undo")];
+        let args = Args::parse(i);
+        assert!(args.is_err());
+    }
+
+    #[test]
+    fn test_parse_args_which() {
+        let i: Vec<String> = vec![String::from("--which"), String::from("somefile")];
+        let args = Args::parse(i);
+        assert!(args.is_ok());
+        let a = args.unwrap();
+        assert!(a.which && a.file_names[0] == "somefile");
+
+        let i: Vec<String> = vec![String::from("--which")];
+        let args = Args::parse(i);
+        assert!(args.is_err());
+
+        let i: Vec<String> = vec![String::from("--which"), String::from("--undo")];
+        let args = Args::parse(i);
+        assert!(args.is_err());
+    }
+
+    #[test]
+    fn test_parse_args_completions() {
+        let i: Vec<String> = vec![String::from("--completions"), String::from("zsh")];
+        let args = Args::parse(i);
+        assert!(args.is_ok());
+        assert!(args.unwrap().completions == Some(Shell::Zsh));
+
+        let i: Vec<String> = vec![String::from("--completions=fish")];
+        let args = Args::parse(i);
+        assert!(args.is_ok());
+        assert!(args.unwrap().completions == Some(Shell::Fish));
+
+        let i: Vec<String> = vec![String::from("--completions")];
+        let args = Args::parse(i);
+        assert!(args.is_err());
+
+        let i: Vec<String> = vec![String::from("--completions=tcsh")];
+        let args = Args::parse(i);
+        assert!(args.is_err());
+    }
+
+    #[test]
+    fn test_parse_args_undo() {
+        let i: Vec<String> = vec![String::from("--undo")];
+        let args = Args::parse(i);
+        assert!(args.is_ok());
+        let a = args.unwrap();
+        assert!(a.undo && a.undo_id.is_none());
+
+        let i: Vec<String> = vec![
+            String::from("--undo"),
+            String::from("20261018101010000-42"),
+            String::from("-v"),
+        ];
+        let args = Args::parse(i);
+        assert!(args.is_ok());
+        let a = args.unwrap();
+        assert!(a.undo && a.verbose);
+        assert!(a.undo_id == Some(String::from("20261018101010000-42")));
+
+        let i: Vec<String> = vec![
+            String::from("-v"),
+            String::from("--undo=20261018101010000-42"),
+        ];
+        let args = Args::parse(i);
+        assert!(args.is_ok());
+        let a = args.unwrap();
+        assert!(a.undo_id == Some(String::from("20261018101010000-42")));
+
+        // nothing to trash when undoing
+        let i: Vec<String> = vec![
+            String::from("--undo"),
+            String::from("-v"),
+            String::from("somefile"),
+        ];
+        let args = Args::parse(i);
+        assert!(args.is_err());
+        let i: Vec<String> = vec![String::from("--undo"), String::from("--files0-from=-")];
+        let args = Args::parse(i);
+        assert!(args.is_err());
+    }
+
+    #[test]
+    fn test_split_operands() {
+        let names = FilesFrom::split_operands(b"a file\0with\nnewline\0", b'\0');
+        assert!(names == vec![PathBuf::from("a file"), PathBuf::from("with\nnewline")]);
+
+        let names = FilesFrom::split_operands(b"one\ntwo\n\nthree", b'\n');
+        assert!(
+            names
+                == vec![
+                    PathBuf::from("one"),
+                    PathBuf::from("two"),
+                    PathBuf::from("three")
+                ]
+        );
+
+        let names = FilesFrom::split_operands(b"", b'\0');
+        assert!(names.is_empty());
+
+        // not utf-8, as is
+        let names = FilesFrom::split_operands(b"bad\xff\0", b'\0');
+        assert!(names == vec![PathBuf::from(OsStr::from_bytes(b"bad\xff"))]);
     }
 }
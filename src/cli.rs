@@ -0,0 +1,693 @@
+use std::error::Error;
+use std::fmt::Write;
+use std::fs::File;
+use std::io::Write as IoWrite;
+use std::path::{Path, PathBuf};
+
+use crate::must_have_dir;
+
+// command line option definitions shared by the binaries, so that the
+// documentation generated from them stays in line with what is accepted
+
+pub struct CliOption {
+    pub short: Option<char>,
+    pub long: &'static str,
+    pub value: Option<CliValue>,
+    pub desc: &'static str,
+}
+
+pub struct CliValue {
+    pub name: &'static str,
+    pub hint: ValueHint,
+    pub optional: bool, // can be left out, ex: --undo [ID]
+}
+
+// what an option value or operand is completed with
+#[derive(PartialEq)]
+pub enum ValueHint {
+    Path,
+    Choices(&'static [&'static str]),
+    Trashed, // original paths of the trashed files, read when completing
+    Any,
+}
+
+// prints the original paths in the trash bins, one per line
+const LIST_TRASHED: &str = "restore --list-trashed 2>/dev/null";
+
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum Shell {
+    Bash,
+    Zsh,
+    Fish,
+}
+
+impl Shell {
+    pub const NAMES: &'static [&'static str] = &["bash", "zsh", "fish"];
+
+    pub fn from(name: &str) -> Result<Self, Box<dyn Error>> {
+        match name {
+            "bash" => Ok(Shell::Bash),
+            "zsh" => Ok(Shell::Zsh),
+            "fish" => Ok(Shell::Fish),
+            _ => Err(Box::<dyn Error>::from(format!(
+                "unsupported shell '{name}', expected one of: {}",
+                Self::NAMES.join(", ")
+            ))),
+        }
+    }
+}
+
+// find the definition for a command line argument, -x or --long[=value].
+// the value is returned if given after =
+pub fn find_option<'a>(
+    options: &'a [CliOption],
+    arg: &str,
+) -> Option<(&'a CliOption, Option<String>)> {
+    if let Some(long) = arg.strip_prefix("--") {
+        let (name, value) = match long.split_once("=") {
+            Some((n, v)) => (n, Some(v.to_string())),
+            None => (long, None),
+        };
+
+        return options.iter().find(|o| o.long == name).map(|o| (o, value));
+    }
+
+    let mut chars = arg.strip_prefix("-")?.chars();
+    let short = chars.next()?;
+    if chars.next().is_some() {
+        return None;
+    }
+
+    options
+        .iter()
+        .find(|o| o.short == Some(short))
+        .map(|o| (o, None))
+}
+
+const HELP_WIDTH: usize = 80;
+const HELP_DESC_COLUMN: usize = 28;
+
+// the option list for --help, in the same layout as coreutils
+pub fn format_options_help(options: &[CliOption]) -> String {
+    let mut help = String::new();
+    for opt in options {
+        let flags = format!(
+            "{}--{}{}",
+            match opt.short {
+                Some(c) => format!("-{c}, "),
+                None => "    ".to_string(),
+            },
+            opt.long,
+            match &opt.value {
+                Some(v) if v.optional => format!("[={}]", v.name),
+                Some(v) => format!("={}", v.name),
+                None => String::new(),
+            }
+        );
+
+        let mut line = format!("    {flags}");
+        if line.len() >= HELP_DESC_COLUMN {
+            let _ = writeln!(help, "{line}");
+            line = String::new();
+        }
+
+        // wrap the description, continuation lines are indented a bit more
+        let mut indent = HELP_DESC_COLUMN;
+        for word in opt.desc.split_whitespace() {
+            if line.len() > indent && line.len() + 1 + word.len() > HELP_WIDTH {
+                let _ = writeln!(help, "{line}");
+                line = String::new();
+                indent = HELP_DESC_COLUMN + 2;
+            }
+
+            if line.len() < indent {
+                line = format!("{line:<indent$}{word}");
+            } else {
+                line = format!("{line} {word}");
+            }
+        }
+        let _ = writeln!(help, "{line}");
+    }
+
+    help
+}
+
+// generate a completion script for a binary. operands are completed with
+// the given hint, None if the binary doesn't take any
+pub fn generate_completions(
+    shell: Shell,
+    binary: &str,
+    options: &[CliOption],
+    operands: Option<ValueHint>,
+) -> String {
+    match shell {
+        Shell::Bash => bash_completions(binary, options, operands),
+        Shell::Zsh => zsh_completions(binary, options, operands),
+        Shell::Fish => fish_completions(binary, options, operands),
+    }
+}
+
+fn bash_completions(binary: &str, options: &[CliOption], operands: Option<ValueHint>) -> String {
+    let func = format!("_{}", binary.replace("-", "_"));
+    let mut words: Vec<String> = vec![];
+    for opt in options {
+        if let Some(c) = opt.short {
+            words.push(format!("-{c}"));
+        }
+        words.push(format!("--{}", opt.long));
+    }
+
+    let mut script = String::new();
+    let _ = writeln!(script, "# bash completion for {binary}");
+    let _ = writeln!(script, "{func}() {{");
+    let _ = writeln!(script, "    local cur prev");
+    let _ = writeln!(script, "    cur=\"${{COMP_WORDS[COMP_CWORD]}}\"");
+    let _ = writeln!(script, "    prev=\"${{COMP_WORDS[COMP_CWORD-1]}}\"");
+    let _ = writeln!(script);
+
+    // values given as a separate word
+    let _ = writeln!(script, "    case \"$prev\" in");
+    for opt in options {
+        let value = match &opt.value {
+            Some(v) if !v.optional => v,
+            _ => continue,
+        };
+
+        let mut names = vec![format!("--{}", opt.long)];
+        if let Some(c) = opt.short {
+            names.push(format!("-{c}"));
+        }
+        let _ = writeln!(script, "        {})", names.join("|"));
+        let _ = writeln!(script, "            {}", bash_compreply(&value.hint));
+        let _ = writeln!(script, "            return");
+        let _ = writeln!(script, "            ;;");
+    }
+    let _ = writeln!(script, "    esac");
+    let _ = writeln!(script);
+
+    let _ = writeln!(script, "    if [[ \"$cur\" == -* ]]; then");
+    let _ = writeln!(
+        script,
+        "        COMPREPLY=($(compgen -W \"{}\" -- \"$cur\"))",
+        words.join(" ")
+    );
+    let _ = writeln!(script, "        return");
+    let _ = writeln!(script, "    fi");
+
+    if let Some(hint) = operands {
+        let _ = writeln!(script);
+        let _ = writeln!(script, "    {}", bash_compreply(&hint));
+    }
+    let _ = writeln!(script, "}}");
+    let _ = writeln!(script, "complete -o filenames -F {func} {binary}");
+
+    script
+}
+
+fn bash_compreply(hint: &ValueHint) -> String {
+    match hint {
+        ValueHint::Path => "COMPREPLY=($(compgen -f -- \"$cur\"))".to_string(),
+        ValueHint::Choices(c) => {
+            format!("COMPREPLY=($(compgen -W \"{}\" -- \"$cur\"))", c.join(" "))
+        }
+        // paths can have spaces, split the listing on lines only
+        ValueHint::Trashed => {
+            format!("local IFS=$'\\n'; COMPREPLY=($(compgen -W \"$({LIST_TRASHED})\" -- \"$cur\"))")
+        }
+        ValueHint::Any => "COMPREPLY=()".to_string(),
+    }
+}
+
+fn zsh_completions(binary: &str, options: &[CliOption], operands: Option<ValueHint>) -> String {
+    let mut script = String::new();
+    let _ = writeln!(script, "#compdef {binary}");
+    let _ = writeln!(script);
+    let _ = writeln!(script, "_arguments -s \\");
+    for opt in options {
+        let desc = zsh_escape(opt.desc);
+        let value = match &opt.value {
+            Some(v) => {
+                let action = zsh_action(&v.hint);
+                if v.optional {
+                    format!("::{}:{action}", v.name)
+                } else {
+                    format!(":{}:{action}", v.name)
+                }
+            }
+            None => String::new(),
+        };
+        // --opt= accepts the value after = or as the next word
+        let long = match &opt.value {
+            Some(v) if v.optional => format!("--{}=-", opt.long),
+            Some(_) => format!("--{}=", opt.long),
+            None => format!("--{}", opt.long),
+        };
+
+        match opt.short {
+            Some(c) => {
+                let _ = writeln!(
+                    script,
+                    "    '(-{c} --{})'{{-{c},{long}}}'[{desc}]{value}' \\",
+                    opt.long
+                );
+            }
+            None => {
+                let _ = writeln!(script, "    '{long}[{desc}]{value}' \\");
+            }
+        }
+    }
+
+    match operands {
+        Some(hint) => {
+            let _ = writeln!(script, "    '*:file:{}'", zsh_action(&hint));
+        }
+        None => {
+            let _ = writeln!(script, "    && return 0");
+        }
+    }
+
+    script
+}
+
+fn zsh_action(hint: &ValueHint) -> String {
+    match hint {
+        ValueHint::Path => "_files".to_string(),
+        ValueHint::Choices(c) => format!("({})", c.join(" ")),
+        ValueHint::Trashed => format!(
+            "{{local -a trashed; trashed=(${{(f)\"$({LIST_TRASHED})\"}}); _multi_parts / trashed}}"
+        ),
+        ValueHint::Any => " ".to_string(),
+    }
+}
+
+// descriptions are inside [] in single quotes
+fn zsh_escape(desc: &str) -> String {
+    desc.replace("'", "'\\''")
+        .replace("[", "\\[")
+        .replace("]", "\\]")
+        .replace(":", "\\:")
+}
+
+fn fish_completions(binary: &str, options: &[CliOption], operands: Option<ValueHint>) -> String {
+    let mut script = String::new();
+    let _ = writeln!(script, "# fish completion for {binary}");
+    match &operands {
+        Some(ValueHint::Path) => (),
+        Some(hint) => {
+            let _ = writeln!(script, "complete -c {binary} -f {}", fish_args(hint));
+        }
+        None => {
+            let _ = writeln!(script, "complete -c {binary} -f");
+        }
+    }
+
+    for opt in options {
+        let mut line = format!("complete -c {binary}");
+        if let Some(c) = opt.short {
+            let _ = write!(line, " -s {c}");
+        }
+        let _ = write!(line, " -l {}", opt.long);
+        // optional values are only given after =, like a flag otherwise
+        match &opt.value {
+            Some(v) if v.optional => (),
+            Some(v) if v.hint == ValueHint::Path => {
+                let _ = write!(line, " -r -F");
+            }
+            Some(v) => {
+                let _ = write!(line, " -x {}", fish_args(&v.hint));
+            }
+            None => (),
+        }
+        let _ = write!(line, " -d '{}'", opt.desc.replace("'", "\\'"));
+        let _ = writeln!(script, "{}", line.trim_end());
+    }
+
+    script
+}
+
+fn fish_args(hint: &ValueHint) -> String {
+    match hint {
+        ValueHint::Choices(c) => format!("-a '{}'", c.join(" ")),
+        ValueHint::Trashed => format!("-a '({LIST_TRASHED})'"),
+        _ => String::new(),
+    }
+}
+
+pub struct ManPage {
+    pub name: &'static str,
+    pub section: u8,
+    pub summary: &'static str,          // one line for the NAME section
+    pub synopsis: Option<&'static str>, // after the name
+    // paragraphs, the ones starting with two spaces are shown as is
+    pub description: &'static [&'static str],
+    pub options: &'static [CliOption],
+    pub sections: &'static [ManSection],
+    pub see_also: &'static [&'static str],
+}
+
+pub struct ManSection {
+    pub title: &'static str,
+    pub paragraphs: &'static [&'static str],
+    pub entries: &'static [(&'static str, &'static str)], // term, description
+}
+
+// trash-rs(5), shared by both binaries
+pub const TRASH_RS_MAN_PAGE: ManPage = ManPage {
+    name: "trash-rs",
+    section: 5,
+    summary: "trash directory layout and configuration for trash and restore",
+    synopsis: None,
+    description: &[
+        "trash(1) and restore(1) implement the freedesktop.org Trash Specification \
+v1.0. Files are never unlinked when trashed, they are moved into a trash \
+directory along with a record of where they came from, so that any \
+implementation of the specification can restore them.",
+    ],
+    options: &[],
+    sections: &[
+        ManSection {
+            title: "TRASH DIRECTORIES",
+            paragraphs: &[
+                "Files on the same device as $XDG_DATA_HOME are moved to the home trash, \
+$XDG_DATA_HOME/Trash. $XDG_DATA_HOME defaults to $HOME/.local/share.",
+                "Files on other devices are moved to a trash directory in the top \
+directory of their mount. $topdir/.Trash/$uid is used if an administrator has \
+created $topdir/.Trash with the sticky bit set, and it is not a symbolic link. \
+Otherwise $topdir/.Trash-$uid is used, and created if needed. Files are not \
+moved across devices to the home trash. trash --which explains this decision \
+for a file.",
+            ],
+            entries: &[],
+        },
+        ManSection {
+            title: "LAYOUT",
+            paragraphs: &[
+                "Each trash directory contains the following.",
+                "  files/          the trashed files and directories
+  info/           a NAME.trashinfo entry for each trashed file
+  directorysizes  the sizes of the trashed directories",
+                "A trashinfo entry records the original location, url encoded, and the \
+local time the file was trashed. The location is absolute in the home trash, \
+and relative to the top directory of the mount otherwise.",
+                "  [Trash Info]
+  Path=/home/user/notes.txt
+  DeletionDate=2024-03-01T10:15:42",
+                "Each line of directorysizes is the size of a trashed directory in \
+bytes, as calculated by du -B1, the modification time of its trashinfo entry \
+in seconds since the epoch, and its url encoded name in files/.",
+            ],
+            entries: &[],
+        },
+        ManSection {
+            title: "FILES",
+            paragraphs: &[
+                "$XDG_STATE_HOME/trash-rs/journal/ holds a file per trash invocation, \
+named after its operation ID, listing the trashinfo entries it created. These \
+are used by trash --undo. $XDG_STATE_HOME defaults to $HOME/.local/state.",
+                "$XDG_CONFIG_HOME/trash-rs/keys.conf changes the keys of restore(1). \
+$XDG_CONFIG_HOME defaults to $HOME/.config. Each line binds an action to a \
+comma separated list of keys, replacing the keys of the action and taking the \
+keys away from the other actions of the same screen. A preset line picks the \
+keys to start from, vim (the default) or emacs. Lines starting with # are \
+comments. Keys are names like enter, esc, tab, space, del, pgup, up or f1, or \
+a single character, with optional ctrl+, alt+ and shift+ prefixes. The actions \
+are listed in the help screen of restore(1).",
+                "  preset = emacs
+  quit = q, ctrl+c
+  delete = del, d",
+                "$XDG_CONFIG_HOME/trash-rs/themes/NAME.conf is a colour theme of \
+restore(1), picked with TRASH_RS_THEME=NAME. Each line sets a colour, by a \
+name like light-red, a 256 colour index, or #rrggbb. A base line picks the \
+theme to start from for the colours that aren't set, dark (the default) or \
+light. The colours are highlight, title-text, text, bold-text, error-text, \
+selected-fg-dir, selected-fg-link, selected-fg-file, selected-bg, \
+unselected-fg-dir, unselected-fg-link, unselected-fg-file, dialog-bg, \
+dialog-text, dialog-button-bg, dialog-button-text, syntax-keyword, \
+syntax-string, syntax-comment and syntax-number.",
+                "  base = light
+  highlight = #268bd2
+  selected-bg = 254",
+            ],
+            entries: &[],
+        },
+        ManSection {
+            title: "ENVIRONMENT",
+            paragraphs: &[
+                "XDG_DATA_HOME, XDG_STATE_HOME and XDG_CONFIG_HOME locate the home trash, \
+the journal and the keys of restore(1), as described above.",
+                "TRASH_RS_THEME selects the colour theme of restore(1), dark, light, or \
+the name of a theme file. Without it, restore(1) shows no colours if NO_COLOR \
+is set, and otherwise asks the terminal for its background colour to pick \
+between dark and light, falling back to dark.",
+            ],
+            entries: &[],
+        },
+    ],
+    see_also: &["trash(1)", "restore(1)"],
+};
+
+// generate a roff man page
+pub fn generate_man_page(page: &ManPage) -> String {
+    let version = env!("CARGO_PKG_VERSION");
+    let package = env!("CARGO_PKG_NAME");
+    let manual = if page.section == 1 {
+        "User Commands"
+    } else {
+        "File Formats"
+    };
+
+    let mut roff = String::new();
+    let _ = writeln!(
+        roff,
+        ".TH \"{}\" \"{}\" \"\" \"{package} {version}\" \"{manual}\"",
+        page.name.to_uppercase(),
+        page.section
+    );
+    let _ = writeln!(roff, ".SH NAME");
+    let _ = writeln!(roff, "{} \\- {}", page.name, roff_escape(page.summary));
+
+    if let Some(synopsis) = page.synopsis {
+        let _ = writeln!(roff, ".SH SYNOPSIS");
+        let _ = writeln!(roff, ".B {}", page.name);
+        let _ = writeln!(roff, "{}", roff_escape(synopsis));
+    }
+
+    let _ = writeln!(roff, ".SH DESCRIPTION");
+    roff_paragraphs(&mut roff, page.description);
+
+    if !page.options.is_empty() {
+        let _ = writeln!(roff, ".SH OPTIONS");
+        for opt in page.options {
+            let mut flags = vec![];
+            if let Some(c) = opt.short {
+                flags.push(format!("\\fB\\-{c}\\fR"));
+            }
+            let value = match &opt.value {
+                Some(v) if v.optional => format!("[=\\fI{}\\fR]", v.name),
+                Some(v) => format!("=\\fI{}\\fR", v.name),
+                None => String::new(),
+            };
+            flags.push(format!("\\fB\\-\\-{}\\fR{value}", roff_escape(opt.long)));
+
+            let _ = writeln!(roff, ".TP");
+            let _ = writeln!(roff, "{}", flags.join(", "));
+            let _ = writeln!(roff, "{}", roff_escape(opt.desc));
+        }
+    }
+
+    for section in page.sections {
+        let _ = writeln!(roff, ".SH {}", section.title);
+        roff_paragraphs(&mut roff, section.paragraphs);
+        for (term, desc) in section.entries {
+            let _ = writeln!(roff, ".TP");
+            let _ = writeln!(roff, "\\fB{}\\fR", roff_escape(term));
+            let _ = writeln!(roff, "{}", roff_escape(desc));
+        }
+    }
+
+    if !page.see_also.is_empty() {
+        let _ = writeln!(roff, ".SH SEE ALSO");
+        let refs: Vec<String> = page
+            .see_also
+            .iter()
+            .map(|r| match r.split_once("(") {
+                Some((name, section)) => format!("\\fB{}\\fR({section}", roff_escape(name)),
+                None => roff_escape(r),
+            })
+            .collect();
+        let _ = writeln!(roff, "{}", refs.join(", "));
+    }
+
+    roff
+}
+
+// write the man pages to a directory as NAME.SECTION, returning the files
+// written
+pub fn write_man_pages(dir: &Path, pages: &[&ManPage]) -> Result<Vec<PathBuf>, Box<dyn Error>> {
+    must_have_dir(&dir.to_path_buf())?;
+
+    let mut written: Vec<PathBuf> = vec![];
+    for page in pages {
+        let path = dir.join(format!("{}.{}", page.name, page.section));
+        let mut f = File::create(&path)?;
+        f.write_all(generate_man_page(page).as_bytes())?;
+        written.push(path);
+    }
+
+    Ok(written)
+}
+
+fn roff_paragraphs(roff: &mut String, paragraphs: &[&str]) {
+    for (i, paragraph) in paragraphs.iter().enumerate() {
+        if i > 0 {
+            let _ = writeln!(roff, ".PP");
+        }
+
+        if paragraph.starts_with("  ") {
+            let _ = writeln!(roff, ".RS 4");
+            let _ = writeln!(roff, ".nf");
+            for line in paragraph.lines() {
+                let _ = writeln!(roff, "{}", roff_escape(line.trim_start()));
+            }
+            let _ = writeln!(roff, ".fi");
+            let _ = writeln!(roff, ".RE");
+        } else {
+            let _ = writeln!(roff, "{}", roff_escape(paragraph));
+        }
+    }
+}
+
+// escape text so that roff doesn't treat it as requests or escapes
+fn roff_escape(text: &str) -> String {
+    let escaped = text.replace("\\", "\\e").replace("-", "\\-");
+    let mut lines: Vec<String> = vec![];
+    for line in escaped.lines() {
+        if line.starts_with(".") || line.starts_with("'") {
+            lines.push(format!("\\&{line}"));
+        } else {
+            lines.push(line.to_string());
+        }
+    }
+
+    lines.join("\n")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const TEST_OPTIONS: &[CliOption] = &[
+        CliOption {
+            short: Some('v'),
+            long: "verbose",
+            value: None,
+            desc: "explain what is being done",
+        },
+        CliOption {
+            short: None,
+            long: "files0-from",
+            value: Some(CliValue {
+                name: "F",
+                hint: ValueHint::Path,
+                optional: false,
+            }),
+            desc: "read names from [F]",
+        },
+        CliOption {
+            short: None,
+            long: "completions",
+            value: Some(CliValue {
+                name: "SHELL",
+                hint: ValueHint::Choices(Shell::NAMES),
+                optional: false,
+            }),
+            desc: "print completion script",
+        },
+    ];
+
+    #[test]
+    fn test_generate_completions() {
+        let bash = generate_completions(Shell::Bash, "trash", TEST_OPTIONS, Some(ValueHint::Path));
+        assert!(bash.contains("-v --verbose --files0-from --completions"));
+        assert!(bash.contains("--completions)"));
+        assert!(bash.contains("compgen -W \"bash zsh fish\""));
+        assert!(bash.contains("complete -o filenames -F _trash trash"));
+
+        let zsh = generate_completions(Shell::Zsh, "trash", TEST_OPTIONS, Some(ValueHint::Path));
+        assert!(zsh.starts_with("#compdef trash"));
+        assert!(zsh.contains("'(-v --verbose)'{-v,--verbose}'[explain what is being done]'"));
+        assert!(zsh.contains("'--files0-from=[read names from \\[F\\]]:F:_files'"));
+        assert!(zsh.contains("'*:file:_files'"));
+
+        let fish = generate_completions(Shell::Fish, "restore", TEST_OPTIONS, None);
+        assert!(fish.contains("complete -c restore -f\n"));
+        assert!(fish.contains("complete -c restore -l files0-from -r -F"));
+        assert!(fish.contains("complete -c restore -l completions -x -a 'bash zsh fish'"));
+
+        // restore completes the original paths of what's in the trash
+        let bash = generate_completions(Shell::Bash, "restore", &[], Some(ValueHint::Trashed));
+        assert!(bash.contains(
+            "local IFS=$'\\n'; COMPREPLY=($(compgen -W \"$(restore --list-trashed 2>/dev/null)\" -- \"$cur\"))"
+        ));
+        let zsh = generate_completions(Shell::Zsh, "restore", &[], Some(ValueHint::Trashed));
+        assert!(zsh.contains(
+            "'*:file:{local -a trashed; trashed=(${(f)\"$(restore --list-trashed 2>/dev/null)\"}); _multi_parts / trashed}'"
+        ));
+        let fish = generate_completions(Shell::Fish, "restore", &[], Some(ValueHint::Trashed));
+        assert!(fish.contains("complete -c restore -f -a '(restore --list-trashed 2>/dev/null)'\n"));
+
+        assert!(Shell::from("tcsh").is_err());
+        assert!(Shell::from("fish").unwrap() == Shell::Fish);
+    }
+    #[test]
+    fn test_find_option() {
+        let (opt, value) = find_option(TEST_OPTIONS, "-v").unwrap();
+        assert!(opt.long == "verbose" && value.is_none());
+        let (opt, value) = find_option(TEST_OPTIONS, "--verbose").unwrap();
+        assert!(opt.long == "verbose" && value.is_none());
+        let (opt, value) = find_option(TEST_OPTIONS, "--files0-from=-").unwrap();
+        assert!(opt.long == "files0-from" && value == Some(String::from("-")));
+
+        assert!(find_option(TEST_OPTIONS, "-x").is_none());
+        assert!(find_option(TEST_OPTIONS, "-vv").is_none());
+        assert!(find_option(TEST_OPTIONS, "--").is_none());
+        assert!(find_option(TEST_OPTIONS, "somefile").is_none());
+    }
+
+    #[test]
+    fn test_format_options_help() {
+        let help = format_options_help(TEST_OPTIONS);
+        let lines: Vec<&str> = help.lines().collect();
+        assert!(lines[0] == "    -v, --verbose           explain what is being done");
+        assert!(lines[1] == "        --files0-from=F     read names from [F]");
+        assert!(lines.iter().all(|l| l.len() <= HELP_WIDTH));
+    }
+
+    #[test]
+    fn test_generate_man_page() {
+        let page = ManPage {
+            name: "trash",
+            section: 1,
+            summary: "move files to the trash bin",
+            synopsis: Some("[OPTION]... [FILE]..."),
+            description: &[".starts with a dot", "  trash -- -foo"],
+            options: TEST_OPTIONS,
+            sections: &[ManSection {
+                title: "KEYS",
+                paragraphs: &[],
+                entries: &[("q", "quit")],
+            }],
+            see_also: &["restore(1)"],
+        };
+
+        let roff = generate_man_page(&page);
+        assert!(roff.starts_with(".TH \"TRASH\" \"1\""));
+        assert!(roff.contains(".SH NAME\ntrash \\- move files to the trash bin\n"));
+        assert!(roff.contains("\\&.starts with a dot\n"));
+        assert!(roff.contains(".nf\ntrash \\-\\- \\-foo\n.fi\n"));
+        assert!(roff.contains(".TP\n\\fB\\-v\\fR, \\fB\\-\\-verbose\\fR\n"));
+        assert!(roff.contains("\\fB\\-\\-files0\\-from\\fR=\\fIF\\fR\n"));
+        assert!(roff.contains(".SH KEYS\n.TP\n\\fBq\\fR\nquit\n"));
+        assert!(roff.contains(".SH SEE ALSO\n\\fBrestore\\fR(1)\n"));
+    }
+}
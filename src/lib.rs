@@ -1,19 +1,22 @@
-use chrono::{DateTime, Local};
+use chrono::{DateTime, Local, NaiveDateTime, TimeZone};
 use rand::Rng;
 use std::env;
 use std::error::Error;
-use std::ffi::CString;
+use std::ffi::{CString, OsStr, OsString};
 use std::fs::{
-    create_dir_all, read_dir, read_to_string, remove_dir_all, remove_file, rename, File,
-    OpenOptions,
+    copy, create_dir, create_dir_all, read_dir, read_link, read_to_string, remove_dir_all,
+    remove_file, rename, set_permissions, File, OpenOptions,
 };
 use std::io::Write;
 use std::os::linux::fs::MetadataExt;
+use std::os::unix::ffi::{OsStrExt, OsStringExt};
 use std::path::MAIN_SEPARATOR_STR;
-use std::path::{Path, PathBuf};
+use std::path::{Component, Path, PathBuf};
 use std::time::SystemTime;
 
-use urlencoding::{decode, encode};
+use urlencoding::{decode_binary, encode_binary};
+
+pub mod cli;
 
 // Does NOT support trashing files from external mounts to user's trash dir
 // Does NOT trash a file from external mounts to home if topdirs cannot be used
@@ -25,6 +28,28 @@ pub enum TrashRootType {
     TopDirUser, // trash directory is the .Trash-{euid} directory in the top directory for the mount the file exists in
 }
 
+// how missing trash directories are handled while resolving a trash root
+#[derive(Clone, Copy, Eq, PartialEq, Debug)]
+pub enum DirCreation {
+    Create,    // create them, as needed when trashing a file
+    MustExist, // treat them as unusable
+    DryRun,    // accept them if they could be created, without creating them
+}
+
+impl DirCreation {
+    // what ensure_dir makes sure of for a directory
+    pub fn get_check_name(&self) -> &'static str {
+        match self {
+            DirCreation::Create => "exists, or is created",
+            DirCreation::MustExist => "exists",
+            DirCreation::DryRun => "exists, or can be created",
+        }
+    }
+}
+
+// the readable entries of a trash directory and the errors of the rest
+pub type TrashListing = (Vec<TrashFile>, Vec<Box<dyn Error>>);
+
 #[derive(Clone)]
 pub struct TrashDirectory {
     pub device: Device,
@@ -61,6 +86,54 @@ impl TrashDirectory {
     pub fn resolve_for_file(
         abs_file_path: &Path,
         verbose: bool,
+    ) -> Result<TrashDirectory, Box<dyn Error>> {
+        TrashDirectory::try_resolve_for_file(
+            abs_file_path,
+            &get_xdg_data_home()?,
+            verbose,
+            DirCreation::Create,
+            &mut TrashRootResolution::default(),
+        )
+    }
+
+    // same as resolve_for_file, without creating any missing trash directories
+    pub fn probe_for_file(
+        abs_file_path: &Path,
+        verbose: bool,
+    ) -> Result<TrashDirectory, Box<dyn Error>> {
+        TrashDirectory::try_resolve_for_file(
+            abs_file_path,
+            &get_xdg_data_home()?,
+            verbose,
+            DirCreation::DryRun,
+            &mut TrashRootResolution::default(),
+        )
+    }
+
+    // the steps probe_for_file takes for a file, with the checks made along
+    // the way and where it stopped if no trash directory can be used
+    pub fn explain_for_file(
+        abs_file_path: &Path,
+        xdg_data_home: &Path,
+    ) -> (TrashRootResolution, Result<TrashDirectory, Box<dyn Error>>) {
+        let mut resolution = TrashRootResolution::default();
+        let trash_dir = TrashDirectory::try_resolve_for_file(
+            abs_file_path,
+            xdg_data_home,
+            false,
+            DirCreation::DryRun,
+            &mut resolution,
+        );
+        (resolution, trash_dir)
+    }
+
+    // the checks made on the trash directories are recorded in resolution
+    pub fn try_resolve_for_file(
+        abs_file_path: &Path,
+        xdg_data_home: &Path,
+        verbose: bool,
+        creation: DirCreation,
+        resolution: &mut TrashRootResolution,
     ) -> Result<TrashDirectory, Box<dyn Error>> {
         if verbose {
             msg("deriving trash root");
@@ -68,13 +141,21 @@ impl TrashDirectory {
 
         // check if the file is in a home mount
         // "To be more precise, from a partition/device different from the one on which $XDG_DATA_HOME resides"
-        let xdg_data_home = get_xdg_data_home()?;
-        must_have_dir(&xdg_data_home)?;
+        resolution.xdg_data_home = Some(xdg_data_home.to_path_buf());
+        resolution.check(
+            xdg_data_home,
+            creation.get_check_name(),
+            ensure_dir(&xdg_data_home.to_path_buf(), creation),
+        )?;
         if verbose {
             msg("deriving file device");
         }
         let mut file_dev = Device::for_path(abs_file_path)?;
-        let xdg_data_home_dev = Device::for_path(&xdg_data_home)?;
+        // in a dry run $XDG_DATA_HOME may not be there yet, the device it
+        // would be created on is the one of its closest existing parent
+        let xdg_data_home_dev = Device::for_path(&get_closest_existing_path(xdg_data_home))?;
+        resolution.file_device = Some(file_dev.clone());
+        resolution.xdg_data_home_device = Some(xdg_data_home_dev.clone());
         let trash_root_type: TrashRootType;
 
         if verbose {
@@ -90,12 +171,17 @@ impl TrashDirectory {
             // not exist, the implementation SHOULD automatically create it,
             // without any warnings or delays
             let trash_home = xdg_data_home.join("Trash");
-            must_have_dir(&trash_home)?;
+            resolution.check(
+                &trash_home,
+                creation.get_check_name(),
+                ensure_dir(&trash_home, creation),
+            )?;
             trash_root_type = TrashRootType::Home;
 
             trash_home
         } else {
             file_dev.resolve_mount()?;
+            resolution.file_device = Some(file_dev.clone());
             let top_dir = file_dev.mount_point.clone().unwrap();
 
             // user specific directory name
@@ -107,7 +193,7 @@ impl TrashDirectory {
                 euid = libc::geteuid();
             }
 
-            match Self::try_topdir_admin_trash_for(&top_dir.clone(), euid, true) {
+            match Self::try_topdir_admin_trash_for(&top_dir.clone(), euid, creation, resolution) {
                 Ok(v) => {
                     trash_root_type = TrashRootType::TopDirAdmin;
                     v
@@ -122,9 +208,13 @@ impl TrashDirectory {
                     // check to the administrator, and MAY also report it to
                     // the user.
 
-                    msg_err(format!("top directory trash for file is unusable: {e}"));
+                    // the probes leave the report to the resolution
+                    if creation == DirCreation::Create {
+                        msg_err(format!("top directory trash for file is unusable: {e}"));
+                    }
 
-                    let top_dir_user_trash = Self::try_topdir_user_trash_for(&top_dir, euid, true)?;
+                    let top_dir_user_trash =
+                        Self::try_topdir_user_trash_for(&top_dir, euid, creation, resolution)?;
                     trash_root_type = TrashRootType::TopDirUser;
                     top_dir_user_trash
                 }
@@ -136,10 +226,18 @@ impl TrashDirectory {
         }
 
         let files_dir = trash_home.join("files");
-        must_have_dir(&files_dir)?;
+        resolution.check(
+            &files_dir,
+            creation.get_check_name(),
+            ensure_dir(&files_dir, creation),
+        )?;
 
         let info_dir = trash_home.join("info");
-        must_have_dir(&info_dir)?;
+        resolution.check(
+            &info_dir,
+            creation.get_check_name(),
+            ensure_dir(&info_dir, creation),
+        )?;
 
         Ok(TrashDirectory {
             device: file_dev,
@@ -154,12 +252,11 @@ impl TrashDirectory {
         &self,
         trash_file: &mut TrashFile,
     ) -> Result<(), Box<dyn Error>> {
-        let stripped_file_name = trash_file
-            .original_file
-            .file_name()
-            .unwrap()
-            .to_str()
-            .unwrap();
+        // file names are bytes, not necessarily utf-8
+        let stripped_file_name = match trash_file.original_file.file_name() {
+            Some(v) => v.to_os_string(),
+            None => return Err(Box::<dyn Error>::from("file path has no file name")),
+        };
 
         // if filename present, start testing for files with an integer suffix
         // following nautilus way of starting from 2
@@ -167,13 +264,10 @@ impl TrashDirectory {
         // looks like there's no real limit in giolocalfile
         // https://gitlab.gnome.org/GNOME/glib/-/blob/main/gio/glocalfile.c?ref_type=heads#L2234
         for n in 1..u32::MAX {
-            let trashable_file_name =
-                Self::get_trashable_file_name(stripped_file_name.to_string(), n);
-            let file = self.files.join(trashable_file_name);
-            let trashinfo = self.info.join(format!(
-                "{}.trashinfo",
-                file.file_name().unwrap().to_str().unwrap()
-            ));
+            let mut trashable_file_name = Self::get_trashable_file_name(&stripped_file_name, n);
+            let file = self.files.join(&trashable_file_name);
+            trashable_file_name.push(".trashinfo");
+            let trashinfo = self.info.join(trashable_file_name);
 
             // we've found a fresh number!!
             if !file.exists() && !trashinfo.exists() {
@@ -184,12 +278,12 @@ impl TrashDirectory {
                 // The system SHOULD support absolute pathnames only in the
                 // “home trash” directory, not in the directories under $topdir
                 let file_path_key = match self.root_type {
-                    TrashRootType::Home => trash_file.original_file.to_str().unwrap(),
+                    TrashRootType::Home => trash_file.original_file.as_path(),
                     _ => {
                         let trash_home_mt_point = self.device.mount_point.as_ref().unwrap();
                         relative_path =
                             get_path_relative_to(&trash_file.original_file, trash_home_mt_point)?;
-                        relative_path.to_str().unwrap()
+                        relative_path.as_path()
                     }
                 };
 
@@ -283,8 +377,8 @@ impl TrashDirectory {
         let mtime_epoch = mtime.duration_since(SystemTime::UNIX_EPOCH)?.as_secs();
 
         // encode the dir name
-        let dir_name = trashed_file.file_name().unwrap().to_str().unwrap();
-        let encoded_dir_name = encode(dir_name);
+        let dir_name = trashed_file.file_name().unwrap_or_default();
+        let encoded_dir_name = encode_binary(dir_name.as_bytes());
 
         let mut rng = rand::thread_rng();
         let random_nu = rng.gen_range(100000000..999999999);
@@ -307,17 +401,15 @@ impl TrashDirectory {
         // part of the spec. If this isn't done, directorysizes keeps on growing
         let mut existing_content = if current_dir_sizes.metadata()?.st_size() != 0 {
             let mut existing_content: String = String::new();
-            let existing_dir_sizes = read_to_string(current_dir_sizes.to_str().unwrap())?;
+            let existing_dir_sizes = read_to_string(&current_dir_sizes)?;
             let trash_file_path = trash_file.files_entry.clone().unwrap();
-            let trash_file_name = trash_file_path.file_name().unwrap().to_str().unwrap();
+            let trash_file_name = trash_file_path.file_name().unwrap_or_default();
             let entries: Vec<&str> = existing_dir_sizes.lines().collect();
             for entry in entries {
                 let fields: Vec<&str> = entry.split_whitespace().collect();
                 if fields.len() == 3 {
-                    let f = match decode(fields[2]) {
-                        Ok(v) => v,
-                        Err(_) => continue,
-                    };
+                    let decoded = decode_binary(fields[2].as_bytes());
+                    let f = OsStr::from_bytes(&decoded);
 
                     // the directory being trashed could be one that was
                     // trashed before and then restored by an implementation
@@ -332,7 +424,7 @@ impl TrashDirectory {
                         continue;
                     }
 
-                    let f_path = self.files.join(f.into_owned());
+                    let f_path = self.files.join(f);
                     if f_path.exists() {
                         existing_content += &format!("{entry}\n").to_string();
                     }
@@ -385,17 +477,13 @@ impl TrashDirectory {
         // cleanup existing entries if other implementations do not support this
         // part of the spec. If this isn't done, directorysizes keeps on growing
         let mut existing_content: String = String::new();
-        let existing_dir_sizes = read_to_string(current_dir_sizes.to_str().unwrap())?;
+        let existing_dir_sizes = read_to_string(&current_dir_sizes)?;
         let entries: Vec<&str> = existing_dir_sizes.lines().collect();
         for entry in entries {
             let fields: Vec<&str> = entry.split_whitespace().collect();
             if fields.len() == 3 {
-                let f = match decode(fields[2]) {
-                    Ok(v) => v,
-                    Err(_) => continue,
-                };
-
-                let f_path = self.files.join(f.into_owned());
+                let decoded = decode_binary(fields[2].as_bytes());
+                let f_path = self.files.join(OsStr::from_bytes(&decoded));
                 if f_path.exists() {
                     existing_content += &format!("{entry}\n").to_string();
                 }
@@ -414,6 +502,61 @@ impl TrashDirectory {
         rename(&target_file_path, &current_dir_sizes)?;
         Ok(())
     }
+    // look for entries that are only half there: a files entry without a
+    // trashinfo, or the other way around, and trashinfo files that can't be
+    // read. nothing is changed
+    pub fn check(&self) -> Result<TrashRootCheck, Box<dyn Error>> {
+        let mut check = TrashRootCheck::default();
+        for child in read_dir(&self.files)? {
+            let files_entry = child?.path();
+            let mut trashinfo_name = files_entry.file_name().unwrap_or_default().to_os_string();
+            trashinfo_name.push(".trashinfo");
+            if !self.info.join(trashinfo_name).is_file() {
+                check.orphaned_files.push(files_entry);
+            }
+        }
+
+        for child in read_dir(&self.info)? {
+            let trashinfo_path = child?.path();
+            let Some(files_entry_name) = trashinfo_path
+                .file_name()
+                .and_then(|n| n.as_bytes().strip_suffix(b".trashinfo"))
+                .map(OsStr::from_bytes)
+            else {
+                continue;
+            };
+
+            if self
+                .files
+                .join(files_entry_name)
+                .symlink_metadata()
+                .is_err()
+            {
+                check.orphaned_trashinfos.push(trashinfo_path);
+            } else if TrashInfo::from(&trashinfo_path).is_err() {
+                check.invalid_trashinfos.push(trashinfo_path);
+            }
+        }
+
+        Ok(check)
+    }
+
+    // fix what can be fixed without losing a file: the trashinfo files of
+    // entries that are gone, and the directorysizes lines for them. files
+    // entries without a trashinfo are left for the user to look at
+    pub fn repair(&self, check: &TrashRootCheck) -> Result<(), Box<dyn Error>> {
+        for trashinfo_path in &check.orphaned_trashinfos {
+            remove_file(trashinfo_path)?;
+        }
+
+        // a missing directorysizes is nothing to clean up
+        if self.get_dirsizes_path()?.exists() {
+            self.cleanup_dirsizes()?;
+        }
+
+        Ok(())
+    }
+
     pub fn get_trashed_files(&self) -> Result<Vec<TrashFile>, Box<dyn Error>> {
         let files_dir = self.files.clone();
         let mut files: Vec<TrashFile> = vec![];
@@ -427,6 +570,32 @@ impl TrashDirectory {
         Ok(files)
     }
 
+    // like get_trashed_files, but an entry that can't be read doesn't hide
+    // the rest. the errors are returned along with the files
+    pub fn get_trashed_files_and_errors(&self) -> Result<TrashListing, Box<dyn Error>> {
+        let mut files: Vec<TrashFile> = vec![];
+        let mut errors: Vec<Box<dyn Error>> = vec![];
+        for child in read_dir(&self.files)? {
+            let child_path = match child {
+                Ok(v) => v.path(),
+                Err(e) => {
+                    errors.push(Box::<dyn Error>::from(e));
+                    continue;
+                }
+            };
+
+            match TrashFile::from(child_path.clone(), self) {
+                Ok(v) => files.push(v),
+                Err(e) => errors.push(Box::<dyn Error>::from(format!(
+                    "'{}': {e}",
+                    child_path.display()
+                ))),
+            }
+        }
+
+        Ok((files, errors))
+    }
+
     pub fn get_all_trash_roots() -> Result<Vec<TrashDirectory>, Box<dyn Error>> {
         // filter /proc/mounts
         let mounts_content = read_to_string("/proc/mounts")?;
@@ -482,33 +651,56 @@ impl TrashDirectory {
     // files/directories with the same name can be trashed from difference
     // sources (or even from the same source).This should be handled without
     // exposing the details to the user
-    pub fn get_trashable_file_name(stripped_file_name: String, idx: u32) -> String {
+    pub fn get_trashable_file_name(stripped_file_name: &OsStr, idx: u32) -> OsString {
         // nautilus trash files when duplicated start from suffix 2
         if idx < 2 {
-            return stripped_file_name;
+            return stripped_file_name.to_os_string();
         }
 
         // suffix is before the file extension if present, even if it is a dir
         // ex: test.dir.ext would be test.2.dir.ext
-        if stripped_file_name.contains(".") {
-            let components = stripped_file_name.splitn(2, ".").collect::<Vec<&str>>();
-            return format!("{}.{}.{}", components[0], idx, components[1]);
-        }
+        let name = stripped_file_name.as_bytes();
+        let suffixed = match name.iter().position(|b| *b == b'.') {
+            Some(i) => [&name[..i], format!(".{idx}").as_bytes(), &name[i..]].concat(),
+            None => [name, format!(".{idx}").as_bytes()].concat(),
+        };
+        OsString::from_vec(suffixed)
+    }
 
-        format!("{}.{}", stripped_file_name, idx)
+    // the top directory of the mount this trash directory belongs to, which
+    // relative paths in topdir trashinfo entries are resolved against
+    // None for the home trash, since its entries are absolute
+    pub fn get_top_dir(&self) -> Option<PathBuf> {
+        match self.root_type {
+            TrashRootType::Home => None,
+            // $topdir/.Trash-$uid
+            TrashRootType::TopDirUser => self.home.parent().map(|p| p.to_path_buf()),
+            // $topdir/.Trash/$uid
+            TrashRootType::TopDirAdmin => self
+                .home
+                .parent()
+                .and_then(|p| p.parent())
+                .map(|p| p.to_path_buf()),
+        }
     }
 
     pub fn topdir_admin_trash_exists_for(
         top_dir: &Path,
         euid: libc::uid_t,
     ) -> Result<PathBuf, Box<dyn Error>> {
-        TrashDirectory::try_topdir_admin_trash_for(top_dir, euid, false)
+        TrashDirectory::try_topdir_admin_trash_for(
+            top_dir,
+            euid,
+            DirCreation::MustExist,
+            &mut TrashRootResolution::default(),
+        )
     }
 
     pub fn try_topdir_admin_trash_for(
         top_dir: &Path,
         euid: libc::uid_t,
-        create_if_not_exist: bool,
+        creation: DirCreation,
+        resolution: &mut TrashRootResolution,
     ) -> Result<PathBuf, Box<dyn Error>> {
         // An administrator can create an $topdir/.Trash directory. The
         // permissions on this directories should permit all users who
@@ -517,76 +709,75 @@ impl TrashDirectory {
         //
         // check if $topdir/.Trash exist and is usable
         let admin_trash = top_dir.join(".Trash");
-        let admin_trash_location = admin_trash.to_str().unwrap();
-        match admin_trash.try_exists() {
-            Ok(true) => {
-                // If this directory is present, the implementation MUST,
-                // by default, check for the “sticky bit”.
-                // todo: provide superusers to disable this check to
-                // support filesystems that don't support sticky bit.
-                //
-                // The implementation also MUST check that this directory
-                // is not a symbolic link.
-
-                // test if user can write to this dir
-                if !is_writable_dir(&admin_trash) {
-                    return Err(Box::<dyn Error>::from(format!(
-                        "top directory trash '{admin_trash_location}' isn't writable"
-                    )));
-                }
-
-                // check if sticky bit is set and is not a symlink
-                let mode = admin_trash.metadata()?.st_mode();
-                let sticky_bit_set = mode & libc::S_ISVTX == libc::S_ISVTX;
-                if sticky_bit_set && !admin_trash.is_symlink() {
-                    // topdir approach 1
-                    //
-                    // if this directory does not exist for the current user, the
-                    // implementation MUST immediately create it, without any
-                    // warnings or delays for the user.
-                    //
-                    // $topdir/.Trash/$uid
-                    let user_trash_home = admin_trash.join(euid.to_string());
-                    if create_if_not_exist {
-                        must_have_dir(&user_trash_home)?;
-                    } else if !user_trash_home.try_exists().unwrap_or(false) {
-                        return Err(Box::<dyn Error>::from(format!(
-                            "user directory in top directory trash '{}' isn't writable",
-                            user_trash_home.display(),
-                        )));
-                    }
-
-                    if !is_writable_dir(&user_trash_home) {
-                        let user_trash_location = user_trash_home.to_str().unwrap();
-                        return Err(Box::<dyn Error>::from(format!(
-                            "user directory in top directory trash '{user_trash_location}' isn't writable"
-                        )));
-                    }
+        let admin_trash_location = admin_trash.display();
+        resolution.require(
+            &admin_trash,
+            "exists",
+            admin_trash.try_exists().unwrap_or(false),
+            format!("top directory trash '{admin_trash_location}' does not exist"),
+        )?;
+
+        // If this directory is present, the implementation MUST,
+        // by default, check for the “sticky bit”.
+        // todo: provide superusers to disable this check to
+        // support filesystems that don't support sticky bit.
+        //
+        // The implementation also MUST check that this directory
+        // is not a symbolic link.
+
+        // test if user can write to this dir
+        resolution.require(
+            &admin_trash,
+            "writable",
+            is_writable_dir(&admin_trash),
+            format!("top directory trash '{admin_trash_location}' isn't writable"),
+        )?;
+
+        // check if sticky bit is set and is not a symlink
+        let mode = admin_trash.metadata()?.st_mode();
+        resolution.require(
+            &admin_trash,
+            "sticky bit set",
+            mode & libc::S_ISVTX == libc::S_ISVTX,
+            format!("top directory trash '{admin_trash_location}' doesn't have the sticky bit set"),
+        )?;
+        resolution.require(
+            &admin_trash,
+            "not a symlink",
+            !admin_trash.is_symlink(),
+            format!("top directory trash '{admin_trash_location}' is a symlink"),
+        )?;
+
+        // topdir approach 1
+        //
+        // if this directory does not exist for the current user, the
+        // implementation MUST immediately create it, without any
+        // warnings or delays for the user.
+        //
+        // $topdir/.Trash/$uid
+        let user_trash_home = admin_trash.join(euid.to_string());
+        Self::check_topdir_user_trash(&user_trash_home, creation, resolution)?;
 
-                    Ok(user_trash_home)
-                } else {
-                    Err(Box::<dyn Error>::from(format!(
-                        "top directory trash '{admin_trash_location}' is a symlink or sticky bit not set",
-                    )))
-                }
-            }
-            _ => Err(Box::<dyn Error>::from(format!(
-                "top directory trash '{admin_trash_location}' does not exist"
-            ))),
-        }
+        Ok(user_trash_home)
     }
 
     pub fn topdir_user_trash_exists_for(
         top_dir: &Path,
         euid: libc::uid_t,
     ) -> Result<PathBuf, Box<dyn Error>> {
-        TrashDirectory::try_topdir_user_trash_for(top_dir, euid, false)
+        TrashDirectory::try_topdir_user_trash_for(
+            top_dir,
+            euid,
+            DirCreation::MustExist,
+            &mut TrashRootResolution::default(),
+        )
     }
 
     pub fn try_topdir_user_trash_for(
         top_dir: &Path,
         euid: libc::uid_t,
-        create_if_not_exist: bool,
+        creation: DirCreation,
+        resolution: &mut TrashRootResolution,
     ) -> Result<PathBuf, Box<dyn Error>> {
         // topdir approach 2
         //
@@ -598,23 +789,109 @@ impl TrashDirectory {
         // $topdir/.Trash-uid
         let user_trash_name = format!(".Trash-{}", euid);
         let user_trash_home = top_dir.join(user_trash_name);
-        if create_if_not_exist {
-            must_have_dir(&user_trash_home)?;
-        } else if !user_trash_home.try_exists().unwrap_or(false) {
-            return Err(Box::<dyn Error>::from(format!(
-                "user directory in top directory trash '{}' isn't writable",
-                user_trash_home.display(),
-            )));
-        }
+        Self::check_topdir_user_trash(&user_trash_home, creation, resolution)?;
 
-        if !is_writable_dir(&user_trash_home) {
-            let user_trash_location = user_trash_home.to_str().unwrap();
-            return Err(Box::<dyn Error>::from(format!(
-                "user directory in top directory trash '{user_trash_location}' isn't writable"
-            )));
+        Ok(user_trash_home)
+    }
+
+    // the user's own directory of a top directory trash, $topdir/.Trash/$uid
+    // or $topdir/.Trash-$uid, has to be there or be made, and be writable
+    fn check_topdir_user_trash(
+        user_trash_home: &PathBuf,
+        creation: DirCreation,
+        resolution: &mut TrashRootResolution,
+    ) -> Result<(), Box<dyn Error>> {
+        let user_trash_location = user_trash_home.display();
+        let exists = if creation == DirCreation::MustExist
+            && !user_trash_home.try_exists().unwrap_or(false)
+        {
+            Err(Box::<dyn Error>::from(format!(
+                "user directory in top directory trash '{user_trash_location}' does not exist"
+            )))
+        } else {
+            ensure_dir(user_trash_home, creation)
+        };
+        resolution.check(user_trash_home, creation.get_check_name(), exists)?;
+
+        // a directory yet to be created in a dry run was already checked
+        // for by ensure_dir
+        if user_trash_home.exists() {
+            resolution.require(
+                user_trash_home,
+                "writable",
+                is_writable_dir(user_trash_home),
+                format!(
+                    "user directory in top directory trash '{user_trash_location}' isn't writable"
+                ),
+            )?;
         }
 
-        Ok(user_trash_home)
+        Ok(())
+    }
+}
+
+// a single check made on a trash directory candidate
+pub struct TrashDirCheck {
+    pub path: PathBuf,
+    pub name: &'static str,
+    pub error: Option<String>, // why it failed, None if it passed
+}
+
+// the problems found in a trash directory
+#[derive(Default)]
+pub struct TrashRootCheck {
+    pub orphaned_files: Vec<PathBuf>, // files entries without a trashinfo
+    pub orphaned_trashinfos: Vec<PathBuf>, // trashinfo files without a files entry
+    pub invalid_trashinfos: Vec<PathBuf>, // trashinfo files that can't be read
+}
+
+impl TrashRootCheck {
+    pub fn is_ok(&self) -> bool {
+        self.orphaned_files.is_empty()
+            && self.orphaned_trashinfos.is_empty()
+            && self.invalid_trashinfos.is_empty()
+    }
+}
+
+// the steps taken to resolve the trash directory for a file, filled in by
+// the resolver as it goes
+#[derive(Default)]
+pub struct TrashRootResolution {
+    pub file_device: Option<Device>,
+    pub xdg_data_home: Option<PathBuf>,
+    pub xdg_data_home_device: Option<Device>,
+    pub checks: Vec<TrashDirCheck>, // in the order they were made
+}
+
+impl TrashRootResolution {
+    // record the result of a check, and pass it on
+    fn check(
+        &mut self,
+        path: &Path,
+        name: &'static str,
+        result: Result<(), Box<dyn Error>>,
+    ) -> Result<(), Box<dyn Error>> {
+        self.checks.push(TrashDirCheck {
+            path: path.to_path_buf(),
+            name,
+            error: result.as_ref().err().map(|e| e.to_string()),
+        });
+        result
+    }
+
+    // record a check that either passes or fails with the given error
+    fn require(
+        &mut self,
+        path: &Path,
+        name: &'static str,
+        passed: bool,
+        error: String,
+    ) -> Result<(), Box<dyn Error>> {
+        let result = match passed {
+            true => Ok(()),
+            false => Err(Box::<dyn Error>::from(error)),
+        };
+        self.check(path, name, result)
     }
 }
 
@@ -626,11 +903,11 @@ pub struct TrashInfo {
 }
 
 impl TrashInfo {
-    pub fn new(trashinfo: PathBuf, original_path: &str, deletion_date: DateTime<Local>) -> Self {
+    pub fn new(trashinfo: PathBuf, original_path: &Path, deletion_date: DateTime<Local>) -> Self {
         // SHOULD store the file name as the sequence of bytes
         // produced by the file system, with characters escaped as in
         // URLs (as defined by RFC 2396, section 2)
-        let file_path_encoded = &encode(original_path);
+        let file_path_encoded = &encode_binary(original_path.as_os_str().as_bytes());
 
         // are to be in the YYYY-MM-DDThh:mm:ss format (see RFC 3339).
         // The time zone should be the user's (or filesystem's) local time
@@ -652,10 +929,11 @@ impl TrashInfo {
     }
 
     pub fn from(path: &PathBuf) -> Result<Self, Box<dyn Error>> {
-        let trashinfo_content = read_to_string(path).expect("couldn't read trashinfo entry");
+        let trashinfo_content = read_to_string(path)?;
         let lines: Vec<&str> = trashinfo_content.split("\n").collect();
 
-        if lines[0].trim() != "[Trash Info]"
+        if lines.len() < 3
+            || lines[0].trim() != "[Trash Info]"
             || !lines[1].starts_with("Path=")
             || !lines[2].starts_with("DeletionDate=")
         {
@@ -664,6 +942,11 @@ impl TrashInfo {
 
         let original_path = &lines[1]["Path=".len()..];
         let deletion_date = &lines[2]["DeletionDate=".len()..];
+        // checked once here, so that the getters can't fail on a bad entry
+        if original_path.is_empty() {
+            return Err(Box::<dyn Error>::from("trashinfo entry has an empty Path"));
+        }
+        Self::parse_deletion_date(deletion_date)?;
 
         Ok(TrashInfo {
             original_path: original_path.to_string(),
@@ -673,7 +956,10 @@ impl TrashInfo {
     }
 
     pub fn get_original_path(&self) -> PathBuf {
-        PathBuf::from(decode(&self.original_path).expect("utf-8").into_owned())
+        // the bytes of the path, which don't have to be utf-8
+        PathBuf::from(OsString::from_vec(
+            decode_binary(self.original_path.as_bytes()).into_owned(),
+        ))
     }
 
     pub fn create_file(&self) -> Result<&PathBuf, Box<dyn Error>> {
@@ -714,29 +1000,44 @@ DeletionDate={}
         Ok(&self.path)
     }
 
+    // the entries read with from() and made with new() always have a valid
+    // date, only a hand made one falls back to the epoch
     pub fn get_deletion_date(&self) -> DateTime<Local> {
+        Self::parse_deletion_date(&self.deletion_date)
+            .unwrap_or_else(|_| DateTime::<Local>::from(SystemTime::UNIX_EPOCH))
+    }
+
+    // YYYY-MM-DDThh:mm:ss in the local time zone
+    fn parse_deletion_date(deletion_date: &str) -> Result<DateTime<Local>, Box<dyn Error>> {
         // sometimes deletion date has tz info because of a bug from a previous commit
         // drop everything after + or Z
-        let mut deletion_date = self.deletion_date.clone();
+        let mut trimmed = deletion_date.to_string();
         for offset_char in ["+", "z", "Z"] {
-            let tz_offset = deletion_date
-                .find(offset_char)
-                .unwrap_or(deletion_date.len());
-            deletion_date.replace_range(tz_offset.., "");
+            let tz_offset = trimmed.find(offset_char).unwrap_or(trimmed.len());
+            trimmed.replace_range(tz_offset.., "");
         }
 
-        // assume user/machine local tz
-        let now = Local::now();
-        let diff_mins: i32 = now.offset().local_minus_utc() / 60;
-        let diff_hours: i32 = diff_mins / 60;
-        let diff_mins_remaining = diff_mins % 60;
-        let deletion_datetime = format!("{deletion_date}+{diff_hours:02}:{diff_mins_remaining:02}");
-        // println!("diff_mins: {diff_mins}, diff_hours: {diff_hours}, diff_mins_rem: {diff_mins_remaining}, date: {deletion_datetime}");
+        let naive = match NaiveDateTime::parse_from_str(&trimmed, "%Y-%m-%dT%H:%M:%S%.f") {
+            Ok(v) => v,
+            Err(e) => {
+                return Err(Box::<dyn Error>::from(format!(
+                    "invalid DeletionDate '{deletion_date}': {e}"
+                )));
+            }
+        };
 
-        deletion_datetime.parse().unwrap()
+        // assume user/machine local tz, the earlier of the two in a DST
+        // overlap
+        match Local.from_local_datetime(&naive).earliest() {
+            Some(v) => Ok(v),
+            None => Err(Box::<dyn Error>::from(format!(
+                "invalid DeletionDate '{deletion_date}': not a local time"
+            ))),
+        }
     }
 }
 
+#[derive(Clone)]
 pub struct TrashFile {
     pub original_file: PathBuf,
     pub files_entry: Option<PathBuf>,
@@ -767,16 +1068,24 @@ impl TrashFile {
         trash_file: PathBuf,
         trash_dir: &TrashDirectory,
     ) -> Result<TrashFile, Box<dyn Error>> {
-        let trashinfo_path = trash_dir.info.join(format!(
-            "{}.trashinfo",
-            trash_file.file_name().unwrap().to_str().unwrap()
-        ));
+        let mut trashinfo_name = match trash_file.file_name() {
+            Some(v) => v.to_os_string(),
+            None => return Err(Box::<dyn Error>::from("invalid trash file path")),
+        };
+        trashinfo_name.push(".trashinfo");
+        let trashinfo_path = trash_dir.info.join(trashinfo_name);
         if !trashinfo_path.is_file() {
             return Err(Box::<dyn Error>::from("trash file has no trashinfo entry"));
         }
 
         let trashinfo = TrashInfo::from(&trashinfo_path)?;
-        let original_file = trashinfo.get_original_path();
+
+        // topdir entries are relative to the top directory of the mount
+        let original_file = match trash_dir.get_top_dir() {
+            Some(top_dir) => top_dir.join(trashinfo.get_original_path()),
+            None => trashinfo.get_original_path(),
+        };
+
         let trash_entry = TrashFile {
             original_file,
             files_entry: Some(trash_file),
@@ -787,6 +1096,47 @@ impl TrashFile {
         Ok(trash_entry)
     }
 
+    // from an existing trashinfo entry, deriving the trash directory it
+    // belongs to from its location
+    pub fn from_trashinfo(trashinfo_path: &Path) -> Result<TrashFile, Box<dyn Error>> {
+        let trashinfo_name = match trashinfo_path.file_name() {
+            Some(v) => v.as_bytes(),
+            None => return Err(Box::<dyn Error>::from("invalid trashinfo entry path")),
+        };
+        let files_entry_name = match trashinfo_name.strip_suffix(b".trashinfo") {
+            Some(v) => OsStr::from_bytes(v),
+            None => return Err(Box::<dyn Error>::from("not a trashinfo entry")),
+        };
+
+        let trash_home = match trashinfo_path.parent().and_then(|p| p.parent()) {
+            Some(v) => v.to_path_buf(),
+            None => return Err(Box::<dyn Error>::from("invalid trashinfo entry path")),
+        };
+
+        // $topdir/.Trash-$uid, $topdir/.Trash/$uid, or the home trash
+        let trash_home_name = trash_home.file_name().and_then(|n| n.to_str());
+        let trash_home_parent_name = trash_home
+            .parent()
+            .and_then(|p| p.file_name())
+            .and_then(|n| n.to_str());
+        let root_type = if trash_home_name.is_some_and(|n| n.starts_with(".Trash-")) {
+            TrashRootType::TopDirUser
+        } else if trash_home_parent_name == Some(".Trash") {
+            TrashRootType::TopDirAdmin
+        } else {
+            TrashRootType::Home
+        };
+
+        let device = Device::for_path(&trash_home)?;
+        let trash_dir = TrashDirectory::from(trash_home, device, root_type)?;
+        let files_entry = trash_dir.files.join(files_entry_name);
+        if files_entry.symlink_metadata().is_err() {
+            return Err(Box::<dyn Error>::from("trashinfo entry has no trash file"));
+        }
+
+        TrashFile::from(files_entry, &trash_dir)
+    }
+
     pub fn create_trashinfo(&self) -> Result<&PathBuf, Box<dyn Error>> {
         if self.files_entry.is_none() || self.trashinfo.is_none() {
             return Err(Box::<dyn Error>::from("trash entries are uninitialised"));
@@ -832,6 +1182,196 @@ impl TrashFile {
         Ok(&self.original_file)
     }
 
+    // restore a file or a directory from inside a trashed directory, to the
+    // same relative path under the original path. the directory stays in the
+    // trash with what's left in it
+    pub fn restore_child(&self, relative_path: &Path) -> Result<PathBuf, Box<dyn Error>> {
+        if self.files_entry.is_none() || self.trashinfo.is_none() {
+            return Err(Box::<dyn Error>::from("trash entries are uninitialised"));
+        }
+
+        let files_entry = self.files_entry.as_ref().unwrap();
+        if files_entry.is_symlink() || !files_entry.is_dir() {
+            return Err(Box::<dyn Error>::from("trash file is not a directory"));
+        }
+
+        // only paths inside the directory
+        if relative_path.as_os_str().is_empty()
+            || !relative_path
+                .components()
+                .all(|c| matches!(c, Component::Normal(_)))
+        {
+            return Err(Box::<dyn Error>::from(format!(
+                "invalid path inside the directory: {}",
+                relative_path.display()
+            )));
+        }
+
+        let child = files_entry.join(relative_path);
+        if child.symlink_metadata().is_err() {
+            return Err(Box::<dyn Error>::from(format!(
+                "{} does not exist in the trash",
+                relative_path.display()
+            )));
+        }
+
+        let target = self.original_file.join(relative_path);
+        if target.symlink_metadata().is_ok() {
+            return Err(Box::<dyn Error>::from(format!(
+                "'{}' exists",
+                target.display()
+            )));
+        }
+
+        if let Some(parent) = target.parent() {
+            create_dir_all(parent)?;
+        }
+        rename(&child, &target)?;
+
+        // the size of the directory changed, doesn't matter if this fails
+        let _ = self.trashroot.add_dirsizes_entry(self);
+
+        Ok(target)
+    }
+
+    // restore to another path than the original one, in an existing
+    // directory. across filesystems the trashed file is copied, and only
+    // removed from the trash once the copy is complete
+    pub fn restore_to(&self, target: &Path) -> Result<PathBuf, Box<dyn Error>> {
+        if self.files_entry.is_none() || self.trashinfo.is_none() {
+            return Err(Box::<dyn Error>::from("trash entries are uninitialised"));
+        }
+
+        if !target.is_absolute() {
+            return Err(Box::<dyn Error>::from(format!(
+                "'{}' is not an absolute path",
+                target.display()
+            )));
+        }
+        if target.symlink_metadata().is_ok() {
+            return Err(Box::<dyn Error>::from(format!(
+                "'{}' exists",
+                target.display()
+            )));
+        }
+        match target.parent() {
+            Some(v) if v.is_dir() => {}
+            Some(v) => {
+                return Err(Box::<dyn Error>::from(format!(
+                    "'{}' is not a directory",
+                    v.display()
+                )))
+            }
+            None => {
+                return Err(Box::<dyn Error>::from(
+                    "cannot restore to the root directory",
+                ))
+            }
+        }
+
+        let files_entry = self.files_entry.as_ref().unwrap();
+        let is_dir = !files_entry.is_symlink() && files_entry.is_dir();
+
+        move_all(files_entry, target)?;
+        remove_file(&self.trashinfo.as_ref().unwrap().path)?;
+
+        if is_dir {
+            // doesn't matter if this fails
+            let _ = self.trashroot.cleanup_dirsizes();
+        }
+
+        Ok(target.to_path_buf())
+    }
+
+    // undo a restore, moving the restored file back into the same trash entry
+    // with the same trashinfo contents, the deletion date included. across
+    // filesystems it's copied, as with restore_to
+    pub fn retrash(&self, restored: &Path) -> Result<&PathBuf, Box<dyn Error>> {
+        if self.files_entry.is_none() || self.trashinfo.is_none() {
+            return Err(Box::<dyn Error>::from("trash entries are uninitialised"));
+        }
+
+        let files_entry = self.files_entry.as_ref().unwrap();
+        if restored.symlink_metadata().is_err() {
+            return Err(Box::<dyn Error>::from(format!(
+                "'{}' doesn't exist",
+                restored.display()
+            )));
+        }
+        if files_entry.symlink_metadata().is_ok() {
+            return Err(Box::<dyn Error>::from(format!(
+                "'{}' exists in the trash",
+                files_entry.display()
+            )));
+        }
+
+        // the trashinfo first, as when trashing, it fails if the name was
+        // taken since
+        self.create_trashinfo()?;
+        if let Err(e) = move_all(restored, files_entry) {
+            let _ = remove_file(&self.trashinfo.as_ref().unwrap().path);
+            return Err(e);
+        }
+
+        let is_dir = !files_entry.is_symlink() && files_entry.is_dir();
+        if is_dir {
+            // doesn't matter if this fails
+            let _ = self.trashroot.add_dirsizes_entry(self);
+        }
+
+        Ok(files_entry)
+    }
+
+    // undo restore_child, moving the restored file back to the same path
+    // inside the trashed directory
+    pub fn retrash_child(&self, relative_path: &Path) -> Result<PathBuf, Box<dyn Error>> {
+        if self.files_entry.is_none() || self.trashinfo.is_none() {
+            return Err(Box::<dyn Error>::from("trash entries are uninitialised"));
+        }
+
+        let files_entry = self.files_entry.as_ref().unwrap();
+        if files_entry.is_symlink() || !files_entry.is_dir() {
+            return Err(Box::<dyn Error>::from("trash file is not a directory"));
+        }
+
+        if relative_path.as_os_str().is_empty()
+            || !relative_path
+                .components()
+                .all(|c| matches!(c, Component::Normal(_)))
+        {
+            return Err(Box::<dyn Error>::from(format!(
+                "invalid path inside the directory: {}",
+                relative_path.display()
+            )));
+        }
+
+        let restored = self.original_file.join(relative_path);
+        if restored.symlink_metadata().is_err() {
+            return Err(Box::<dyn Error>::from(format!(
+                "'{}' doesn't exist",
+                restored.display()
+            )));
+        }
+
+        let child = files_entry.join(relative_path);
+        if child.symlink_metadata().is_ok() {
+            return Err(Box::<dyn Error>::from(format!(
+                "{} exists in the trash",
+                relative_path.display()
+            )));
+        }
+
+        if let Some(parent) = child.parent() {
+            create_dir_all(parent)?;
+        }
+        rename(&restored, &child)?;
+
+        // the size of the directory changed, doesn't matter if this fails
+        let _ = self.trashroot.add_dirsizes_entry(self);
+
+        Ok(child)
+    }
+
     pub fn delete_forever(&self) -> Result<(), Box<dyn Error>> {
         if self.files_entry.is_none() || self.trashinfo.is_none() {
             return Err(Box::<dyn Error>::from("trash entries are uninitialised"));
@@ -862,28 +1402,147 @@ impl TrashFile {
             return Err(Box::<dyn Error>::from("trash entries are uninitialised"));
         }
 
-        let size = if self.files_entry.as_ref().unwrap().is_symlink() {
-            self.files_entry
-                .as_ref()
-                .unwrap()
-                .symlink_metadata()
-                .unwrap()
-                .st_size()
-        } else if self.files_entry.as_ref().unwrap().is_dir() {
-            get_dir_size(self.files_entry.as_ref().unwrap())?
+        // gone from the trash since it was listed is an error, not a panic
+        let files_entry = self.files_entry.as_ref().unwrap();
+        let size = if files_entry.is_symlink() {
+            files_entry.symlink_metadata()?.st_size()
+        } else if files_entry.is_dir() {
+            get_dir_size(files_entry)?
         } else {
-            self.files_entry
-                .as_ref()
-                .unwrap()
-                .metadata()
-                .unwrap()
-                .st_size()
+            files_entry.metadata()?.st_size()
         };
 
         Ok(size)
     }
 }
 
+// the trashinfo entries created by a single trash invocation
+//
+// each operation is journaled in its own file under
+// $XDG_STATE_HOME/trash-rs/journal, named after the operation id, with one
+// url encoded trashinfo path per line. ids sort in the order the operations
+// were made, so the last operation can be found without reading every file.
+pub struct TrashOperation {
+    pub id: String,
+    pub trashinfo_paths: Vec<PathBuf>,
+    journal: PathBuf,
+}
+
+impl TrashOperation {
+    // a new, empty operation. Nothing is written until an entry is recorded
+    pub fn new() -> Result<Self, Box<dyn Error>> {
+        let now = Local::now().format("%Y%m%d%H%M%S%3f");
+        let id = format!("{now}-{}", std::process::id());
+        let journal = Self::get_journal_dir()?.join(&id);
+
+        Ok(TrashOperation {
+            id,
+            trashinfo_paths: vec![],
+            journal,
+        })
+    }
+
+    // load a previously journaled operation
+    pub fn load(id: &str) -> Result<Self, Box<dyn Error>> {
+        if id.is_empty() || id.contains(MAIN_SEPARATOR_STR) || id.starts_with(".") {
+            return Err(Box::<dyn Error>::from(format!(
+                "invalid operation id '{id}'"
+            )));
+        }
+
+        let journal = Self::get_journal_dir()?.join(id);
+        if !journal.is_file() {
+            return Err(Box::<dyn Error>::from(format!("no such operation '{id}'")));
+        }
+
+        let mut trashinfo_paths: Vec<PathBuf> = vec![];
+        for line in read_to_string(&journal)?.lines() {
+            if line.is_empty() {
+                continue;
+            }
+
+            let path = decode_binary(line.as_bytes()).into_owned();
+            trashinfo_paths.push(PathBuf::from(OsString::from_vec(path)));
+        }
+
+        Ok(TrashOperation {
+            id: id.to_string(),
+            trashinfo_paths,
+            journal,
+        })
+    }
+
+    // the most recent journaled operation, if any
+    pub fn last() -> Result<Option<Self>, Box<dyn Error>> {
+        let journal_dir = Self::get_journal_dir()?;
+        if !journal_dir.is_dir() {
+            return Ok(None);
+        }
+
+        let mut ids: Vec<String> = vec![];
+        for child in read_dir(journal_dir)? {
+            let child = child?;
+            if let Some(name) = child.file_name().to_str() {
+                ids.push(name.to_string());
+            }
+        }
+
+        match ids.iter().max() {
+            Some(id) => Ok(Some(Self::load(id)?)),
+            None => Ok(None),
+        }
+    }
+
+    // append a trashed file to the journal
+    pub fn record(&mut self, trash_file: &TrashFile) -> Result<(), Box<dyn Error>> {
+        let trashinfo_path = match &trash_file.trashinfo {
+            Some(v) => v.path.clone(),
+            None => {
+                return Err(Box::<dyn Error>::from(
+                    "attempt to journal an incomplete trash operation",
+                ))
+            }
+        };
+
+        must_have_dir(&Self::get_journal_dir()?)?;
+        let mut f = OpenOptions::new()
+            .append(true)
+            .create(true)
+            .open(&self.journal)?;
+        let encoded = encode_binary(trashinfo_path.as_os_str().as_bytes());
+        f.write_all(format!("{encoded}\n").as_bytes())?;
+
+        self.trashinfo_paths.push(trashinfo_path);
+        Ok(())
+    }
+
+    // rewrite the journal with the given entries, removing it if none are left
+    pub fn save(&mut self, trashinfo_paths: Vec<PathBuf>) -> Result<(), Box<dyn Error>> {
+        self.trashinfo_paths = trashinfo_paths;
+        if self.trashinfo_paths.is_empty() {
+            if self.journal.exists() {
+                remove_file(&self.journal)?;
+            }
+
+            return Ok(());
+        }
+
+        let mut content = String::new();
+        for path in &self.trashinfo_paths {
+            content += &format!("{}\n", encode_binary(path.as_os_str().as_bytes()));
+        }
+
+        let mut f = File::create(&self.journal)?;
+        f.write_all(content.as_bytes())?;
+        Ok(())
+    }
+
+    fn get_journal_dir() -> Result<PathBuf, Box<dyn Error>> {
+        let binary_name = env!("CARGO_PKG_NAME");
+        Ok(get_xdg_state_home()?.join(binary_name).join("journal"))
+    }
+}
+
 // retrieve os defined home directory. $HOME MUST be defined as of now.
 // todo: lookup passwd for home dir entry if $HOME isn't defined
 pub fn get_home_dir() -> Result<PathBuf, Box<dyn Error>> {
@@ -909,14 +1568,92 @@ pub fn get_xdg_data_home() -> Result<PathBuf, Box<dyn Error>> {
     Ok(xdg_data_home)
 }
 
+// retrieve XDG_STATE_HOME value, from env var or falling back to spec default
+pub fn get_xdg_state_home() -> Result<PathBuf, Box<dyn Error>> {
+    // if XDG_STATE_HOME is not defined, fallback to $HOME/.local/state
+    let xdg_state_home = match env::var("XDG_STATE_HOME") {
+        Ok(v) => PathBuf::from(&v),
+        Err(_) => {
+            let home_dir = get_home_dir()
+                .map_err(|_| Box::<dyn Error>::from("couldn't retrieve home directory location"));
+
+            home_dir?.join(".local").join("state")
+        }
+    };
+
+    Ok(xdg_state_home)
+}
+
+// rename, or across filesystems copy and then remove the source. a partial
+// copy isn't left behind
+fn move_all(source: &Path, target: &Path) -> Result<(), Box<dyn Error>> {
+    match rename(source, target) {
+        Ok(_) => Ok(()),
+        Err(e) if e.raw_os_error() == Some(libc::EXDEV) => {
+            let is_dir = !source.is_symlink() && source.is_dir();
+            if let Err(e) = copy_all(source, target) {
+                let _ = if is_dir {
+                    remove_dir_all(target)
+                } else {
+                    remove_file(target)
+                };
+                return Err(e);
+            }
+
+            if is_dir {
+                remove_dir_all(source)?;
+            } else {
+                remove_file(source)?;
+            }
+            Ok(())
+        }
+        Err(e) => Err(Box::new(e)),
+    }
+}
+
+// copy a file, a symlink, or a directory with everything in it, keeping the
+// permissions
+fn copy_all(source: &Path, target: &Path) -> Result<(), Box<dyn Error>> {
+    let metadata = source.symlink_metadata()?;
+    if metadata.is_symlink() {
+        std::os::unix::fs::symlink(read_link(source)?, target)?;
+    } else if metadata.is_dir() {
+        create_dir(target)?;
+        for entry in read_dir(source)? {
+            let entry = entry?;
+            copy_all(&entry.path(), &target.join(entry.file_name()))?;
+        }
+        set_permissions(target, metadata.permissions())?;
+    } else {
+        copy(source, target)?;
+    }
+
+    Ok(())
+}
+
+// retrieve XDG_CONFIG_HOME value, from env var or falling back to spec default
+pub fn get_xdg_config_home() -> Result<PathBuf, Box<dyn Error>> {
+    // if XDG_CONFIG_HOME is not defined, fallback to $HOME/.config
+    let xdg_config_home = match env::var("XDG_CONFIG_HOME") {
+        Ok(v) => PathBuf::from(&v),
+        Err(_) => {
+            let home_dir = get_home_dir()
+                .map_err(|_| Box::<dyn Error>::from("couldn't retrieve home directory location"));
+
+            home_dir?.join(".config")
+        }
+    };
+
+    Ok(xdg_config_home)
+}
+
 // todo: this check is done with process real uid, so sudo invocation will still fail
 // alternative is to use faccessat() with AT_EACCESS.
 // the decision here is to whether allow sudo invocation to trash a file that
 // a user doesn't have access to
 pub fn is_writable_dir(path: &Path) -> bool {
     let writable: libc::c_int;
-    let dir_location = path.to_str().unwrap();
-    let path_cstr = match CString::new(dir_location) {
+    let path_cstr = match CString::new(path.as_os_str().as_bytes()) {
         Ok(v) => v,
         Err(_) => return false,
     };
@@ -945,7 +1682,7 @@ pub fn must_have_dir(path: &PathBuf) -> Result<(), Box<dyn Error>> {
             if !path.is_dir() {
                 return Err(Box::<dyn Error>::from(format!(
                     "path exists but is not a directory: {}",
-                    path.to_str().unwrap()
+                    path.display()
                 )));
             }
         }
@@ -953,7 +1690,7 @@ pub fn must_have_dir(path: &PathBuf) -> Result<(), Box<dyn Error>> {
             return create_dir_all(path).map_err(|e| {
                 Box::<dyn Error>::from(format!(
                     "cannot create directory: {}, {}",
-                    path.to_str().unwrap(),
+                    path.display(),
                     e,
                 ))
             });
@@ -961,7 +1698,7 @@ pub fn must_have_dir(path: &PathBuf) -> Result<(), Box<dyn Error>> {
         Err(_) => {
             return Err(Box::<dyn Error>::from(format!(
                 "cannot verify directory exists: {}",
-                path.to_str().unwrap()
+                path.display()
             )));
         }
     };
@@ -969,6 +1706,54 @@ pub fn must_have_dir(path: &PathBuf) -> Result<(), Box<dyn Error>> {
     Ok(())
 }
 
+// make sure the specified path exists as a directory, or could be created
+// if needed, depending on how missing directories are to be handled.
+// MustExist does not fail for a missing directory here, since callers word
+// that error differently
+pub fn ensure_dir(path: &PathBuf, creation: DirCreation) -> Result<(), Box<dyn Error>> {
+    match creation {
+        DirCreation::Create => must_have_dir(path),
+        DirCreation::MustExist => Ok(()),
+        DirCreation::DryRun => could_have_dir(path),
+    }
+}
+
+// dry run counterpart of must_have_dir. Checks that the specified path is
+// a directory, or that it could be created under its closest existing parent
+// if it doesn't exist. Nothing is created.
+pub fn could_have_dir(path: &Path) -> Result<(), Box<dyn Error>> {
+    let closest = get_closest_existing_path(path);
+    if !closest.is_dir() {
+        return Err(Box::<dyn Error>::from(format!(
+            "path exists but is not a directory: {}",
+            closest.display()
+        )));
+    }
+
+    if closest != path && !is_writable_dir(&closest) {
+        return Err(Box::<dyn Error>::from(format!(
+            "cannot create directory: {}, {} isn't writable",
+            path.display(),
+            closest.display(),
+        )));
+    }
+
+    Ok(())
+}
+
+// the path itself if it exists, otherwise its closest existing ancestor
+pub fn get_closest_existing_path(path: &Path) -> PathBuf {
+    let mut closest = path;
+    while !closest.try_exists().unwrap_or(false) {
+        match closest.parent() {
+            Some(v) => closest = v,
+            None => break,
+        }
+    }
+
+    closest.to_path_buf()
+}
+
 // returns a PathBuf of a relative path of child against parent
 pub fn get_path_relative_to(child: &Path, parent: &PathBuf) -> Result<PathBuf, Box<dyn Error>> {
     if !child.is_absolute() || !parent.is_absolute() {
@@ -994,8 +1779,7 @@ pub fn can_delete_file(abs_file_path: &Path) -> bool {
 
     // 1. can read and modify?
     let file_writable: libc::c_int;
-    let location = abs_file_path.to_str().unwrap();
-    let path_cstr = match CString::new(location) {
+    let path_cstr = match CString::new(abs_file_path.as_os_str().as_bytes()) {
         Ok(v) => v,
         Err(_) => return false,
     };
@@ -1057,7 +1841,10 @@ impl Device {
     const PROCINFO_FIELD_MAJORMINOR: usize = 2;
     const PROCINFO_FIELD_MOUNT_ROOT: usize = 3;
     const PROCINFO_FIELD_MOUNT_POINT: usize = 4;
-    const PROCINFO_FIELD_DEV_NAME: usize = 9;
+    // the optional fields before the separator vary in count, so the mount
+    // source is found relative to the separator: - fstype source
+    const PROCINFO_SEPARATOR: &'static str = "-";
+    const PROCINFO_FIELD_DEV_NAME_AFTER_SEPARATOR: usize = 2;
 
     // does not traverse symlinks
     pub fn for_path(abs_file_path: &Path) -> Result<Device, Box<dyn Error>> {
@@ -1070,6 +1857,18 @@ impl Device {
         })
     }
 
+    pub fn get_dev_name(&self) -> Option<&String> {
+        self.dev_name.as_ref()
+    }
+
+    pub fn get_mount_root(&self) -> Option<&PathBuf> {
+        self.mount_root.as_ref()
+    }
+
+    pub fn get_mount_point(&self) -> Option<&PathBuf> {
+        self.mount_point.as_ref()
+    }
+
     pub fn resolve_mount(&mut self) -> Result<(), Box<dyn Error>> {
         let mountinfo = read_to_string("/proc/self/mountinfo").unwrap();
         let mounts: Vec<&str> = mountinfo.lines().collect();
@@ -1078,7 +1877,11 @@ impl Device {
             if fields[Self::PROCINFO_FIELD_MAJORMINOR]
                 == format!("{}:{}", self.dev_num.major, self.dev_num.minor)
             {
-                self.dev_name = Some(fields[Self::PROCINFO_FIELD_DEV_NAME].to_string());
+                self.dev_name = fields
+                    .iter()
+                    .position(|f| *f == Self::PROCINFO_SEPARATOR)
+                    .and_then(|i| fields.get(i + Self::PROCINFO_FIELD_DEV_NAME_AFTER_SEPARATOR))
+                    .map(|f| f.to_string());
                 self.mount_root = Some(PathBuf::from(
                     fields[Self::PROCINFO_FIELD_MOUNT_ROOT].to_string(),
                 ));
@@ -1104,6 +1907,14 @@ pub struct DeviceNumber {
 }
 
 impl DeviceNumber {
+    pub fn get_major(&self) -> u32 {
+        self.major
+    }
+
+    pub fn get_minor(&self) -> u32 {
+        self.minor
+    }
+
     // does not traverse symlinks
     // latest device drivers ref - Ch3
     // Within the kernel, the dev_t type (defined in <linux/types.h>) is used to hold device
@@ -1147,16 +1958,14 @@ pub fn to_abs_path(path: impl AsRef<Path>) -> Result<PathBuf, Box<dyn Error>> {
     let abs_path = if path.is_absolute() {
         path.to_path_buf()
     } else {
-        // ex: if starts with ./, remove it because that looks ugly
-        let rel_indicator = format!(".{MAIN_SEPARATOR_STR}");
-        let trimmed_path = if path.starts_with(&rel_indicator) {
-            let t = path.display().to_string();
-            &PathBuf::from(t.strip_prefix(&rel_indicator).unwrap())
+        // ex: if starts with ./, remove it because that looks ugly. . and ./
+        // alone are the current dir itself, without a trailing /
+        let trimmed_path = path.strip_prefix(".").unwrap_or(path);
+        if trimmed_path.as_os_str().is_empty() {
+            env::current_dir()?
         } else {
-            path
-        };
-
-        env::current_dir()?.join(trimmed_path)
+            env::current_dir()?.join(trimmed_path)
+        }
     };
 
     Ok(abs_path)
@@ -1182,18 +1991,44 @@ where
 mod tests {
     use std::fs::create_dir_all;
     use std::fs::remove_dir_all;
+    use std::fs::write;
     use std::process::Command;
 
     use super::*;
 
-    #[test]
-    fn test_get_dir_size() {
-        let temp_dir = env::temp_dir();
+    // a directory of its own for a test, under the temp dir
+    fn get_test_dir() -> PathBuf {
         let time_now = match SystemTime::now().duration_since(SystemTime::UNIX_EPOCH) {
-            Ok(v) => v.as_secs(),
+            Ok(v) => v.as_nanos(),
             Err(_) => panic!("prepare for war"),
         };
-        let temp_test_dir = temp_dir.join("trash-rs").join(format!("{}", time_now));
+        let test_dir = env::temp_dir()
+            .join("trash-rs")
+            .join(format!("{time_now}-{}", std::process::id()));
+        let _ = create_dir_all(&test_dir);
+        test_dir
+    }
+
+    // a home trash directory with its files and info directories, in a test
+    // directory of its own to remove when done
+    fn get_test_trash_dir() -> (TrashDirectory, PathBuf) {
+        let test_dir = get_test_dir();
+        let home = test_dir.join("Trash");
+        let trash_dir = TrashDirectory {
+            device: Device::for_path(&env::temp_dir()).unwrap(),
+            files: home.join("files"),
+            info: home.join("info"),
+            home,
+            root_type: TrashRootType::Home,
+        };
+        let _ = create_dir_all(&trash_dir.files);
+        let _ = create_dir_all(&trash_dir.info);
+        (trash_dir, test_dir)
+    }
+
+    #[test]
+    fn test_get_dir_size() {
+        let temp_test_dir = get_test_dir();
         let test_dir_1 = temp_test_dir.join("test-1");
         let test_dir_2 = temp_test_dir.join("test-2");
         let _ = create_dir_all(test_dir_1.clone());
@@ -1220,4 +2055,275 @@ mod tests {
 
         let _ = remove_dir_all(temp_test_dir);
     }
+
+    #[test]
+    fn test_get_trashed_files_and_errors() {
+        let (trash_dir, temp_test_dir) = get_test_trash_dir();
+
+        // valid, orphaned, and truncated entries
+        let _ = File::create(trash_dir.files.join("valid"));
+        let _ = write(
+            trash_dir.info.join("valid.trashinfo"),
+            "[Trash Info]\nPath=/tmp/valid\nDeletionDate=2024-01-01T10:00:00\n",
+        );
+        let _ = File::create(trash_dir.files.join("orphaned"));
+        let _ = File::create(trash_dir.files.join("truncated"));
+        let _ = write(trash_dir.info.join("truncated.trashinfo"), "[Trash Info]\n");
+
+        let (files, errors) = trash_dir.get_trashed_files_and_errors().unwrap();
+        assert!(files.len() == 1);
+        assert!(files[0].original_file == Path::new("/tmp/valid"));
+        assert!(errors.len() == 2);
+        assert!(trash_dir.get_trashed_files().is_err());
+
+        let _ = remove_dir_all(temp_test_dir);
+    }
+
+    #[test]
+    fn test_check() {
+        let (trash_dir, temp_test_dir) = get_test_trash_dir();
+
+        let _ = File::create(trash_dir.files.join("valid"));
+        let _ = write(
+            trash_dir.info.join("valid.trashinfo"),
+            "[Trash Info]\nPath=/tmp/valid\nDeletionDate=2024-01-01T10:00:00\n",
+        );
+        assert!(trash_dir.check().unwrap().is_ok());
+
+        let _ = File::create(trash_dir.files.join("no_trashinfo"));
+        let _ = write(
+            trash_dir.info.join("gone.trashinfo"),
+            "[Trash Info]\nPath=/tmp/gone\nDeletionDate=2024-01-01T10:00:00\n",
+        );
+        let _ = File::create(trash_dir.files.join("truncated"));
+        let _ = write(trash_dir.info.join("truncated.trashinfo"), "[Trash Info]\n");
+
+        let check = trash_dir.check().unwrap();
+        assert!(check.orphaned_files == vec![trash_dir.files.join("no_trashinfo")]);
+        assert!(check.orphaned_trashinfos == vec![trash_dir.info.join("gone.trashinfo")]);
+        assert!(check.invalid_trashinfos == vec![trash_dir.info.join("truncated.trashinfo")]);
+
+        trash_dir.repair(&check).unwrap();
+        let check = trash_dir.check().unwrap();
+        assert!(check.orphaned_trashinfos.is_empty());
+        assert!(check.orphaned_files.len() == 1);
+        assert!(trash_dir.files.join("no_trashinfo").exists());
+
+        let _ = remove_dir_all(temp_test_dir);
+    }
+
+    #[test]
+    fn test_restore_child() {
+        let (trash_dir, temp_test_dir) = get_test_trash_dir();
+        let _ = create_dir_all(trash_dir.files.join("dir").join("sub"));
+        let _ = write(trash_dir.files.join("dir").join("sub").join("a"), "aaaa");
+        let _ = write(trash_dir.files.join("dir").join("b"), "bb");
+        let original_dir = temp_test_dir.join("original").join("dir");
+        let _ = write(
+            trash_dir.info.join("dir.trashinfo"),
+            format!(
+                "[Trash Info]\nPath={}\nDeletionDate=2024-01-01T10:00:00\n",
+                original_dir.display()
+            ),
+        );
+        let trash_file = TrashFile::from(trash_dir.files.join("dir"), &trash_dir).unwrap();
+
+        // missing parent directories are created
+        let restored = trash_file.restore_child(Path::new("sub/a")).unwrap();
+        assert!(restored == original_dir.join("sub").join("a"));
+        assert!(read_to_string(&restored).unwrap() == "aaaa");
+        assert!(!trash_dir.files.join("dir").join("sub").join("a").exists());
+        assert!(trash_dir.info.join("dir.trashinfo").exists());
+
+        // the parent entry reflects what's left
+        let dir_sizes = read_to_string(trash_dir.home.join("directorysizes")).unwrap();
+        let dir_size = get_dir_size(&trash_dir.files.join("dir")).unwrap();
+        assert!(dir_sizes.starts_with(&format!("{dir_size} ")));
+        assert!(dir_sizes.ends_with(" dir\n"));
+
+        assert!(trash_file.restore_child(Path::new("../dir")).is_err());
+        assert!(trash_file.restore_child(Path::new("missing")).is_err());
+        let _ = write(original_dir.join("b"), "existing");
+        assert!(trash_file.restore_child(Path::new("b")).is_err());
+
+        let _ = remove_dir_all(temp_test_dir);
+    }
+
+    #[test]
+    fn test_restore_to() {
+        let (trash_dir, temp_test_dir) = get_test_trash_dir();
+        let _ = create_dir_all(trash_dir.files.join("dir").join("sub"));
+        let _ = write(trash_dir.files.join("dir").join("sub").join("a"), "aaaa");
+        let _ = std::os::unix::fs::symlink("sub/a", trash_dir.files.join("dir").join("link"));
+        let _ = write(
+            trash_dir.info.join("dir.trashinfo"),
+            "[Trash Info]\nPath=/gone/dir\nDeletionDate=2024-01-01T10:00:00\n",
+        );
+        let trash_file = TrashFile::from(trash_dir.files.join("dir"), &trash_dir).unwrap();
+
+        let elsewhere = temp_test_dir.join("elsewhere");
+        assert!(trash_file.restore_to(&elsewhere.join("dir")).is_err());
+        assert!(trash_file.restore_to(Path::new("relative")).is_err());
+        let _ = create_dir_all(&elsewhere);
+        let _ = write(elsewhere.join("taken"), "");
+        assert!(trash_file.restore_to(&elsewhere.join("taken")).is_err());
+
+        let restored = trash_file.restore_to(&elsewhere.join("renamed")).unwrap();
+        assert!(read_to_string(restored.join("sub").join("a")).unwrap() == "aaaa");
+        assert!(!trash_dir.files.join("dir").exists());
+        assert!(!trash_dir.info.join("dir.trashinfo").exists());
+
+        // what's done across filesystems
+        copy_all(&restored, &elsewhere.join("copy")).unwrap();
+        assert!(read_to_string(elsewhere.join("copy").join("sub").join("a")).unwrap() == "aaaa");
+        assert!(read_link(elsewhere.join("copy").join("link")).unwrap() == Path::new("sub/a"));
+
+        let _ = remove_dir_all(temp_test_dir);
+    }
+
+    #[test]
+    fn test_retrash() {
+        let (trash_dir, temp_test_dir) = get_test_trash_dir();
+        let _ = create_dir_all(trash_dir.files.join("dir").join("sub"));
+        let _ = write(trash_dir.files.join("dir").join("sub").join("a"), "aaaa");
+        let original_dir = temp_test_dir.join("original").join("dir");
+        let _ = create_dir_all(original_dir.parent().unwrap());
+        let trashinfo = format!(
+            "[Trash Info]\nPath={}\nDeletionDate=2024-01-01T10:00:00\n",
+            original_dir.display()
+        );
+        let _ = write(trash_dir.info.join("dir.trashinfo"), &trashinfo);
+        let trash_file = TrashFile::from(trash_dir.files.join("dir"), &trash_dir).unwrap();
+
+        // a child and back
+        let restored = trash_file.restore_child(Path::new("sub/a")).unwrap();
+        assert!(trash_file.retrash(&restored).is_err());
+        trash_file.retrash_child(Path::new("sub/a")).unwrap();
+        assert!(!restored.exists());
+        assert!(read_to_string(trash_dir.files.join("dir/sub/a")).unwrap() == "aaaa");
+        assert!(trash_file.retrash_child(Path::new("sub/a")).is_err());
+        // the directories made for the child are left
+        let _ = remove_dir_all(&original_dir);
+
+        // the whole directory, to where it was and elsewhere, the trashinfo
+        // as it was
+        trash_file.restore().unwrap();
+        trash_file.retrash(&original_dir).unwrap();
+        assert!(read_to_string(trash_dir.info.join("dir.trashinfo")).unwrap() == trashinfo);
+        assert!(read_to_string(trash_dir.files.join("dir/sub/a")).unwrap() == "aaaa");
+        assert!(trash_file.retrash(&original_dir).is_err());
+
+        let elsewhere = temp_test_dir.join("renamed");
+        trash_file.restore_to(&elsewhere).unwrap();
+        trash_file.retrash(&elsewhere).unwrap();
+        assert!(!elsewhere.exists());
+        assert!(read_to_string(trash_dir.info.join("dir.trashinfo")).unwrap() == trashinfo);
+
+        let _ = remove_dir_all(temp_test_dir);
+    }
+
+    #[test]
+    fn test_trashinfo_from() {
+        let (trash_dir, temp_test_dir) = get_test_trash_dir();
+        let trashinfo = trash_dir.info.join("a.trashinfo");
+
+        let _ = write(
+            &trashinfo,
+            "[Trash Info]\nPath=/tmp/%ff\nDeletionDate=2024-01-01T10:00:00\n",
+        );
+        let v = TrashInfo::from(&trashinfo).unwrap();
+        assert!(v.get_original_path().as_os_str().as_bytes() == b"/tmp/\xff");
+        let date = v.get_deletion_date().naive_local();
+        assert!(date.to_string() == "2024-01-01 10:00:00");
+
+        // an offset from an old version, and fractions from other
+        // implementations
+        for date in ["2024-01-01T10:00:00+05:30", "2024-01-01T10:00:00.250"] {
+            let _ = write(
+                &trashinfo,
+                format!("[Trash Info]\nPath=/tmp/a\nDeletionDate={date}\n"),
+            );
+            assert!(TrashInfo::from(&trashinfo).is_ok());
+        }
+
+        for content in [
+            "[Trash Info]\nPath=/tmp/a\nDeletionDate=yesterday\n",
+            "[Trash Info]\nPath=/tmp/a\nDeletionDate=\n",
+            "[Trash Info]\nPath=\nDeletionDate=2024-01-01T10:00:00\n",
+        ] {
+            let _ = write(&trashinfo, content);
+            assert!(TrashInfo::from(&trashinfo).is_err());
+        }
+
+        let _ = remove_dir_all(temp_test_dir);
+    }
+
+    #[test]
+    fn test_to_abs_path() {
+        let cwd = env::current_dir().unwrap();
+        assert!(to_abs_path(".").unwrap() == cwd);
+        assert!(to_abs_path("./").unwrap() == cwd);
+        assert!(to_abs_path(".").unwrap().as_os_str() == cwd.as_os_str());
+        assert!(to_abs_path("./a/b").unwrap() == cwd.join("a/b"));
+        assert!(to_abs_path("..").unwrap() == cwd.join(".."));
+        assert!(to_abs_path(".hidden").unwrap() == cwd.join(".hidden"));
+        assert!(to_abs_path("/tmp/a").unwrap() == Path::new("/tmp/a"));
+    }
+
+    #[test]
+    fn test_explain_for_file() {
+        let temp_test_dir = get_test_dir();
+        let test_file = temp_test_dir.join("test_file");
+        let _ = File::create(&test_file).expect("couldn't create test file");
+        let xdg_data_home = temp_test_dir.join("data");
+
+        // the home trash on the same device, nothing is created
+        let (resolution, trash_dir) = TrashDirectory::explain_for_file(&test_file, &xdg_data_home);
+        assert!(trash_dir.unwrap().home == xdg_data_home.join("Trash"));
+        let checked: Vec<&Path> = resolution.checks.iter().map(|c| c.path.as_path()).collect();
+        assert!(
+            checked
+                == vec![
+                    xdg_data_home.as_path(),
+                    &xdg_data_home.join("Trash"),
+                    &xdg_data_home.join("Trash").join("files"),
+                    &xdg_data_home.join("Trash").join("info"),
+                ]
+        );
+        assert!(resolution.checks.iter().all(|c| c.error.is_none()));
+        assert!(!xdg_data_home.exists());
+
+        // files/ can't be made, the resolution stops there
+        create_dir_all(xdg_data_home.join("Trash")).expect("couldn't create trash dir");
+        let _ = File::create(xdg_data_home.join("Trash").join("files"))
+            .expect("couldn't create test file");
+        let (resolution, trash_dir) = TrashDirectory::explain_for_file(&test_file, &xdg_data_home);
+        assert!(trash_dir.is_err());
+        let last_check = resolution.checks.last().unwrap();
+        assert!(last_check.path == xdg_data_home.join("Trash").join("files"));
+        assert!(last_check.error.is_some());
+        assert!(resolution.checks.len() == 3);
+
+        let _ = remove_dir_all(temp_test_dir);
+    }
+
+    #[test]
+    fn test_could_have_dir() {
+        let temp_test_dir = get_test_dir();
+
+        let missing_dir = temp_test_dir.join("Trash").join("files");
+        assert!(get_closest_existing_path(&missing_dir) == temp_test_dir);
+        assert!(could_have_dir(&missing_dir).is_ok());
+        assert!(could_have_dir(&temp_test_dir).is_ok());
+        // nothing is created
+        assert!(!temp_test_dir.join("Trash").exists());
+
+        // can't create a directory under a file
+        let test_file = temp_test_dir.join("test_file");
+        let _ = File::create(&test_file).expect("couldn't create test file");
+        assert!(could_have_dir(&test_file.join("files")).is_err());
+        assert!(could_have_dir(&test_file).is_err());
+
+        let _ = remove_dir_all(temp_test_dir);
+    }
 }